@@ -1,19 +1,76 @@
-use registers::CpuRegisters;
+use core::fmt;
+use std::fmt::Display;
 
+use interrupts::InterruptController;
+use registers::{CpuRegisters, StatusFlags};
+
+use crate::emu::scheduler::Component;
+use crate::emu::EmulationError;
 use crate::memory;
+use crate::romdata;
 
+mod addressing;
 pub(crate) mod instructions;
+mod interrupts;
+pub mod math;
+pub mod nmi;
 mod registers;
+mod timing;
 
 /**************************************** Constant Values ***************************************************************/
 
 /**************************************** Struct and Type definitions ***************************************************/
 
+/// Whether the CPU is executing instructions.
+///     Running:    Executing instructions as normal.
+///     Waiting:    Sleeping after WAI, until an interrupt arrives. The rest of the system keeps running.
+///     Stopped:    Stopped by STP. Only a reset starts the CPU again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuRunState {
+    Running,
+    Waiting,
+    Stopped,
+}
+
+/// Error which is returned when the CPU reaches an opcode that has not been implemented.
+/// The PC is left pointing at the opcode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnimplementedOpcodeError {
+    opcode: u8,
+    address: usize,
+}
+
+impl Display for UnimplementedOpcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unimplemented opcode ${:02X} at ${:06X}",
+            self.opcode, self.address
+        )
+    }
+}
+
+/// Time taken by one step of the CPU.
+///     clocks: Master clocks the step took.
+///     cycles: CPU cycles the step took, each a memory access or an internal operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuStep {
+    pub clocks: u16,
+    pub cycles: u8,
+}
+
+/// One internal cycle, which the CPU idles for while it is stopped or waiting.
+const IDLE_STEP: CpuStep = CpuStep {
+    clocks: timing::FAST_ACCESS_CLOCKS,
+    cycles: 1,
+};
+
 /// Virtualized representation of the CPU internally.
 #[derive(Debug)]
 pub struct CpuState {
     pub(self) registers: CpuRegisters,
-    pub cycles_to_pend: u8, // Number of cycles to pend before running next operation.
+    pub(self) interrupts: InterruptController,
+    pub run_state: CpuRunState,
 }
 
 impl CpuState {
@@ -21,18 +78,71 @@ impl CpuState {
     pub const fn new() -> Self {
         Self {
             registers: CpuRegisters::new(),
-            cycles_to_pend: 0x00,
+            interrupts: InterruptController::new(),
+            run_state: CpuRunState::Running,
         }
     }
 
-    /// Fetch, Decode, Execute the next instruction, and return false if the VM needs to stop running.
+    /// Reset the CPU, and start executing from `bank`:`pc`.
+    /// # Parameters
+    ///     - `self`
+    ///     - `bank`:   Bank to start executing from.
+    ///     - `pc`:     Address to start executing from, usually the RESET vector.
+    pub fn reset(&mut self, bank: u8, pc: u16) {
+        self.registers.reset(bank, pc);
+        self.interrupts.reset();
+        self.run_state = CpuRunState::Running;
+    }
+
+    /// Set the exception vector table that interrupts vector through.
+    /// # Parameters
+    ///     - `self`
+    ///     - `table`:  Exception vector table parsed from the ROM.
+    pub fn set_exception_vectors(&mut self, table: romdata::ExceptionVectorTable) {
+        self.interrupts.set_exception_vectors(table);
+    }
+
+    /// Signal an NMI, which is taken before the next instruction regardless of the I flag.
+    pub fn request_nmi(&mut self) { self.interrupts.request_nmi(); }
+
+    /// Assert or release the IRQ line. While asserted, an IRQ is taken before each instruction unless I is set.
+    /// # Parameters
+    ///     - `self`
+    ///     - `asserted`:   `true` while a device is requesting an IRQ.
+    // Nothing raises an IRQ until the timers exist.
+    #[cfg(test)]
+    pub fn set_irq(&mut self, asserted: bool) { self.interrupts.set_irq(asserted); }
+
+    /// Signal an ABORT, which is taken before the next instruction.
+    // Nothing on the SNES raises an ABORT.
+    #[cfg(test)]
+    pub fn request_abort(&mut self) { self.interrupts.request_abort(); }
+
+    /// Fetch, Decode, Execute the next instruction, or take a pending interrupt.
+    /// While the CPU is stopped, or waiting for an interrupt, it idles for one internal cycle.
     /// # Parameters
     ///     - `self`
     ///     - `memory`: Mutable pointer to current memory state.
     /// # Returns
-    ///     - `true`:    If running,
-    ///     - `false`:   If run should halt.
-    pub fn step(&mut self, mem: &mut memory::Memory) -> bool {
+    ///     - `Ok(step)`:   The master clocks and CPU cycles the step took.
+    ///     - `Err(error)`: If the next opcode has not been implemented.
+    pub fn step(&mut self, mem: &mut memory::Memory) -> Result<CpuStep, UnimplementedOpcodeError> {
+        match self.run_state {
+            CpuRunState::Running => {}
+            CpuRunState::Stopped => return Ok(IDLE_STEP),
+            // While waiting on WAI, nothing runs until an interrupt arrives.
+            CpuRunState::Waiting => match self.interrupts.should_wake() {
+                true => self.run_state = CpuRunState::Running,
+                false => return Ok(IDLE_STEP),
+            },
+        }
+
+        // Interrupts are taken between instructions.
+        let irq_disabled = self.registers.get_flag(StatusFlags::IRQDisable);
+        if let Some(vector) = self.interrupts.take_pending(irq_disabled) {
+            return Ok(instructions::interrupt(self, vector, mem));
+        }
+
         let next_instruction = instructions::fetch_and_decode(self, mem);
         instructions::execute(self, next_instruction, mem)
     }
@@ -43,8 +153,63 @@ impl CpuState {
     }
 
     // Print the state of the CPU.
-    pub fn print_state(&self) { self.registers.print_state(); }
+    pub fn print_state(&self) {
+        self.registers.print_state();
+        if self.run_state != CpuRunState::Running {
+            println!("CPU is {:?}", self.run_state);
+        }
+    }
 }
+
+impl Component for CpuState {
+    /// Step the CPU by one instruction, interrupt, or idle cycle.
+    fn tick(&mut self, memory: &mut memory::Memory) -> Result<usize, EmulationError> {
+        Ok(self.step(memory)?.clocks as usize)
+    }
+}
+
 /**************************************** File Scope Functions **********************************************************/
 
 /**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stp() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = memory::Memory::new();
+        test_mem.put_byte(0x808000, 0xDB).unwrap();
+        test_mem.put_byte(0x808001, 0xEA).unwrap();
+
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.run_state, CpuRunState::Stopped);
+        assert_eq!(test_cpu.get_pc(), 0x808001);
+
+        // Nothing runs while stopped, not even interrupts.
+        test_cpu.request_nmi();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808001);
+
+        // Only a reset starts the CPU again.
+        test_cpu.reset(0x80, 0x8001);
+        assert_eq!(test_cpu.run_state, CpuRunState::Running);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808002);
+    }
+
+    #[test]
+    fn test_wai_state() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = memory::Memory::new();
+        test_mem.put_byte(0x808000, 0xCB).unwrap();
+
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.run_state, CpuRunState::Waiting);
+
+        test_cpu.set_irq(true);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.run_state, CpuRunState::Running);
+    }
+}
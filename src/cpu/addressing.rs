@@ -0,0 +1,824 @@
+use super::{
+    memory::{self},
+    registers::{StatusFlags, REGISTER_MODE_8_BIT},
+    CpuState,
+};
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Mask for a full 24-bit address.
+const ADDRESS_MASK: usize = 0xFFFFFF;
+
+/// Mask for the bank portion of a full address.
+const BANK_MASK: usize = 0xFF0000;
+
+/// Mask for the bank and page portion of a full address.
+const PAGE_MASK: usize = 0xFFFF00;
+
+/// Length of a pointer read by an indirect addressing mode.
+const POINTER_BYTES: u8 = 2;
+
+/// Length of a pointer read by a long indirect addressing mode, which includes the bank.
+const POINTER_LONG_BYTES: u8 = 3;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Addressing modes of the 65816.
+/// https://undisbeliever.net/snesdev/65816-opcodes.html#addressing-modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CpuAddressMode {
+    Implied,                        // NOP
+    Accumulator,                    // ASL A
+    Immediate,                      // LDA #const
+    Stack,                          // PHA
+    BlockMove,                      // MVN srcbk, destbk
+    DirectPage,                     // LDA dp
+    DirectPageIndexedX,             // LDA dp,X
+    DirectPageIndexedY,             // LDX dp,Y
+    DirectPageIndirect,             // LDA (dp)
+    DirectPageIndirectLong,         // LDA [dp]
+    DirectPageIndexedIndirectX,     // LDA (dp,X)
+    DirectPageIndirectIndexedY,     // LDA (dp),Y
+    DirectPageIndirectLongIndexedY, // LDA [dp],Y
+    Absolute,                       // LDA addr
+    AbsoluteIndexedX,               // LDA addr,X
+    AbsoluteIndexedY,               // LDA addr,Y
+    AbsoluteLong,                   // LDA long
+    AbsoluteLongIndexedX,           // LDA long,X
+    StackRelative,                  // LDA sr,S
+    StackRelativeIndirectIndexedY,  // LDA (sr,S),Y
+    AbsoluteIndirect,               // JMP (addr)
+    AbsoluteIndirectLong,           // JML [addr]
+    AbsoluteIndexedIndirect,        // JMP (addr,X)
+    ProgramCounterRelative,         // BRA nearlabel
+    ProgramCounterRelativeLong,     // BRL label
+}
+
+/// How the address of each following byte is computed when an access spans more than one byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AddressWrap {
+    Long, // Follows the full 24-bit address space, so a word may cross into the next bank.
+    Bank, // Wraps within the bank, as with direct page and stack accesses.
+    Page, // Wraps within the page, as with direct page accesses in emulation mode when DL is $00.
+}
+
+/// A fully resolved effective address for an instruction.
+///     - `address`:        Composed 24-bit address of the first byte of the operand.
+///     - `wrap`:           How to step to the following bytes of the operand.
+///     - `page_crossed`:   Whether indexing moved the address onto another page, which costs a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct EffectiveAddress {
+    pub(super) address: usize,
+    pub(super) wrap: AddressWrap,
+    pub(super) page_crossed: bool,
+}
+
+impl EffectiveAddress {
+    pub(super) fn new(address: usize, wrap: AddressWrap) -> Self {
+        Self {
+            address: address & ADDRESS_MASK,
+            wrap,
+            page_crossed: false,
+        }
+    }
+
+    /// Compute the address `count` bytes after this one, honoring the wrapping rules of the address.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `count`:  Number of bytes past the effective address.
+    /// # Returns:
+    ///     - Composed full address of the target byte.
+    pub(super) fn offset(&self, count: usize) -> usize {
+        match self.wrap {
+            AddressWrap::Long => (self.address + count) & ADDRESS_MASK,
+            AddressWrap::Bank => (self.address & BANK_MASK) | ((self.address + count) & 0xFFFF),
+            AddressWrap::Page => (self.address & PAGE_MASK) | ((self.address + count) & 0xFF),
+        }
+    }
+
+    /// Read a byte or a word from this address.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`:     Memory to read from.
+    ///     - `width`:      `REGISTER_MODE_8_BIT` to read a byte, `REGISTER_MODE_16_BIT` to read a word.
+    /// # Returns:
+    ///     - The value read, widened to a u16.
+    pub(super) fn read(&self, memory: &mut memory::Memory, width: bool) -> u16 {
+        let low = memory.read(self.address);
+
+        match width {
+            REGISTER_MODE_8_BIT => low as u16,
+            _ => u16::from_le_bytes([low, memory.read(self.offset(1))]),
+        }
+    }
+
+    /// Read a 24-bit value from this address, as is used by the long pointer addressing modes.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`:     Memory to read from.
+    /// # Returns:
+    ///     - The composed 24-bit value.
+    pub(super) fn read_long(&self, memory: &mut memory::Memory) -> usize {
+        let address = self.read(memory, false);
+        memory::compose_address(memory.read(self.offset(2)), address)
+    }
+
+    /// Write a byte or a word to this address.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`:     Memory to write to.
+    ///     - `width`:      `REGISTER_MODE_8_BIT` to write a byte, `REGISTER_MODE_16_BIT` to write a word.
+    ///     - `value`:      Value to write. Only the low byte is written in 8-bit mode.
+    pub(super) fn write(&self, memory: &mut memory::Memory, width: bool, value: u16) {
+        let bytes = value.to_le_bytes();
+        memory.write(self.address, bytes[0]);
+
+        if width != REGISTER_MODE_8_BIT {
+            memory.write(self.offset(1), bytes[1]);
+        }
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Compute an address within the direct page.
+/// In emulation mode, when the low byte of the direct page register is zero, the direct page behaves like the 6502
+/// zero page and all indexing wraps within it. Otherwise, direct page accesses wrap within bank $00.
+/// # Parameters:
+///     - `cpu`:        CPU to read the direct page register from.
+///     - `offset`:     Direct page offset from the instruction.
+///     - `index`:      Value of the index register to apply, or 0.
+/// # Returns:
+///     - The effective address inside the direct page.
+fn direct_page_address(cpu: &CpuState, offset: u16, index: u16) -> EffectiveAddress {
+    let direct_page = cpu.registers.direct_page.0;
+
+    if cpu.registers.emulation && (direct_page & 0x00FF) == 0 {
+        let low = (offset.wrapping_add(index)) & 0x00FF;
+        EffectiveAddress::new((direct_page | low) as usize, AddressWrap::Page)
+    }
+    else {
+        let address = direct_page.wrapping_add(offset).wrapping_add(index);
+        EffectiveAddress::new(address as usize, AddressWrap::Bank)
+    }
+}
+
+/// Apply an index to an already composed address in the data bank.
+/// # Parameters:
+///     - `base`:       Composed 24-bit base address.
+///     - `index`:      Value of the index register to apply.
+/// # Returns:
+///     - The indexed effective address, noting if it crossed a page boundary.
+fn indexed_address(base: usize, index: u16) -> EffectiveAddress {
+    let mut effective = EffectiveAddress::new(base + index as usize, AddressWrap::Long);
+    effective.page_crossed = (base & PAGE_MASK) != (effective.address & PAGE_MASK);
+    effective
+}
+
+/// Compute the target of a relative branch.
+/// The PC has already been stepped past the instruction when it runs, so the offset is taken from the PC as it is.
+/// # Parameters:
+///     - `cpu`:        CPU to read the program counter and bank from.
+///     - `offset`:     Signed offset from the next instruction.
+/// # Returns:
+///     - The target of the branch, which always remains within the program bank.
+fn relative_address(cpu: &CpuState, offset: u16) -> EffectiveAddress {
+    let next_instruction = cpu.registers.pc.0;
+    let target = next_instruction.wrapping_add(offset);
+    let mut effective = EffectiveAddress::new(
+        memory::compose_address(cpu.registers.program_bank.0, target),
+        AddressWrap::Bank,
+    );
+    effective.page_crossed = (next_instruction & 0xFF00) != (target & 0xFF00);
+    effective
+}
+
+/// Check whether an addressing mode operates on the direct page.
+/// # Parameters:
+///     - `mode`:       Addressing mode to test.
+/// # Returns:
+///     - `true`:       If the mode is one of the direct page modes.
+///     - `false`:      Otherwise.
+fn is_direct_page(mode: CpuAddressMode) -> bool {
+    matches!(
+        mode,
+        CpuAddressMode::DirectPage
+            | CpuAddressMode::DirectPageIndexedX
+            | CpuAddressMode::DirectPageIndexedY
+            | CpuAddressMode::DirectPageIndirect
+            | CpuAddressMode::DirectPageIndirectLong
+            | CpuAddressMode::DirectPageIndexedIndirectX
+            | CpuAddressMode::DirectPageIndirectIndexedY
+            | CpuAddressMode::DirectPageIndirectLongIndexedY
+    )
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// Resolve the effective address for an instruction.
+/// https://undisbeliever.net/snesdev/65816-opcodes.html#addressing-modes
+///
+/// # Parameters:
+///     - `cpu`:        Current state of the CPU, for the index, stack, direct page and bank registers.
+///     - `memory`:     Memory to read any indirect pointers from.
+///     - `mode`:       Addressing mode of the instruction.
+///     - `bank`:       Bank byte of the operand, for the long addressing modes.
+///     - `param`:      16-bit operand of the instruction.
+/// # Returns:
+///     - `Some(EffectiveAddress)`:     The address the instruction operates on.
+///     - `None`:                       If the addressing mode does not reference memory.
+pub(super) fn resolve(
+    cpu: &CpuState, memory: &mut memory::Memory, mode: CpuAddressMode, bank: Option<u8>, param: u16,
+) -> Option<EffectiveAddress> {
+    let index_x = cpu.registers.index_x.0;
+    let index_y = cpu.registers.index_y.0;
+    let data_bank = cpu.registers.data_bank.0;
+    let program_bank = cpu.registers.program_bank.0;
+
+    // Indirect modes read a pointer before they can find the operand.
+    let pointer = pointer_location(cpu, mode, param).map(|(location, bytes)| match bytes {
+        POINTER_LONG_BYTES => location.read_long(memory),
+        _ => location.read(memory, false) as usize,
+    });
+    let pointer = || pointer.expect("Indirect addressing mode has no pointer location.");
+
+    match mode {
+        CpuAddressMode::Implied
+        | CpuAddressMode::Accumulator
+        | CpuAddressMode::Immediate
+        | CpuAddressMode::Stack
+        | CpuAddressMode::BlockMove => None,
+
+        CpuAddressMode::DirectPage => Some(direct_page_address(cpu, param, 0)),
+        CpuAddressMode::DirectPageIndexedX => Some(direct_page_address(cpu, param, index_x)),
+        CpuAddressMode::DirectPageIndexedY => Some(direct_page_address(cpu, param, index_y)),
+
+        CpuAddressMode::DirectPageIndirect | CpuAddressMode::DirectPageIndexedIndirectX => {
+            Some(EffectiveAddress::new(
+                memory::compose_address(data_bank, pointer() as u16),
+                AddressWrap::Long,
+            ))
+        }
+        CpuAddressMode::DirectPageIndirectLong => {
+            Some(EffectiveAddress::new(pointer(), AddressWrap::Long))
+        }
+        CpuAddressMode::DirectPageIndirectIndexedY
+        | CpuAddressMode::StackRelativeIndirectIndexedY => Some(indexed_address(
+            memory::compose_address(data_bank, pointer() as u16),
+            index_y,
+        )),
+        CpuAddressMode::DirectPageIndirectLongIndexedY => Some(indexed_address(pointer(), index_y)),
+
+        CpuAddressMode::Absolute => Some(EffectiveAddress::new(
+            memory::compose_address(data_bank, param),
+            AddressWrap::Long,
+        )),
+        CpuAddressMode::AbsoluteIndexedX => Some(indexed_address(
+            memory::compose_address(data_bank, param),
+            index_x,
+        )),
+        CpuAddressMode::AbsoluteIndexedY => Some(indexed_address(
+            memory::compose_address(data_bank, param),
+            index_y,
+        )),
+        CpuAddressMode::AbsoluteLong => Some(EffectiveAddress::new(
+            memory::compose_address(bank.unwrap_or(data_bank), param),
+            AddressWrap::Long,
+        )),
+        CpuAddressMode::AbsoluteLongIndexedX => Some(indexed_address(
+            memory::compose_address(bank.unwrap_or(data_bank), param),
+            index_x,
+        )),
+
+        CpuAddressMode::StackRelative => {
+            let address = cpu.registers.stack_ptr.0.wrapping_add(param);
+            Some(EffectiveAddress::new(address as usize, AddressWrap::Bank))
+        }
+
+        // Jumps through a pointer stay in the program bank.
+        CpuAddressMode::AbsoluteIndirect | CpuAddressMode::AbsoluteIndexedIndirect => {
+            Some(EffectiveAddress::new(
+                memory::compose_address(program_bank, pointer() as u16),
+                AddressWrap::Bank,
+            ))
+        }
+        CpuAddressMode::AbsoluteIndirectLong => {
+            Some(EffectiveAddress::new(pointer(), AddressWrap::Bank))
+        }
+
+        CpuAddressMode::ProgramCounterRelative => {
+            Some(relative_address(cpu, param as u8 as i8 as u16))
+        }
+        CpuAddressMode::ProgramCounterRelativeLong => Some(relative_address(cpu, param)),
+    }
+}
+
+/// Find where an indirect addressing mode reads its pointer from.
+/// # Parameters:
+///     - `cpu`:        Current state of the CPU, for the index, stack, direct page and bank registers.
+///     - `mode`:       Addressing mode of the instruction.
+///     - `param`:      16-bit operand of the instruction.
+/// # Returns:
+///     - `Some((location, bytes))`:    The address of the pointer, and whether it is 2 or 3 bytes long.
+///     - `None`:                       If the addressing mode does not use a pointer.
+pub(super) fn pointer_location(
+    cpu: &CpuState, mode: CpuAddressMode, param: u16,
+) -> Option<(EffectiveAddress, u8)> {
+    let index_x = cpu.registers.index_x.0;
+
+    match mode {
+        CpuAddressMode::DirectPageIndirect | CpuAddressMode::DirectPageIndirectIndexedY => {
+            Some((direct_page_address(cpu, param, 0), POINTER_BYTES))
+        }
+        CpuAddressMode::DirectPageIndexedIndirectX => {
+            Some((direct_page_address(cpu, param, index_x), POINTER_BYTES))
+        }
+        CpuAddressMode::DirectPageIndirectLong | CpuAddressMode::DirectPageIndirectLongIndexedY => {
+            // Long pointers were introduced with the 65816 and never wrap within the zero page.
+            let mut location = direct_page_address(cpu, param, 0);
+            location.wrap = AddressWrap::Bank;
+            Some((location, POINTER_LONG_BYTES))
+        }
+        CpuAddressMode::StackRelativeIndirectIndexedY => {
+            let location = cpu.registers.stack_ptr.0.wrapping_add(param);
+            Some((
+                EffectiveAddress::new(location as usize, AddressWrap::Bank),
+                POINTER_BYTES,
+            ))
+        }
+        // The pointer for (abs) and [abs] is always read from bank $00.
+        CpuAddressMode::AbsoluteIndirect => Some((
+            EffectiveAddress::new(param as usize, AddressWrap::Bank),
+            POINTER_BYTES,
+        )),
+        CpuAddressMode::AbsoluteIndirectLong => Some((
+            EffectiveAddress::new(param as usize, AddressWrap::Bank),
+            POINTER_LONG_BYTES,
+        )),
+        // Unlike (abs), the pointer for (abs,X) is read from the program bank.
+        CpuAddressMode::AbsoluteIndexedIndirect => {
+            let location =
+                memory::compose_address(cpu.registers.program_bank.0, param.wrapping_add(index_x));
+            Some((
+                EffectiveAddress::new(location, AddressWrap::Bank),
+                POINTER_BYTES,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Number of cycles a read of an operand takes for an addressing mode, including the opcode fetch.
+/// This is the count for an 8-bit operand, and handlers add a cycle for each extra byte they touch.
+/// https://undisbeliever.net/snesdev/65816-opcodes.html#lda-load-accumulator-from-memory
+///
+/// # Parameters:
+///     - `cpu`:        Current state of the CPU, to check the direct page and index register width.
+///     - `mode`:       Addressing mode of the instruction.
+///     - `effective`:  Resolved effective address, to check for page crossings.
+/// # Returns:
+///     - Number of cycles the access takes.
+pub(super) fn read_cycles(
+    cpu: &CpuState, mode: CpuAddressMode, effective: Option<EffectiveAddress>,
+) -> u8 {
+    let mut cycles: u8 = match mode {
+        CpuAddressMode::Implied
+        | CpuAddressMode::Accumulator
+        | CpuAddressMode::Immediate
+        | CpuAddressMode::ProgramCounterRelative => 2,
+        CpuAddressMode::DirectPage => 3,
+        CpuAddressMode::DirectPageIndexedX
+        | CpuAddressMode::DirectPageIndexedY
+        | CpuAddressMode::Absolute
+        | CpuAddressMode::AbsoluteIndexedX
+        | CpuAddressMode::AbsoluteIndexedY
+        | CpuAddressMode::StackRelative
+        | CpuAddressMode::ProgramCounterRelativeLong => 4,
+        CpuAddressMode::DirectPageIndirect
+        | CpuAddressMode::DirectPageIndirectIndexedY
+        | CpuAddressMode::AbsoluteLong
+        | CpuAddressMode::AbsoluteLongIndexedX
+        | CpuAddressMode::AbsoluteIndirect => 5,
+        CpuAddressMode::DirectPageIndirectLong
+        | CpuAddressMode::DirectPageIndexedIndirectX
+        | CpuAddressMode::DirectPageIndirectLongIndexedY
+        | CpuAddressMode::AbsoluteIndirectLong
+        | CpuAddressMode::AbsoluteIndexedIndirect => 6,
+        CpuAddressMode::StackRelativeIndirectIndexedY => 7,
+        CpuAddressMode::Stack | CpuAddressMode::BlockMove => 3,
+    };
+
+    // Every direct page access takes an extra cycle when the direct page is not aligned to a page.
+    if is_direct_page(mode) && cpu.registers.direct_page.0 & 0x00FF != 0 {
+        cycles += 1;
+    }
+
+    // Indexing across a page, or with 16-bit index registers, takes an extra cycle.
+    if let CpuAddressMode::AbsoluteIndexedX
+    | CpuAddressMode::AbsoluteIndexedY
+    | CpuAddressMode::DirectPageIndirectIndexedY = mode
+    {
+        let crossed = effective.is_some_and(|address| address.page_crossed);
+        if crossed || !cpu.registers.get_flag(StatusFlags::IndexSize) {
+            cycles += 1;
+        }
+    }
+
+    cycles
+}
+
+/// Number of cycles a write of an operand takes for an addressing mode, including the opcode fetch.
+/// Writes always take the extra indexing cycle, whether or not a page boundary was crossed.
+/// https://undisbeliever.net/snesdev/65816-opcodes.html#sta-store-accumulator-to-memory
+///
+/// # Parameters:
+///     - `cpu`:        Current state of the CPU, to check the direct page register.
+///     - `mode`:       Addressing mode of the instruction.
+/// # Returns:
+///     - Number of cycles the access takes.
+pub(super) fn write_cycles(cpu: &CpuState, mode: CpuAddressMode) -> u8 {
+    let mut indexed = EffectiveAddress::new(0, AddressWrap::Long);
+    indexed.page_crossed = true;
+    read_cycles(cpu, mode, Some(indexed))
+}
+
+/// Number of cycles a read-modify-write of an operand takes for an addressing mode, including the opcode fetch.
+/// The operand is read, modified during an internal cycle, and then written back.
+/// This is the count for an 8-bit operand, and handlers add two cycles for a 16-bit operand.
+/// https://undisbeliever.net/snesdev/65816-opcodes.html#inc-increment
+///
+/// # Parameters:
+///     - `cpu`:        Current state of the CPU, to check the direct page register.
+///     - `mode`:       Addressing mode of the instruction.
+/// # Returns:
+///     - Number of cycles the access takes.
+pub(super) fn modify_cycles(cpu: &CpuState, mode: CpuAddressMode) -> u8 {
+    match mode {
+        CpuAddressMode::Accumulator => 2,
+        _ => write_cycles(cpu, mode) + 2,
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use memory::Memory;
+    use std::num::Wrapping;
+
+    /**************************************** Test Helpers **************************************************************/
+
+    /// Build a CPU with a non-trivial set of registers to resolve addresses against.
+    fn test_cpu() -> CpuState {
+        let mut cpu = CpuState::new();
+        cpu.registers.set_emulation(false);
+        cpu.registers.clear_flag(StatusFlags::AccSize);
+        cpu.registers.clear_flag(StatusFlags::IndexSize);
+        cpu.registers.direct_page = Wrapping(0x1000);
+        cpu.registers.data_bank = Wrapping(0x7E);
+        cpu.registers.program_bank = Wrapping(0x80);
+        cpu.registers.stack_ptr = Wrapping(0x01F0);
+        cpu.registers.index_x = Wrapping(0x0004);
+        cpu.registers.index_y = Wrapping(0x0010);
+        cpu.registers.pc = Wrapping(0x8000);
+        cpu
+    }
+
+    /**************************************** Unit Test Implementations *************************************************/
+
+    #[test]
+    fn test_no_address_modes() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        for mode in [
+            CpuAddressMode::Implied,
+            CpuAddressMode::Accumulator,
+            CpuAddressMode::Immediate,
+            CpuAddressMode::Stack,
+            CpuAddressMode::BlockMove,
+        ] {
+            assert_eq!(resolve(&cpu, &mut memory, mode, None, 0x1234), None);
+        }
+    }
+
+    #[test]
+    fn test_direct_page() {
+        let mut cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        let test_cases = vec![
+            // mode, param, address
+            (CpuAddressMode::DirectPage, 0x20, 0x001020),
+            (CpuAddressMode::DirectPageIndexedX, 0x20, 0x001024),
+            (CpuAddressMode::DirectPageIndexedY, 0x20, 0x001030),
+            (CpuAddressMode::DirectPageIndexedX, 0xFE, 0x001102),
+        ];
+
+        for (mode, param, address) in test_cases {
+            let effective = resolve(&cpu, &mut memory, mode, None, param).unwrap();
+            assert_eq!(effective.address, address);
+            assert_eq!(effective.wrap, AddressWrap::Bank);
+        }
+
+        // The direct page wraps within bank $00.
+        cpu.registers.direct_page = Wrapping(0xFFF0);
+        let effective = resolve(
+            &cpu,
+            &mut memory,
+            CpuAddressMode::DirectPageIndexedX,
+            None,
+            0x20,
+        )
+        .unwrap();
+        assert_eq!(effective.address, 0x000014);
+        assert_eq!(effective.offset(0xFFEC), 0x000000);
+    }
+
+    #[test]
+    fn test_direct_page_emulation_wrap() {
+        let mut cpu = test_cpu();
+        let mut memory = Memory::new();
+        cpu.registers.emulation = true;
+
+        // With DL == 0, indexing wraps inside the page.
+        let effective = resolve(
+            &cpu,
+            &mut memory,
+            CpuAddressMode::DirectPageIndexedX,
+            None,
+            0xFE,
+        )
+        .unwrap();
+        assert_eq!(effective.address, 0x001002);
+        assert_eq!(effective.wrap, AddressWrap::Page);
+        assert_eq!(
+            resolve(&cpu, &mut memory, CpuAddressMode::DirectPage, None, 0xFF)
+                .unwrap()
+                .offset(1),
+            0x001000
+        );
+
+        // With DL != 0, the 65816 behaviour is used even in emulation mode.
+        cpu.registers.direct_page = Wrapping(0x1001);
+        let effective = resolve(
+            &cpu,
+            &mut memory,
+            CpuAddressMode::DirectPageIndexedX,
+            None,
+            0xFE,
+        )
+        .unwrap();
+        assert_eq!(effective.address, 0x001103);
+        assert_eq!(effective.wrap, AddressWrap::Bank);
+    }
+
+    #[test]
+    fn test_direct_page_indirect() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        // Pointer at dp $20: $3456, bank $12.
+        memory.put_byte(0x001020, 0x56).unwrap();
+        memory.put_byte(0x001021, 0x34).unwrap();
+        memory.put_byte(0x001022, 0x12).unwrap();
+        // Pointer at dp $24 (dp $20 + X): $ABCD.
+        memory.put_byte(0x001024, 0xCD).unwrap();
+        memory.put_byte(0x001025, 0xAB).unwrap();
+
+        let test_cases = vec![
+            // mode, address
+            (CpuAddressMode::DirectPageIndirect, 0x7E3456),
+            (CpuAddressMode::DirectPageIndirectLong, 0x123456),
+            (CpuAddressMode::DirectPageIndexedIndirectX, 0x7EABCD),
+            (CpuAddressMode::DirectPageIndirectIndexedY, 0x7E3466),
+            (CpuAddressMode::DirectPageIndirectLongIndexedY, 0x123466),
+        ];
+
+        for (mode, address) in test_cases {
+            println!("Test case: {:?}", mode);
+            let effective = resolve(&cpu, &mut memory, mode, None, 0x20).unwrap();
+            assert_eq!(effective.address, address);
+            assert_eq!(effective.wrap, AddressWrap::Long);
+        }
+    }
+
+    #[test]
+    fn test_absolute() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        let test_cases = vec![
+            // mode, bank, param, address, page crossed
+            (CpuAddressMode::Absolute, None, 0x1234, 0x7E1234, false),
+            (
+                CpuAddressMode::AbsoluteIndexedX,
+                None,
+                0x1234,
+                0x7E1238,
+                false,
+            ),
+            (
+                CpuAddressMode::AbsoluteIndexedY,
+                None,
+                0x12F8,
+                0x7E1308,
+                true,
+            ),
+            // Indexing past the end of a bank continues into the next one.
+            (
+                CpuAddressMode::AbsoluteIndexedY,
+                None,
+                0xFFF8,
+                0x7F0008,
+                true,
+            ),
+            (
+                CpuAddressMode::AbsoluteLong,
+                Some(0x12),
+                0x3456,
+                0x123456,
+                false,
+            ),
+            (
+                CpuAddressMode::AbsoluteLongIndexedX,
+                Some(0x12),
+                0x3456,
+                0x12345A,
+                false,
+            ),
+            (
+                CpuAddressMode::AbsoluteLongIndexedX,
+                Some(0xFF),
+                0xFFFF,
+                0x000003,
+                true,
+            ),
+        ];
+
+        for (mode, bank, param, address, page_crossed) in test_cases {
+            println!("Test case: {:?} {:04X}", mode, param);
+            let effective = resolve(&cpu, &mut memory, mode, bank, param).unwrap();
+            assert_eq!(effective.address, address);
+            assert_eq!(effective.page_crossed, page_crossed);
+            assert_eq!(effective.wrap, AddressWrap::Long);
+        }
+
+        // A word read from the end of a bank reads the first byte of the next bank.
+        let effective = resolve(&cpu, &mut memory, CpuAddressMode::Absolute, None, 0xFFFF).unwrap();
+        assert_eq!(effective.offset(1), 0x7F0000);
+    }
+
+    #[test]
+    fn test_stack_relative() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        // Pointer at S + 3: $2000.
+        memory.put_byte(0x0001F3, 0x00).unwrap();
+        memory.put_byte(0x0001F4, 0x20).unwrap();
+
+        let effective = resolve(&cpu, &mut memory, CpuAddressMode::StackRelative, None, 0x03).unwrap();
+        assert_eq!(effective.address, 0x0001F3);
+        assert_eq!(effective.wrap, AddressWrap::Bank);
+
+        let effective = resolve(
+            &cpu,
+            &mut memory,
+            CpuAddressMode::StackRelativeIndirectIndexedY,
+            None,
+            0x03,
+        )
+        .unwrap();
+        assert_eq!(effective.address, 0x7E2010);
+    }
+
+    #[test]
+    fn test_absolute_indirect() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        // Pointer in bank $00 at $0300: $9000, bank $C0.
+        memory.put_byte(0x000300, 0x00).unwrap();
+        memory.put_byte(0x000301, 0x90).unwrap();
+        memory.put_byte(0x000302, 0xC0).unwrap();
+        // Pointer in the program bank at $80:0304 (abs + X): $A000.
+        memory.put_byte(0x800304, 0x00).unwrap();
+        memory.put_byte(0x800305, 0xA0).unwrap();
+
+        let test_cases = vec![
+            // mode, address
+            (CpuAddressMode::AbsoluteIndirect, 0x809000),
+            (CpuAddressMode::AbsoluteIndirectLong, 0xC09000),
+            (CpuAddressMode::AbsoluteIndexedIndirect, 0x80A000),
+        ];
+
+        for (mode, address) in test_cases {
+            println!("Test case: {:?}", mode);
+            let effective = resolve(&cpu, &mut memory, mode, None, 0x0300).unwrap();
+            assert_eq!(effective.address, address);
+        }
+    }
+
+    #[test]
+    fn test_program_counter_relative() {
+        let mut cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        let test_cases = vec![
+            // mode, pc of the next instruction, param, address
+            (
+                CpuAddressMode::ProgramCounterRelative,
+                0x8002,
+                0x0010,
+                0x808012,
+            ),
+            (
+                CpuAddressMode::ProgramCounterRelative,
+                0x8002,
+                0x00FE,
+                0x808000,
+            ),
+            (
+                CpuAddressMode::ProgramCounterRelative,
+                0x8012,
+                0x0080,
+                0x807F92,
+            ),
+            (
+                CpuAddressMode::ProgramCounterRelativeLong,
+                0x8003,
+                0x1000,
+                0x809003,
+            ),
+            // Relative branches wrap within the program bank.
+            (
+                CpuAddressMode::ProgramCounterRelativeLong,
+                0xFFF3,
+                0x0020,
+                0x800013,
+            ),
+        ];
+
+        for (mode, pc, param, address) in test_cases {
+            cpu.registers.pc = Wrapping(pc);
+            let effective = resolve(&cpu, &mut memory, mode, None, param).unwrap();
+            assert_eq!(effective.address, address);
+        }
+    }
+
+    #[test]
+    fn test_read_write_word() {
+        let cpu = test_cpu();
+        let mut memory = Memory::new();
+
+        let effective = resolve(&cpu, &mut memory, CpuAddressMode::Absolute, None, 0x2000).unwrap();
+        effective.write(&mut memory, false, 0xAABB);
+        assert_eq!(memory.get_byte(0x7E2000).unwrap(), 0xBB);
+        assert_eq!(memory.get_byte(0x7E2001).unwrap(), 0xAA);
+        assert_eq!(effective.read(&mut memory, false), 0xAABB);
+        assert_eq!(effective.read(&mut memory, REGISTER_MODE_8_BIT), 0x00BB);
+
+        effective.write(&mut memory, REGISTER_MODE_8_BIT, 0x1122);
+        assert_eq!(effective.read(&mut memory, false), 0xAA22);
+    }
+
+    #[test]
+    fn test_read_cycles() {
+        let mut cpu = test_cpu();
+        cpu.registers.set_flag(StatusFlags::IndexSize);
+        let mut memory = Memory::new();
+
+        let test_cases = vec![
+            // mode, param, direct page, cycles
+            (CpuAddressMode::Immediate, 0x00, 0x0000, 2),
+            (CpuAddressMode::DirectPage, 0x00, 0x0000, 3),
+            (CpuAddressMode::DirectPage, 0x00, 0x0001, 4),
+            (CpuAddressMode::AbsoluteIndexedX, 0x1000, 0x0000, 4),
+            (CpuAddressMode::AbsoluteIndexedX, 0x10FF, 0x0000, 5),
+            (
+                CpuAddressMode::StackRelativeIndirectIndexedY,
+                0x00,
+                0x0000,
+                7,
+            ),
+        ];
+
+        for (mode, param, direct_page, cycles) in test_cases {
+            cpu.registers.direct_page = Wrapping(direct_page);
+            let effective = resolve(&cpu, &mut memory, mode, None, param);
+            assert_eq!(read_cycles(&cpu, mode, effective), cycles);
+        }
+
+        // Writes take the indexing cycle even without crossing a page.
+        cpu.registers.direct_page = Wrapping(0x0000);
+        assert_eq!(write_cycles(&cpu, CpuAddressMode::AbsoluteIndexedX), 5);
+        assert_eq!(write_cycles(&cpu, CpuAddressMode::Absolute), 4);
+
+        // Read-modify-write.
+        assert_eq!(modify_cycles(&cpu, CpuAddressMode::Accumulator), 2);
+        assert_eq!(modify_cycles(&cpu, CpuAddressMode::Absolute), 6);
+        assert_eq!(modify_cycles(&cpu, CpuAddressMode::AbsoluteIndexedX), 7);
+    }
+}
@@ -1,9 +1,23 @@
 mod adc;
+mod block;
 mod branch;
-mod lda;
+mod compare;
+mod flags;
+mod increment;
+mod interrupt;
+mod jump;
+mod load;
+mod logic;
 mod misc;
+mod sbc;
+mod shift;
+mod stack;
+mod store;
+mod transfer;
 
+use addressing::{CpuAddressMode, EffectiveAddress};
 use registers::REGISTER_MODE_16_BIT;
+use std::num::Wrapping;
 
 use super::*;
 
@@ -19,8 +33,96 @@ const INST_PARAM_OFFSET: u16 = 1;
 #[derive(Debug, Clone, Copy)]
 pub enum CpuOpcode {
     Adc,
+    Sbc,
+    Cmp,
+    Cpx,
+    Cpy,
+    Inc,
+    Dec,
+    Inx,
+    Iny,
+    Dex,
+    Dey,
+    And,
+    Ora,
+    Eor,
+    Bit,
+    Tsb,
+    Trb,
+    Asl,
+    Lsr,
+    Rol,
+    Ror,
+    Bcc,
+    Bcs,
+    Beq,
+    Bne,
+    Bmi,
+    Bpl,
+    Bvc,
+    Bvs,
+    Bra,
+    Brl,
+    Jmp,
+    Jml,
+    Jsr,
+    Jsl,
+    Rts,
+    Rtl,
+    Pha,
+    Phx,
+    Phy,
+    Php,
+    Phb,
+    Phk,
+    Phd,
+    Pla,
+    Plx,
+    Ply,
+    Plp,
+    Plb,
+    Pld,
+    Pea,
+    Pei,
+    Per,
+    Tax,
+    Tay,
+    Txa,
+    Tya,
+    Txy,
+    Tyx,
+    Tsx,
+    Txs,
+    Tcs,
+    Tsc,
+    Tcd,
+    Tdc,
+    Xba,
+    Cli,
+    Sei,
+    Cld,
+    Sed,
+    Clv,
+    Rep,
+    Sep,
+    Mvn,
+    Mvp,
+    Brk,
+    Cop,
+    Rti,
+    Wai,
     Lda,
+    Ldx,
+    Ldy,
+    Sta,
+    Stx,
+    Sty,
+    Stz,
+    Clc,
+    Sec,
+    Xce,
     Stp,
+    Wdm,
     Nop,
     // Many More
 }
@@ -33,6 +135,189 @@ pub(super) struct CpuInstructionFnArguments<'a> {
     memory: &'a mut memory::Memory,
     bank: Option<u8>,
     param: u16,
+    mode: CpuAddressMode,
+    fast_rom: bool,     // Whether MEMSEL enabled FastROM when the instruction started.
+    access_clocks: u16, // Master clocks taken by the memory accesses counted so far.
+    accesses: u8,       // Number of memory accesses counted so far.
+}
+
+impl<'a> CpuInstructionFnArguments<'a> {
+    /// Build the arguments for an instruction, with no parameter and no memory accesses counted yet.
+    /// # Parameters:
+    ///     - `cpu`:    CPU to run the instruction on.
+    ///     - `memory`: Memory the instruction accesses.
+    ///     - `mode`:   Addressing mode of the instruction.
+    fn new(cpu: &'a mut CpuState, memory: &'a mut memory::Memory, mode: CpuAddressMode) -> Self {
+        let fast_rom = timing::fast_rom_enabled(memory);
+        Self {
+            cpu,
+            memory,
+            bank: None,
+            param: 0,
+            mode,
+            fast_rom,
+            access_clocks: 0,
+            accesses: 0,
+        }
+    }
+}
+
+impl CpuInstructionFnArguments<'_> {
+    /// Count memory accesses towards the timing of this instruction.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the first byte accessed.
+    ///     - `bytes`:      Number of bytes accessed, each taking one cycle.
+    fn record_access(&mut self, address: usize, bytes: u8) {
+        self.access_clocks += timing::access_clocks(address, self.fast_rom) * bytes as u16;
+        self.accesses += bytes;
+    }
+
+    /// Convert the number of cycles an instruction took into the time the step took.
+    /// Cycles which were counted as memory accesses take as long as the memory they accessed, and every other cycle
+    /// is an internal operation, which is always fast.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `cycles`: Total number of cycles the instruction took.
+    /// # Returns:
+    ///     - The master clocks and cycles the instruction took.
+    fn step_timing(&self, cycles: u8) -> CpuStep {
+        let internal = cycles.saturating_sub(self.accesses);
+        CpuStep {
+            clocks: self.access_clocks + internal as u16 * timing::FAST_ACCESS_CLOCKS,
+            cycles: self.accesses + internal,
+        }
+    }
+
+    /// Resolve the effective address of this instruction's operand.
+    /// # Returns:
+    ///     - `Some(EffectiveAddress)`:     The address the instruction operates on.
+    ///     - `None`:                       If the addressing mode does not reference memory.
+    fn effective_address(&mut self) -> Option<EffectiveAddress> {
+        addressing::resolve(self.cpu, self.memory, self.mode, self.bank, self.param)
+    }
+
+    /// Fetch the operand of this instruction, either from the instruction itself or from memory.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the operand.
+    /// # Returns:
+    ///     - `(value, address)`:   The operand, and the address it was read from if it was in memory.
+    fn read_operand(&mut self, width: bool) -> (u16, Option<EffectiveAddress>) {
+        match self.effective_address() {
+            Some(address) => {
+                self.record_access(address.address, operand_bytes(width));
+                (address.read(self.memory, width), Some(address))
+            }
+            None => match width {
+                registers::REGISTER_MODE_8_BIT => (self.param & 0x00FF, None),
+                REGISTER_MODE_16_BIT => (self.param, None),
+            },
+        }
+    }
+
+    /// Read-modify-write the operand of this instruction, either the accumulator or a value in memory.
+    /// In 8-bit mode only the low byte of the operand is passed in, and only the low byte is written back.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `width`:      `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the operand.
+    ///     - `operation`:  Function which takes the CPU and the operand, and returns the modified operand.
+    /// # Returns:
+    ///     - The number of cycles the instruction took.
+    fn modify_operand(
+        &mut self, width: bool, operation: impl FnOnce(&mut CpuState, u16) -> u16,
+    ) -> u8 {
+        let low_byte_mask = match width {
+            registers::REGISTER_MODE_8_BIT => 0x00FF,
+            REGISTER_MODE_16_BIT => 0xFFFF,
+        };
+
+        match self.effective_address() {
+            Some(address) => {
+                let result = operation(self.cpu, address.read(self.memory, width));
+                address.write(self.memory, width, result & low_byte_mask);
+                self.record_access(address.address, operand_bytes(width) * 2);
+            }
+            None => {
+                let acc = self.cpu.registers.acc.0;
+                let result = operation(self.cpu, acc & low_byte_mask);
+                self.cpu.registers.acc =
+                    Wrapping((acc & !low_byte_mask) | (result & low_byte_mask));
+            }
+        }
+
+        let mut cycles = addressing::modify_cycles(self.cpu, self.mode);
+        if width == REGISTER_MODE_16_BIT && self.mode != CpuAddressMode::Accumulator {
+            cycles += 2;
+        }
+
+        cycles
+    }
+
+    /// Write the operand of this instruction to memory.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the operand.
+    ///     - `width`:      `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the operand.
+    ///     - `value`:      Value to write.
+    fn write_operand(&mut self, address: EffectiveAddress, width: bool, value: u16) {
+        address.write(self.memory, width, value);
+        self.record_access(address.address, operand_bytes(width));
+    }
+
+    /// Push a byte onto the stack in bank $00.
+    /// In emulation mode the stack pointer wraps within page 1.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `value`:  Byte to push.
+    fn push_byte(&mut self, value: u8) {
+        let stack_ptr = self.cpu.registers.stack_ptr;
+        self.memory.write(stack_ptr.0 as usize, value);
+        self.record_access(stack_ptr.0 as usize, 1);
+        self.cpu.registers.stack_ptr = self.wrap_stack(stack_ptr - Wrapping(1));
+    }
+
+    /// Push a word onto the stack, high byte first, so that it sits little endian in memory.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `value`:  Word to push.
+    fn push_word(&mut self, value: u16) {
+        let [low, high] = value.to_le_bytes();
+        self.push_byte(high);
+        self.push_byte(low);
+    }
+
+    /// Pull a byte from the stack in bank $00.
+    /// In emulation mode the stack pointer wraps within page 1.
+    /// # Returns:
+    ///     - The byte pulled from the stack.
+    fn pull_byte(&mut self) -> u8 {
+        self.cpu.registers.stack_ptr = self.wrap_stack(self.cpu.registers.stack_ptr + Wrapping(1));
+        self.record_access(self.cpu.registers.stack_ptr.0 as usize, 1);
+        self.memory.read(self.cpu.registers.stack_ptr.0 as usize)
+    }
+
+    /// Pull a word from the stack, low byte first.
+    /// # Returns:
+    ///     - The word pulled from the stack.
+    fn pull_word(&mut self) -> u16 {
+        let low = self.pull_byte();
+        let high = self.pull_byte();
+        u16::from_le_bytes([low, high])
+    }
+
+    /// Keep a new stack pointer value within page 1 when in emulation mode.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `stack_ptr`:  New value for the stack pointer.
+    /// # Returns:
+    ///     - The stack pointer value to store.
+    fn wrap_stack(&self, stack_ptr: Wrapping<u16>) -> Wrapping<u16> {
+        match self.cpu.registers.emulation {
+            true => Wrapping(registers::EMULATION_STACK_PAGE | (stack_ptr.0 & 0x00FF)),
+            false => stack_ptr,
+        }
+    }
 }
 
 /// The width of the parameter for this operation.
@@ -40,21 +325,45 @@ pub(super) struct CpuInstructionFnArguments<'a> {
 #[repr(u16)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum CpuParamWidth {
-    Variable = 0, // Paramater is variable width (depends on ALU setting)
-    None     = 1,
-    Byte     = 2, // Parameter is 8-bit (1 Byte)
-    Word     = 3, // Parameter is 16-bit (1 Word)
-    Long     = 4, // Parameter is 24-bit (long)
+    Variable      = 0, // Paramater is variable width (depends on ALU setting)
+    None          = 1,
+    Byte          = 2, // Parameter is 8-bit (1 Byte)
+    Word          = 3, // Parameter is 16-bit (1 Word)
+    Long          = 4, // Parameter is 24-bit (long)
+    VariableIndex = 5, // Parameter is variable width (depends on index register setting)
+}
+
+impl CpuParamWidth {
+    /// Resolve a variable width parameter to a fixed width from the current register widths.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `registers`:  Registers to check the accumulator and index register width flags from.
+    /// # Returns:
+    ///     - The fixed width of the parameter.
+    fn resolve(self, registers: &registers::CpuRegisters) -> Self {
+        let flag = match self {
+            CpuParamWidth::Variable => registers::StatusFlags::AccSize,
+            CpuParamWidth::VariableIndex => registers::StatusFlags::IndexSize,
+            _ => return self,
+        };
+
+        match registers.get_flag(flag) {
+            registers::REGISTER_MODE_8_BIT => CpuParamWidth::Byte,
+            REGISTER_MODE_16_BIT => CpuParamWidth::Word,
+        }
+    }
 }
 
 /// A conglomerate wrapper of the prior enums.
 ///     - `opcode`      Opcode of next operation to run.
 ///     - `width`       Width of next operation, to calculate parameters.
+///     - `mode`        Addressing mode of next operation, to resolve its operand.
 ///     - `function`    Function pointer to handler for next operation.
 #[derive(Debug, Clone, Copy)]
 pub struct CpuInstruction {
     pub opcode: CpuOpcode,
     width: CpuParamWidth,
+    mode: CpuAddressMode,
     function: CpuInstructionFn,
 }
 
@@ -62,1287 +371,1557 @@ pub struct CpuInstruction {
 /// Would prefer this to be a hashmap, but rust cannot generate a HashMap::From() as const, and a global cannot be declared using `let`.
 pub(crate) const INSTRUCTION_MAP: [CpuInstruction; NUM_INSTRUCTIONS] = [
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Brk,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::Immediate,
+        function: interrupt::brk,
     }, /* 0x00 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: logic::ora,
     }, /* 0x01 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cop,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::Immediate,
+        function: interrupt::cop,
     }, /* 0x02 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: logic::ora,
     }, /* 0x03 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Tsb,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::tsb,
     }, /* 0x04 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::ora,
     }, /* 0x05 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Asl,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: shift::asl,
     }, /* 0x06 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: logic::ora,
     }, /* 0x07 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Php,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::php,
     }, /* 0x08 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: logic::ora,
     }, /* 0x09 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Asl,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: shift::asl,
     }, /* 0x0A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Phd,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::phd,
     }, /* 0x0B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Tsb,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::tsb,
     }, /* 0x0C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::ora,
     }, /* 0x0D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Asl,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: shift::asl,
     }, /* 0x0E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: logic::ora,
     }, /* 0x0F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bpl,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bpl,
     }, /* 0x10 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: logic::ora,
     }, /* 0x11 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: logic::ora,
     }, /* 0x12 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: logic::ora,
     }, /* 0x13 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Trb,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::trb,
     }, /* 0x14 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: logic::ora,
     }, /* 0x15 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Asl,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: shift::asl,
     }, /* 0x16 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: logic::ora,
     }, /* 0x17 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Clc,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::clc,
     }, /* 0x18 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: logic::ora,
     }, /* 0x19 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Inc,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: increment::inc,
     }, /* 0x1A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tcs,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tcs,
     }, /* 0x1B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Trb,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::trb,
     }, /* 0x1C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: logic::ora,
     }, /* 0x1D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Asl,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: shift::asl,
     }, /* 0x1E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ora,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: logic::ora,
     }, /* 0x1F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jsr,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: jump::jsr,
     }, /* 0x20 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: logic::and,
     }, /* 0x21 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jsl,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: jump::jsl,
     }, /* 0x22 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: logic::and,
     }, /* 0x23 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bit,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::bit,
     }, /* 0x24 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::and,
     }, /* 0x25 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Rol,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: shift::rol,
     }, /* 0x26 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: logic::and,
     }, /* 0x27 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Plp,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::plp,
     }, /* 0x28 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: logic::and,
     }, /* 0x29 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Rol,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: shift::rol,
     }, /* 0x2A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Pld,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::pld,
     }, /* 0x2B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bit,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::bit,
     }, /* 0x2C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::and,
     }, /* 0x2D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Rol,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: shift::rol,
     }, /* 0x2E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: logic::and,
     }, /* 0x2F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bmi,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bmi,
     }, /* 0x30 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: logic::and,
     }, /* 0x31 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: logic::and,
     }, /* 0x32 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: logic::and,
     }, /* 0x33 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bit,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: logic::bit,
     }, /* 0x34 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: logic::and,
     }, /* 0x35 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Rol,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: shift::rol,
     }, /* 0x36 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: logic::and,
     }, /* 0x37 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Sec,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::sec,
     }, /* 0x38 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: logic::and,
     }, /* 0x39 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Dec,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: increment::dec,
     }, /* 0x3A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tsc,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tsc,
     }, /* 0x3B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bit,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: logic::bit,
     }, /* 0x3C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: logic::and,
     }, /* 0x3D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Rol,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: shift::rol,
     }, /* 0x3E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::And,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: logic::and,
     }, /* 0x3F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Rti,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: interrupt::rti,
     }, /* 0x40 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: logic::eor,
     }, /* 0x41 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
-    }, /* 0x42 */
+        opcode: CpuOpcode::Wdm,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::Implied,
+        function: misc::nop,
+    }, /* 0x42 Wdm */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: logic::eor,
     }, /* 0x43 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Mvp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::BlockMove,
+        function: block::mvp,
     }, /* 0x44 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: logic::eor,
     }, /* 0x45 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lsr,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: shift::lsr,
     }, /* 0x46 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: logic::eor,
     }, /* 0x47 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Pha,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::pha,
     }, /* 0x48 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: logic::eor,
     }, /* 0x49 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Lsr,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: shift::lsr,
     }, /* 0x4A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Phk,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::phk,
     }, /* 0x4B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: jump::jmp,
     }, /* 0x4C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: logic::eor,
     }, /* 0x4D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lsr,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: shift::lsr,
     }, /* 0x4E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: logic::eor,
     }, /* 0x4F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bvc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bvc,
     }, /* 0x50 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: logic::eor,
     }, /* 0x51 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: logic::eor,
     }, /* 0x52 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: logic::eor,
     }, /* 0x53 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Mvn,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::BlockMove,
+        function: block::mvn,
     }, /* 0x54 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: logic::eor,
     }, /* 0x55 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lsr,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: shift::lsr,
     }, /* 0x56 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: logic::eor,
     }, /* 0x57 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Cli,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::cli,
     }, /* 0x58 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: logic::eor,
     }, /* 0x59 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Phy,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::phy,
     }, /* 0x5A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tcd,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tcd,
     }, /* 0x5B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jml,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: jump::jmp,
     }, /* 0x5C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: logic::eor,
     }, /* 0x5D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lsr,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: shift::lsr,
     }, /* 0x5E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Eor,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: logic::eor,
     }, /* 0x5F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Rts,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: jump::rts,
     }, /* 0x60 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: adc::absolute,
     }, /* 0x61 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Per,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::ProgramCounterRelativeLong,
+        function: stack::per,
     }, /* 0x62 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: adc::absolute,
     }, /* 0x63 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stz,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: store::stz,
     }, /* 0x64 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: adc::absolute,
     }, /* 0x65 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ror,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: shift::ror,
     }, /* 0x66 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: adc::absolute,
     }, /* 0x67 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Pla,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::pla,
     }, /* 0x68 */
     CpuInstruction {
         opcode: CpuOpcode::Adc,
         width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
         function: adc::immediate,
     }, /* 0x69 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Ror,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Accumulator,
+        function: shift::ror,
     }, /* 0x6A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Rtl,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: jump::rtl,
     }, /* 0x6B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndirect,
+        function: jump::jmp,
     }, /* 0x6C */
     CpuInstruction {
         opcode: CpuOpcode::Adc,
         width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
         function: adc::absolute,
     }, /* 0x6D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ror,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: shift::ror,
     }, /* 0x6E */
     CpuInstruction {
         opcode: CpuOpcode::Adc,
         width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
         function: adc::absolute,
     }, /* 0x6F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bvs,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bvs,
     }, /* 0x70 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: adc::absolute,
     }, /* 0x71 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: adc::absolute,
     }, /* 0x72 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: adc::absolute,
     }, /* 0x73 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stz,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: store::stz,
     }, /* 0x74 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: adc::absolute,
     }, /* 0x75 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ror,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: shift::ror,
     }, /* 0x76 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: adc::absolute,
     }, /* 0x77 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Sei,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::sei,
     }, /* 0x78 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: adc::absolute,
     }, /* 0x79 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Ply,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::ply,
     }, /* 0x7A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tdc,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tdc,
     }, /* 0x7B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedIndirect,
+        function: jump::jmp,
     }, /* 0x7C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: adc::absolute,
     }, /* 0x7D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ror,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: shift::ror,
     }, /* 0x7E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Adc,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: adc::absolute,
     }, /* 0x7F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bra,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bra,
     }, /* 0x80 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: store::sta,
     }, /* 0x81 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Brl,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::ProgramCounterRelativeLong,
+        function: branch::brl,
     }, /* 0x82 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: store::sta,
     }, /* 0x83 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sty,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: store::sty,
     }, /* 0x84 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: store::sta,
     }, /* 0x85 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stx,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: store::stx,
     }, /* 0x86 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: store::sta,
     }, /* 0x87 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Dey,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: increment::dey,
     }, /* 0x88 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bit,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: logic::bit,
     }, /* 0x89 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Txa,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::txa,
     }, /* 0x8A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Phb,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::phb,
     }, /* 0x8B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sty,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: store::sty,
     }, /* 0x8C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: store::sta,
     }, /* 0x8D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stx,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: store::stx,
     }, /* 0x8E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: store::sta,
     }, /* 0x8F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bcc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bcc,
     }, /* 0x90 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: store::sta,
     }, /* 0x91 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: store::sta,
     }, /* 0x92 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: store::sta,
     }, /* 0x93 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sty,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: store::sty,
     }, /* 0x94 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: store::sta,
     }, /* 0x95 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stx,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedY,
+        function: store::stx,
     }, /* 0x96 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: store::sta,
     }, /* 0x97 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tya,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tya,
     }, /* 0x98 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: store::sta,
     }, /* 0x99 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Txs,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::txs,
     }, /* 0x9A */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Txy,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::txy,
     }, /* 0x9B */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stz,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: store::stz,
     }, /* 0x9C */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: store::sta,
     }, /* 0x9D */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Stz,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: store::stz,
     }, /* 0x9E */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sta,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: store::sta,
     }, /* 0x9F */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldy,
+        width: CpuParamWidth::VariableIndex,
+        mode: CpuAddressMode::Immediate,
+        function: load::ldy,
     }, /* 0xA0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: load::lda,
     }, /* 0xA1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldx,
+        width: CpuParamWidth::VariableIndex,
+        mode: CpuAddressMode::Immediate,
+        function: load::ldx,
     }, /* 0xA2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: load::lda,
     }, /* 0xA3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldy,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: load::ldy,
     }, /* 0xA4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: load::lda,
     }, /* 0xA5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldx,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: load::ldx,
     }, /* 0xA6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: load::lda,
     }, /* 0xA7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tay,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tay,
     }, /* 0xA8 */
     CpuInstruction {
         opcode: CpuOpcode::Lda,
-        width: CpuParamWidth::Word,
-        function: lda::immediate,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: load::lda,
     }, /* 0xA9 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tax,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tax,
     }, /* 0xAA */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Plb,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::plb,
     }, /* 0xAB */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldy,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: load::ldy,
     }, /* 0xAC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: load::lda,
     }, /* 0xAD */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldx,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: load::ldx,
     }, /* 0xAE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: load::lda,
     }, /* 0xAF */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bcs,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bcs,
     }, /* 0xB0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: load::lda,
     }, /* 0xB1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: load::lda,
     }, /* 0xB2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: load::lda,
     }, /* 0xB3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldy,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: load::ldy,
     }, /* 0xB4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: load::lda,
     }, /* 0xB5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldx,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedY,
+        function: load::ldx,
     }, /* 0xB6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: load::lda,
     }, /* 0xB7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Clv,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::clv,
     }, /* 0xB8 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: load::lda,
     }, /* 0xB9 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tsx,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tsx,
     }, /* 0xBA */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Tyx,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::tyx,
     }, /* 0xBB */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldy,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: load::ldy,
     }, /* 0xBC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: load::lda,
     }, /* 0xBD */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Ldx,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: load::ldx,
     }, /* 0xBE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Lda,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: load::lda,
     }, /* 0xBF */
-    CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+    CpuInstruction {
+        opcode: CpuOpcode::Cpy,
+        width: CpuParamWidth::VariableIndex,
+        mode: CpuAddressMode::Immediate,
+        function: compare::cpy,
     }, /* 0xC0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: compare::cmp,
     }, /* 0xC1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Rep,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::Immediate,
+        function: flags::rep,
     }, /* 0xC2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: compare::cmp,
     }, /* 0xC3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cpy,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: compare::cpy,
     }, /* 0xC4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: compare::cmp,
     }, /* 0xC5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Dec,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: increment::dec,
     }, /* 0xC6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: compare::cmp,
     }, /* 0xC7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Iny,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: increment::iny,
     }, /* 0xC8 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: compare::cmp,
     }, /* 0xC9 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Dex,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: increment::dex,
     }, /* 0xCA */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Wai,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: interrupt::wai,
     }, /* 0xCB */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cpy,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: compare::cpy,
     }, /* 0xCC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: compare::cmp,
     }, /* 0xCD */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Dec,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: increment::dec,
     }, /* 0xCE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: compare::cmp,
     }, /* 0xCF */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Bne,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::bne,
     }, /* 0xD0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: compare::cmp,
     }, /* 0xD1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: compare::cmp,
     }, /* 0xD2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: compare::cmp,
     }, /* 0xD3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Pei,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: stack::pei,
     }, /* 0xD4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: compare::cmp,
     }, /* 0xD5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Dec,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: increment::dec,
     }, /* 0xD6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: compare::cmp,
     }, /* 0xD7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Cld,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::cld,
     }, /* 0xD8 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: compare::cmp,
     }, /* 0xD9 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Phx,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::phx,
     }, /* 0xDA */
     CpuInstruction {
         opcode: CpuOpcode::Stp,
         width: CpuParamWidth::None,
+        mode: CpuAddressMode::Implied,
         function: misc::stp,
     }, /* 0xDB Stp */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jml,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndirectLong,
+        function: jump::jmp,
     }, /* 0xDC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: compare::cmp,
     }, /* 0xDD */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Dec,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: increment::dec,
     }, /* 0xDE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cmp,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: compare::cmp,
     }, /* 0xDF */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cpx,
+        width: CpuParamWidth::VariableIndex,
+        mode: CpuAddressMode::Immediate,
+        function: compare::cpx,
     }, /* 0xE0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedIndirectX,
+        function: sbc::absolute,
     }, /* 0xE1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sep,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::Immediate,
+        function: flags::sep,
     }, /* 0xE2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelative,
+        function: sbc::absolute,
     }, /* 0xE3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cpx,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: compare::cpx,
     }, /* 0xE4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: sbc::absolute,
     }, /* 0xE5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Inc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPage,
+        function: increment::inc,
     }, /* 0xE6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLong,
+        function: sbc::absolute,
     }, /* 0xE7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Inx,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: increment::inx,
     }, /* 0xE8 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Variable,
+        mode: CpuAddressMode::Immediate,
+        function: sbc::immediate,
     }, /* 0xE9 */
     CpuInstruction {
         opcode: CpuOpcode::Nop,
         width: CpuParamWidth::None,
+        mode: CpuAddressMode::Implied,
         function: misc::nop,
     }, /* 0xEA Nop */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Xba,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: transfer::xba,
     }, /* 0xEB */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Cpx,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: compare::cpx,
     }, /* 0xEC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: sbc::absolute,
     }, /* 0xED */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Inc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Absolute,
+        function: increment::inc,
     }, /* 0xEE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLong,
+        function: sbc::absolute,
     }, /* 0xEF */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Beq,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::ProgramCounterRelative,
+        function: branch::beq,
     }, /* 0xF0 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectIndexedY,
+        function: sbc::absolute,
     }, /* 0xF1 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirect,
+        function: sbc::absolute,
     }, /* 0xF2 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::StackRelativeIndirectIndexedY,
+        function: sbc::absolute,
     }, /* 0xF3 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Pea,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::Immediate,
+        function: stack::pea,
     }, /* 0xF4 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: sbc::absolute,
     }, /* 0xF5 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Inc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndexedX,
+        function: increment::inc,
     }, /* 0xF6 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Byte,
+        mode: CpuAddressMode::DirectPageIndirectLongIndexedY,
+        function: sbc::absolute,
     }, /* 0xF7 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Sed,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::sed,
     }, /* 0xF8 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedY,
+        function: sbc::absolute,
     }, /* 0xF9 */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Plx,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Stack,
+        function: stack::plx,
     }, /* 0xFA */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
+        opcode: CpuOpcode::Xce,
         width: CpuParamWidth::None,
-        function: misc::stp,
+        mode: CpuAddressMode::Implied,
+        function: flags::xce,
     }, /* 0xFB */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Jsr,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedIndirect,
+        function: jump::jsr,
     }, /* 0xFC */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: sbc::absolute,
     }, /* 0xFD */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Inc,
+        width: CpuParamWidth::Word,
+        mode: CpuAddressMode::AbsoluteIndexedX,
+        function: increment::inc,
     }, /* 0xFE */
     CpuInstruction {
-        opcode: CpuOpcode::Stp,
-        width: CpuParamWidth::None,
-        function: misc::stp,
+        opcode: CpuOpcode::Sbc,
+        width: CpuParamWidth::Long,
+        mode: CpuAddressMode::AbsoluteLongIndexedX,
+        function: sbc::absolute,
     }, /* 0xFF */
 ];
 
+/**************************************** File Scope Functions **********************************************************/
+
+/// Number of bytes in an operand of a given width.
+/// # Parameters:
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the operand.
+/// # Returns:
+///     - 1 or 2.
+const fn operand_bytes(width: bool) -> u8 {
+    match width {
+        registers::REGISTER_MODE_8_BIT => 1,
+        REGISTER_MODE_16_BIT => 2,
+    }
+}
+
 /**************************************** Public Functions **************************************************************/
 
 /// Fetch an instruction from memory.
@@ -1352,9 +1931,9 @@ pub(crate) const INSTRUCTION_MAP: [CpuInstruction; NUM_INSTRUCTIONS] = [
 ///     - `memory`: Pointer to memory object to read data from.
 /// # Returns
 ///     - `CpuInstruction`:     Decoded CPU instruction from the table.
-pub(super) fn fetch_and_decode(cpu: &mut CpuState, memory: &memory::Memory) -> CpuInstruction {
+pub(super) fn fetch_and_decode(cpu: &mut CpuState, memory: &mut memory::Memory) -> CpuInstruction {
     let address = cpu.get_pc();
-    INSTRUCTION_MAP[memory.get_byte(address).unwrap() as usize]
+    INSTRUCTION_MAP[memory.read(address) as usize]
 }
 
 /// Execute an instruction.
@@ -1363,84 +1942,303 @@ pub(super) fn fetch_and_decode(cpu: &mut CpuState, memory: &memory::Memory) -> C
 ///     - `inst`:    Instruction struct containing all relevant information about an operation.
 ///     - `p_mem`:   Mutable pointer to the memory for this instance.
 /// # Returns
-///     - true:      If continuing running
-///     - false:     If a BRK or Stp has been reached.
+///     - `Ok(step)`:   The master clocks and cycles the instruction took.
+///     - `Err(error)`: If the instruction has not been implemented. The PC is left pointing at it.
 pub(super) fn execute(
     cpu: &mut CpuState, inst: CpuInstruction, memory: &mut memory::Memory,
-) -> bool {
-    let mut arg: CpuInstructionFnArguments = CpuInstructionFnArguments {
-        cpu,
-        memory,
-        bank: None,
-        param: 0,
-    };
+) -> Result<CpuStep, UnimplementedOpcodeError> {
+    let mut arg = CpuInstructionFnArguments::new(cpu, memory, inst.mode);
 
-    let mut parameter_location: usize = arg.cpu.get_pc() + INST_PARAM_OFFSET as usize;
+    let parameter_location: usize = arg.cpu.get_pc() + INST_PARAM_OFFSET as usize;
+
+    // Variable width parameters are sized by the register width before the instruction runs.
+    let width = inst.width.resolve(&arg.cpu.registers);
 
     // Prepare the parameter.
-    arg.param = match inst.width {
+    let read_word = |memory: &mut memory::Memory| {
+        u16::from_le_bytes([
+            memory.read(parameter_location),
+            memory.read(parameter_location + 1),
+        ])
+    };
+    arg.param = match width {
         CpuParamWidth::None => 0,
-        CpuParamWidth::Variable => {
-            match arg.cpu.registers.get_flag(registers::StatusFlags::AccSize) {
-                registers::REGISTER_MODE_8_BIT => arg
-                    .memory
-                    .get_byte(parameter_location)
-                    .expect("Parameter for instruction was out of bounds")
-                    as u16,
-                REGISTER_MODE_16_BIT => arg
-                    .memory
-                    .get_word(parameter_location)
-                    .expect("Parameter for instruction was out of bounds"),
-            }
-        }
-        CpuParamWidth::Byte => {
-            arg.memory
-                .get_byte(parameter_location)
-                .expect("Parameter for instruction was out of bounds") as u16
-        }
-        CpuParamWidth::Word => arg
-            .memory
-            .get_word(parameter_location)
-            .expect("Parameter for instruction was out of bounds"),
+        CpuParamWidth::Byte => arg.memory.read(parameter_location) as u16,
+        CpuParamWidth::Word => read_word(arg.memory),
         CpuParamWidth::Long => {
-            arg.bank = Some(
-                arg.memory
-                    .get_byte(parameter_location)
-                    .expect("Bank for parameter was out of bounds"),
-            );
-            parameter_location += 1;
-            arg.memory
-                .get_word(parameter_location)
-                .expect("Parameter for instruction was out of bounds")
+            // Long parameters are stored little endian, so the bank byte comes last.
+            let param = read_word(arg.memory);
+            arg.bank = Some(arg.memory.read(parameter_location + 2));
+            param
+        }
+        CpuParamWidth::Variable | CpuParamWidth::VariableIndex => {
+            unreachable!("Variable parameter widths are resolved before fetching")
         }
     };
 
-    // Call the function to execute.
-    println!(
-        "Executing {:?} ({:#04X}) parameter {:04X}",
-        inst.opcode,
-        arg.memory
-            .get_byte(arg.cpu.get_pc())
-            .expect("Failed to get PC"),
-        arg.param
-    );
+    // Increment the pc past the instruction and its parameter before running it, so that control flow instructions
+    // can set it themselves, and relative addresses are taken from the next instruction.
+    let address = arg.cpu.get_pc();
+    arg.cpu.registers.pc += width as u16;
 
-    let running = match (inst.function)(&mut arg) {
-        // TODO: Implement cycle pending
-        Some(_cycle_count) => true,
-        None => false,
-    };
+    // The opcode and parameter are fetched from the program bank, and any pointer is read before the operand.
+    arg.record_access(address, width as u8);
+    if let Some((location, bytes)) = addressing::pointer_location(arg.cpu, arg.mode, arg.param) {
+        arg.record_access(location.address, bytes);
+    }
 
-    // Calculate the PC Offset if the instruction was of variable len and then increment the pc.
-    arg.cpu.registers.pc += if inst.width == CpuParamWidth::Variable {
-        match arg.cpu.registers.get_flag(registers::StatusFlags::AccSize) {
-            registers::REGISTER_MODE_8_BIT => CpuParamWidth::Byte as u16,
-            registers::REGISTER_MODE_16_BIT => CpuParamWidth::Word as u16,
+    match (inst.function)(&mut arg) {
+        Some(cycles) => Ok(arg.step_timing(cycles)),
+        None => {
+            arg.cpu.registers.pc -= width as u16;
+            Err(UnimplementedOpcodeError {
+                opcode: arg.memory.get_byte(address).expect("Failed to get PC"),
+                address,
+            })
         }
     }
-    else {
-        inst.width as u16
-    };
+}
+
+/// Enter the handler for a hardware interrupt, before the instruction at the PC runs.
+/// # Parameters
+///     - `cpu`:    Pointer to CPU state.
+///     - `vector`: Interrupt being taken.
+///     - `memory`: Pointer to memory, for the stack.
+/// # Returns
+///     - The master clocks and cycles taken to enter the handler.
+pub(super) fn interrupt(
+    cpu: &mut CpuState, vector: romdata::ExceptionVector, memory: &mut memory::Memory,
+) -> CpuStep {
+    let mut arg = CpuInstructionFnArguments::new(cpu, memory, CpuAddressMode::Implied);
+    let cycles = interrupt::enter(&mut arg, vector);
+    arg.step_timing(cycles)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /**************************************** Test Helpers **************************************************************/
+
+    /// Build a CPU and memory with pointers laid out for every addressing mode, as used by `operand_for()`.
+    ///     - Native mode with 16-bit registers.
+    ///     - Direct page is $0100, data bank is $7E, X is $0002, Y is $0004, and S is $1FF0.
+    ///     - $000110: long pointer to $7F2000.
+    ///     - $000122: pointer to $3000.
+    ///     - $001FF3: pointer to $4000.
+    pub fn setup_addressing_test() -> (CpuState, memory::Memory) {
+        let mut cpu = CpuState::new();
+        let mut memory = memory::Memory::new();
+
+        cpu.registers.set_emulation(false);
+        cpu.registers.clear_flag(registers::StatusFlags::AccSize);
+        cpu.registers.clear_flag(registers::StatusFlags::IndexSize);
+        cpu.registers.direct_page = Wrapping(0x0100);
+        cpu.registers.data_bank = Wrapping(0x7E);
+        cpu.registers.index_x = Wrapping(0x0002);
+        cpu.registers.index_y = Wrapping(0x0004);
+        cpu.registers.stack_ptr = Wrapping(0x1FF0);
+
+        memory._put_word(0x000110, 0x2000).unwrap();
+        memory.put_byte(0x000112, 0x7F).unwrap();
+        memory._put_word(0x000122, 0x3000).unwrap();
+        memory._put_word(0x001FF3, 0x4000).unwrap();
+
+        (cpu, memory)
+    }
+
+    /// Get the operand bytes and the resolved address for an addressing mode, from the layout in
+    /// `setup_addressing_test()`.
+    /// # Parameters:
+    ///     - `mode`:   Addressing mode to get an operand for.
+    /// # Returns:
+    ///     - `(operand, address)`: The operand bytes to follow the opcode, and the address they resolve to.
+    pub fn operand_for(mode: CpuAddressMode) -> (Vec<u8>, usize) {
+        match mode {
+            CpuAddressMode::DirectPage => (vec![0x10], 0x000110),
+            CpuAddressMode::DirectPageIndexedX => (vec![0x10], 0x000112),
+            CpuAddressMode::DirectPageIndexedY => (vec![0x10], 0x000114),
+            CpuAddressMode::DirectPageIndirect => (vec![0x10], 0x7E2000),
+            CpuAddressMode::DirectPageIndirectLong => (vec![0x10], 0x7F2000),
+            CpuAddressMode::DirectPageIndexedIndirectX => (vec![0x20], 0x7E3000),
+            CpuAddressMode::DirectPageIndirectIndexedY => (vec![0x10], 0x7E2004),
+            CpuAddressMode::DirectPageIndirectLongIndexedY => (vec![0x10], 0x7F2004),
+            CpuAddressMode::Absolute => (vec![0x00, 0x50], 0x7E5000),
+            CpuAddressMode::AbsoluteIndexedX => (vec![0x00, 0x50], 0x7E5002),
+            CpuAddressMode::AbsoluteIndexedY => (vec![0x00, 0x50], 0x7E5004),
+            CpuAddressMode::AbsoluteLong => (vec![0x00, 0x50, 0x7F], 0x7F5000),
+            CpuAddressMode::AbsoluteLongIndexedX => (vec![0x00, 0x50, 0x7F], 0x7F5002),
+            CpuAddressMode::StackRelative => (vec![0x03], 0x001FF3),
+            CpuAddressMode::StackRelativeIndirectIndexedY => (vec![0x03], 0x7E4004),
+            _ => panic!("No test operand for {:?}", mode),
+        }
+    }
+
+    /// Place an instruction at $80:8000 and run it.
+    /// # Parameters:
+    ///     - `cpu`:        CPU to run the instruction on.
+    ///     - `memory`:     Memory to place the instruction in.
+    ///     - `opcode`:     Opcode of the instruction.
+    ///     - `operand`:    Bytes of the operand which follow the opcode.
+    pub fn run_opcode(cpu: &mut CpuState, memory: &mut memory::Memory, opcode: u8, operand: &[u8]) {
+        let pc = memory::compose_address(0x80, 0x8000);
+        memory.put_byte(pc, opcode).unwrap();
+        for (offset, byte) in operand.iter().enumerate() {
+            memory.put_byte(pc + 1 + offset, *byte).unwrap();
+        }
+
+        cpu.registers.program_bank = Wrapping(0x80);
+        cpu.registers.pc = Wrapping(0x8000);
+        cpu.step(memory).unwrap();
+    }
+
+    /**************************************** Tests *********************************************************************/
+
+    #[test]
+    fn test_param_width_resolve() {
+        let test_cases = vec![
+            // width, AccSize set, IndexSize set, expected
+            (CpuParamWidth::Variable, false, false, CpuParamWidth::Word),
+            (CpuParamWidth::Variable, true, false, CpuParamWidth::Byte),
+            (CpuParamWidth::Variable, false, true, CpuParamWidth::Word),
+            (
+                CpuParamWidth::VariableIndex,
+                false,
+                false,
+                CpuParamWidth::Word,
+            ),
+            (
+                CpuParamWidth::VariableIndex,
+                true,
+                false,
+                CpuParamWidth::Word,
+            ),
+            (
+                CpuParamWidth::VariableIndex,
+                false,
+                true,
+                CpuParamWidth::Byte,
+            ),
+            (CpuParamWidth::Long, true, true, CpuParamWidth::Long),
+            (CpuParamWidth::None, true, true, CpuParamWidth::None),
+        ];
+
+        for (width, acc_8bit, index_8bit, expected) in test_cases {
+            println!("Test Case: {:?} m={} x={}", width, acc_8bit, index_8bit);
+            let mut registers = registers::CpuRegisters::new();
+            registers.set_emulation(false);
+            registers.clear_flag(registers::StatusFlags::AccSize);
+            registers.clear_flag(registers::StatusFlags::IndexSize);
+            if acc_8bit {
+                registers.set_flag(registers::StatusFlags::AccSize);
+            }
+            if index_8bit {
+                registers.set_flag(registers::StatusFlags::IndexSize);
+            }
+            assert_eq!(width.resolve(&registers), expected);
+        }
+    }
+
+    #[test]
+    fn test_immediate_pc_increment() {
+        let test_cases = vec![
+            // opcode, AccSize set, IndexSize set, expected PC
+            (0xA9, false, true, 0x8003),
+            (0xA9, true, false, 0x8002),
+            (0xA2, true, false, 0x8003),
+            (0xA2, false, true, 0x8002),
+            (0xA0, true, false, 0x8003),
+            (0xA0, false, true, 0x8002),
+        ];
+
+        for (opcode, acc_8bit, index_8bit, expected) in test_cases {
+            println!("Test Case: {:#04X} m={} x={}", opcode, acc_8bit, index_8bit);
+            let (mut cpu, mut memory) = setup_addressing_test();
+            if acc_8bit {
+                cpu.registers.set_flag(registers::StatusFlags::AccSize);
+            }
+            if index_8bit {
+                cpu.registers.set_flag(registers::StatusFlags::IndexSize);
+            }
+            run_opcode(&mut cpu, &mut memory, opcode, &[0x01, 0x01]);
+            assert_eq!(cpu.registers.pc.0, expected);
+        }
+    }
+
+    #[test]
+    fn test_master_clocks() {
+        let test_cases = vec![
+            // direct page, fast rom, opcode, operand, master clocks, cycles
+            // NOP: an 8 clock opcode fetch from SlowROM, and a 6 clock internal cycle.
+            (0x0100, false, 0xEA, vec![], 14, 2),
+            (0x0100, true, 0xEA, vec![], 12, 2),
+            // LDA dp: two operand bytes fetched, and a 16-bit read from WRAM.
+            (0x0100, false, 0xA5, vec![0x10], 32, 4),
+            // An unaligned direct page adds an internal cycle.
+            (0x0101, false, 0xA5, vec![0x10], 38, 5),
+            // LDA long from the fast I/O region.
+            (0x0100, false, 0xAF, vec![0x00, 0x21, 0x00], 44, 6),
+            (0x0100, true, 0xAF, vec![0x00, 0x21, 0x00], 36, 6),
+            // LDA (dp): the pointer is read from WRAM, and the operand from $7E:2000.
+            (0x0100, false, 0xB2, vec![0x10], 48, 6),
+            // BRA: a taken branch adds an internal cycle.
+            (0x0100, false, 0x80, vec![0x10], 22, 3),
+            // WDM: the opcode and its signature byte are both fetched from SlowROM.
+            (0x0100, false, 0x42, vec![0x00], 16, 2),
+        ];
+
+        for (direct_page, fast_rom, opcode, operand, clocks, cycles) in test_cases {
+            println!(
+                "Test Case: {:#04X} {:02X?} D: {:#06X} FastROM: {}",
+                opcode, operand, direct_page, fast_rom
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.direct_page = Wrapping(direct_page);
+            test_mem
+                .put_byte(timing::MEMSEL_ADDR, fast_rom as u8)
+                .unwrap();
+
+            test_mem.put_byte(0x808000, opcode).unwrap();
+            for (offset, byte) in operand.iter().enumerate() {
+                test_mem.put_byte(0x808001 + offset, *byte).unwrap();
+            }
+            test_cpu.registers.program_bank = Wrapping(0x80);
+            test_cpu.registers.pc = Wrapping(0x8000);
 
-    running
+            assert_eq!(test_cpu.step(&mut test_mem), Ok(CpuStep { clocks, cycles }));
+        }
+    }
+
+    #[test]
+    fn test_wdm() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        let flags = test_cpu.registers.get_flag_vals();
+
+        // WDM skips its signature byte and does nothing else.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x42, &[0xA9]);
+        assert_eq!(test_cpu.get_pc(), 0x808002);
+        assert_eq!(test_cpu.registers.get_flag_vals(), flags);
+    }
+
+    #[test]
+    fn test_unimplemented_instruction() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_mem.put_byte(0x808000, 0xEA).unwrap();
+        test_cpu.registers.program_bank = Wrapping(0x80);
+        test_cpu.registers.pc = Wrapping(0x8000);
+
+        // An instruction which cannot run is reported, and the PC stays on the opcode.
+        let instruction = CpuInstruction {
+            opcode: CpuOpcode::Nop,
+            width: CpuParamWidth::Byte,
+            mode: CpuAddressMode::Implied,
+            function: |_| None,
+        };
+        let error = execute(&mut test_cpu, instruction, &mut test_mem).unwrap_err();
+        assert_eq!(error.to_string(), "unimplemented opcode $EA at $808000");
+        assert_eq!(test_cpu.get_pc(), 0x808000);
+        assert_eq!(test_cpu.run_state, CpuRunState::Running);
+    }
 }
@@ -1,7 +1,7 @@
 use std::num::Wrapping;
 
 use super::{
-    memory::{self},
+    addressing::{self},
     registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
     CpuInstructionFnArguments, CpuState,
 };
@@ -10,97 +10,83 @@ use super::{
 /**************************************** Struct and Type definitions ***************************************************/
 /**************************************** File Scope Functions **********************************************************/
 
-/// Perform an addition with carry and set the flags accordingly.
+/**************************************** Public Functions **************************************************************/
+
+/// Add a value and the carry flag to the accumulator, or subtract it with borrow, and set the flags accordingly.
+/// Works in binary or decimal depending on the decimal flag, and in 8 or 16 bits depending on the accumulator width.
+///
+/// The sum is built up one nibble at a time, in the same way as the 65816 does. In decimal mode each nibble is adjusted
+/// back into the range 0-9 before carrying into the next, and the overflow flag is taken from the top nibble before it
+/// is adjusted.
+/// https://snes.nesdev.org/wiki/65C816_Decimal_mode
+///
 /// Parameters:
-///     - `cpu`: State of the CPU to modify.
-///     - `param`: The value to add to the ACC value.
-fn perform_add(cpu: &mut CpuState, mut param: u16) {
-    // If the carry flag is already set then carry it forward
-    if cpu.registers.get_flag(StatusFlags::Carry) {
-        param += 1;
-    }
+///     - `cpu`:        State of the CPU to modify.
+///     - `param`:      The value to add to, or subtract from, the ACC value.
+///     - `subtract`:   `true` for SBC, `false` for ADC.
+pub(super) fn add_with_carry(cpu: &mut CpuState, param: u16, subtract: bool) {
+    let width = cpu.registers.get_flag(StatusFlags::AccSize);
+    let decimal = cpu.registers.get_flag(StatusFlags::Decimal);
+    let (mask, sign, nibbles): (i32, i32, u32) = match width {
+        REGISTER_MODE_8_BIT => (0x00FF, 0x0080, 2),
+        REGISTER_MODE_16_BIT => (0xFFFF, 0x8000, 4),
+    };
 
-    // If the operation is in 8-bit mode, then perform all of the math in a u8 context.
-    match cpu.registers.get_flag(StatusFlags::AccSize) {
-        REGISTER_MODE_8_BIT => {
-            let acc_value: u8 = (cpu.registers.acc.0 & 0x00FF) as u8;
-            let param_value: u8 = (param & 0x00FF) as u8;
-
-            // Check if an unsigned overflow occurred. If it did, then set the carry bit.
-            match acc_value.checked_add(param_value) {
-                Some(_value) => {
-                    cpu.registers.clear_flag(StatusFlags::Carry);
-                }
-                None => {
-                    cpu.registers.set_flag(StatusFlags::Carry);
-                }
-            }
+    let acc = cpu.registers.acc.0 as i32 & mask;
+    // Subtraction is addition of the one's complement, where the carry acts as an inverted borrow.
+    let value = match subtract {
+        true => !(param as i32) & mask,
+        false => param as i32 & mask,
+    };
 
-            // Check if a signed overflow occurred. If it did, then set the overflow bit.
-            // http://www.6502.org/tutorials/vflag.html
-            match (acc_value as i8).checked_add(param_value as i8) {
-                Some(_value) => {
-                    cpu.registers.clear_flag(StatusFlags::Overflow);
-                }
-                None => {
-                    cpu.registers.set_flag(StatusFlags::Overflow);
-                }
-            }
+    let mut carry = cpu.registers.get_flag(StatusFlags::Carry) as i32;
+    let mut result: i32 = 0;
+    let mut overflow = false;
 
-            match acc_value.wrapping_add(param_value) as i8 >= 0 {
-                true => {
-                    cpu.registers.clear_flag(StatusFlags::Negative);
-                }
-                false => {
-                    cpu.registers.set_flag(StatusFlags::Negative);
-                }
-            }
+    for nibble in 0..nibbles {
+        let shift = nibble * 4;
+        let nibble_mask = 0x0F << shift;
+        let lower_mask = (1 << shift) - 1;
+
+        result =
+            (acc & nibble_mask) + (value & nibble_mask) + (carry << shift) + (result & lower_mask);
 
-            cpu.registers.acc = Wrapping(acc_value.wrapping_add(param_value) as u16);
+        if nibble == nibbles - 1 {
+            overflow = (!(acc ^ value) & (acc ^ result) & sign) != 0;
         }
-        REGISTER_MODE_16_BIT => {
-            // Check if an unsigned overflow occurred and set the carry bit if needed
-            match cpu.registers.acc.0.checked_add(param) {
-                Some(_value) => {
-                    cpu.registers.clear_flag(StatusFlags::Carry);
-                }
-                None => {
-                    cpu.registers.set_flag(StatusFlags::Carry);
-                }
-            }
 
-            // Check if a signed overflow occurred and set the carry bit if needed.
-            match (cpu.registers.acc.0 as i16).checked_add(param as i16) {
-                Some(_value) => {
-                    cpu.registers.clear_flag(StatusFlags::Overflow);
-                }
-                None => {
-                    cpu.registers.set_flag(StatusFlags::Overflow);
-                }
+        if decimal {
+            if subtract && result < (0x10 << shift) {
+                result -= 0x06 << shift;
             }
-
-            match cpu.registers.acc.0.wrapping_add(param) as i16 >= 0 {
-                true => {
-                    cpu.registers.clear_flag(StatusFlags::Negative);
-                }
-                false => {
-                    cpu.registers.set_flag(StatusFlags::Negative);
-                }
+            else if !subtract && result >= (0x0A << shift) {
+                result += 0x06 << shift;
             }
-
-            cpu.registers.acc += Wrapping(param);
         }
+
+        carry = (result >= (0x10 << shift)) as i32;
     }
 
-    // Update the flags that will be the same.
+    let result = (result & mask) as u16;
 
-    match cpu.registers.acc.0 {
-        0 => cpu.registers.set_flag(StatusFlags::Zero),
-        _ => cpu.registers.clear_flag(StatusFlags::Zero),
+    match carry {
+        0 => cpu.registers.clear_flag(StatusFlags::Carry),
+        _ => cpu.registers.set_flag(StatusFlags::Carry),
     }
-}
 
-/**************************************** Public Functions **************************************************************/
+    match overflow {
+        true => cpu.registers.set_flag(StatusFlags::Overflow),
+        false => cpu.registers.clear_flag(StatusFlags::Overflow),
+    }
+
+    cpu.registers.set_nz_flags(result, width);
+
+    // In 8-bit mode, the high byte of the accumulator (B) is left alone.
+    cpu.registers.acc = match width {
+        REGISTER_MODE_8_BIT => Wrapping((cpu.registers.acc.0 & 0xFF00) | result),
+        REGISTER_MODE_16_BIT => Wrapping(result),
+    };
+}
 
 /// ADC Immediate
 /// Syntax: ADC #const
@@ -109,7 +95,7 @@ fn perform_add(cpu: &mut CpuState, mut param: u16) {
 /// Flags affected: nv----zc
 pub(super) fn immediate(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
     // Add the value.
-    perform_add(arg.cpu, arg.param);
+    add_with_carry(arg.cpu, arg.param, false);
 
     let mut cycles_to_pend: u8 = 0;
 
@@ -122,48 +108,25 @@ pub(super) fn immediate(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
     Some(cycles_to_pend)
 }
 
-/// ADC absolute
-/// Opcode: 0x6D for short, 0x6F for long
-/// Bytes: 3 for short, 4 for long
+/// ADC from memory
+/// Syntax: ADC dp, ADC addr, ADC long, and their indexed and indirect forms.
+/// Opcodes: 0x61, 0x63, 0x65, 0x67, 0x6D, 0x6F, 0x71, 0x72, 0x73, 0x75, 0x77, 0x79, 0x7D, 0x7F
+/// Bytes: 2-4 depending on addressing mode.
 /// Flags Affected: nv----zc
 pub(super) fn absolute(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
-    // If the instruction included a bank address, This is a 4-byte inst and is accessing a value
-    // with a full address.
-    //
-    // If the instruction didn't include a bank address, this is a 3-byte inst and is accessing a
-    // value with a full address.
-    let value = match arg.bank {
-        Some(bank) => {
-            let memaddr = memory::compose_address(bank, arg.param);
-            arg.memory
-                .get_word(memaddr)
-                .expect("Parameter was out of bounds in memory.")
-        }
-        None => {
-            let memaddr = memory::compose_address(arg.cpu.registers.data_bank.0, arg.param);
-            arg.memory
-                .get_word(memaddr)
-                .expect("Parameter was out of bounds in memory.")
-        }
-    };
+    // The resolver takes care of composing the address from the data bank, or the bank byte of a
+    // 4-byte instruction.
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, address) = arg.read_operand(width);
 
-    perform_add(arg.cpu, value);
+    add_with_carry(arg.cpu, value, false);
 
-    let mut cycles_to_pend = 0;
+    let mut cycles_to_pend = addressing::read_cycles(arg.cpu, arg.mode, address);
 
-    if value == 0 {
+    if width == REGISTER_MODE_16_BIT {
         cycles_to_pend += 1;
     }
 
-    match arg.bank {
-        Some(_) => {
-            cycles_to_pend += 5;
-        }
-        None => {
-            cycles_to_pend += 4;
-        }
-    }
-
     Some(cycles_to_pend)
 }
 
@@ -172,7 +135,10 @@ pub(super) fn absolute(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
 #[cfg(test)]
 mod tests {
     use crate::cpu::instructions::adc;
-    use memory::Memory;
+    use crate::memory::Memory;
+    use addressing::CpuAddressMode;
+
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
 
     use super::*;
 
@@ -197,12 +163,12 @@ mod tests {
             test_cpu.registers.set_flag(StatusFlags::AccSize);
             test_cpu.registers.acc = Wrapping(case[0]);
 
-            let mut test_arg: CpuInstructionFnArguments = CpuInstructionFnArguments {
-                cpu: &mut test_cpu,
-                memory: &mut test_memory,
-                bank: None,
-                param: case[1],
-            };
+            let mut test_arg = CpuInstructionFnArguments::new(
+                &mut test_cpu,
+                &mut test_memory,
+                CpuAddressMode::Immediate,
+            );
+            test_arg.param = case[1];
 
             println!("Test case: {:?}", case);
 
@@ -250,15 +216,16 @@ mod tests {
         for case in test_cases {
             let mut test_cpu: CpuState = CpuState::new();
             let mut test_memory: Memory = Memory::new();
+            test_cpu.registers.set_emulation(false);
             test_cpu.registers.clear_flag(StatusFlags::AccSize);
             test_cpu.registers.acc = Wrapping(case[0]);
 
-            let mut test_arg: CpuInstructionFnArguments = CpuInstructionFnArguments {
-                cpu: &mut test_cpu,
-                memory: &mut test_memory,
-                bank: None,
-                param: case[1],
-            };
+            let mut test_arg = CpuInstructionFnArguments::new(
+                &mut test_cpu,
+                &mut test_memory,
+                CpuAddressMode::Immediate,
+            );
+            test_arg.param = case[1];
 
             println!("Test case: {:?}", case);
 
@@ -291,6 +258,167 @@ mod tests {
             );
         }
     }
+
+    /// Run ADC #param in decimal mode against the accumulator, and check the result and flags.
+    /// # Parameters:
+    ///     - `case`:   acc, param, carry in, result, n, v, z, c
+    ///     - `width`:  Accumulator width to run in.
+    fn check_decimal(case: [u16; 8], width: bool) {
+        let mut test_cpu: CpuState = CpuState::new();
+        let mut test_memory: Memory = Memory::new();
+        test_cpu.registers.set_emulation(false);
+        match width {
+            REGISTER_MODE_8_BIT => test_cpu.registers.set_flag(StatusFlags::AccSize),
+            REGISTER_MODE_16_BIT => test_cpu.registers.clear_flag(StatusFlags::AccSize),
+        }
+        test_cpu.registers.set_flag(StatusFlags::Decimal);
+        if case[2] != 0 {
+            test_cpu.registers.set_flag(StatusFlags::Carry);
+        }
+        test_cpu.registers.acc = Wrapping(case[0]);
+
+        let mut test_arg = CpuInstructionFnArguments::new(
+            &mut test_cpu,
+            &mut test_memory,
+            CpuAddressMode::Immediate,
+        );
+        test_arg.param = case[1];
+
+        println!("Test case: {:04X?}", case);
+        adc::immediate(&mut test_arg);
+
+        assert_eq!(test_cpu.registers.acc, Wrapping(case[3]));
+        assert_eq!(
+            case[4],
+            test_cpu.registers.get_flag(StatusFlags::Negative) as u16
+        );
+        assert_eq!(
+            case[5],
+            test_cpu.registers.get_flag(StatusFlags::Overflow) as u16
+        );
+        assert_eq!(
+            case[6],
+            test_cpu.registers.get_flag(StatusFlags::Zero) as u16
+        );
+        assert_eq!(
+            case[7],
+            test_cpu.registers.get_flag(StatusFlags::Carry) as u16
+        );
+    }
+
+    #[test]
+    fn test_immediate_carry_in() {
+        let test_cases = vec![
+            //ACC +  B + c,  = C,    n, v, z, c
+            [0x007F, 0x007F, 1, 0x00FF, 1, 1, 0, 0],
+            [0x00FF, 0x00FF, 1, 0x00FF, 1, 0, 0, 1],
+            [0x0000, 0x00FF, 1, 0x0000, 0, 0, 1, 1],
+        ];
+
+        for case in test_cases {
+            let mut test_cpu: CpuState = CpuState::new();
+            let mut test_memory: Memory = Memory::new();
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+            test_cpu.registers.set_flag(StatusFlags::Carry);
+            test_cpu.registers.acc = Wrapping(case[0]);
+
+            let mut test_arg = CpuInstructionFnArguments::new(
+                &mut test_cpu,
+                &mut test_memory,
+                CpuAddressMode::Immediate,
+            );
+            test_arg.param = case[1];
+
+            println!("Test case: {:04X?}", case);
+            adc::immediate(&mut test_arg);
+
+            assert_eq!(test_cpu.registers.acc, Wrapping(case[3]));
+            assert_eq!(
+                case[4],
+                test_cpu.registers.get_flag(StatusFlags::Negative) as u16
+            );
+            assert_eq!(
+                case[5],
+                test_cpu.registers.get_flag(StatusFlags::Overflow) as u16
+            );
+            assert_eq!(
+                case[6],
+                test_cpu.registers.get_flag(StatusFlags::Zero) as u16
+            );
+            assert_eq!(
+                case[7],
+                test_cpu.registers.get_flag(StatusFlags::Carry) as u16
+            );
+        }
+    }
+
+    #[test]
+    fn test_immediate_decimal_8bit() {
+        let test_cases = vec![
+            //ACC +  B + c,  = C,    n, v, z, c
+            [0x0012, 0x0034, 0, 0x0046, 0, 0, 0, 0],
+            [0x0015, 0x0026, 0, 0x0041, 0, 0, 0, 0],
+            [0x0058, 0x0046, 1, 0x0005, 0, 1, 0, 1],
+            [0x0050, 0x0050, 0, 0x0000, 0, 1, 1, 1],
+            [0x0099, 0x0001, 0, 0x0000, 0, 0, 1, 1],
+            [0x0079, 0x0000, 1, 0x0080, 1, 1, 0, 0],
+            [0x0081, 0x0092, 0, 0x0073, 0, 1, 0, 1],
+            [0x0049, 0x0049, 1, 0x0099, 1, 1, 0, 0],
+        ];
+
+        for case in test_cases {
+            check_decimal(case, REGISTER_MODE_8_BIT);
+        }
+    }
+
+    #[test]
+    fn test_immediate_decimal_16bit() {
+        let test_cases = vec![
+            //ACC +  B + c,  = C,    n, v, z, c
+            [0x1234, 0x4321, 0, 0x5555, 0, 0, 0, 0],
+            [0x0999, 0x0001, 0, 0x1000, 0, 0, 0, 0],
+            [0x9999, 0x0001, 0, 0x0000, 0, 0, 1, 1],
+            [0x5000, 0x5000, 1, 0x0001, 0, 1, 0, 1],
+            [0x7999, 0x0000, 1, 0x8000, 1, 1, 0, 0],
+        ];
+
+        for case in test_cases {
+            check_decimal(case, REGISTER_MODE_16_BIT);
+        }
+    }
+
+    #[test]
+    fn test_adc_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0x61, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0x63, CpuAddressMode::StackRelative),
+            (0x65, CpuAddressMode::DirectPage),
+            (0x67, CpuAddressMode::DirectPageIndirectLong),
+            (0x6D, CpuAddressMode::Absolute),
+            (0x6F, CpuAddressMode::AbsoluteLong),
+            (0x71, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0x72, CpuAddressMode::DirectPageIndirect),
+            (0x73, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0x75, CpuAddressMode::DirectPageIndexedX),
+            (0x77, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0x79, CpuAddressMode::AbsoluteIndexedY),
+            (0x7D, CpuAddressMode::AbsoluteIndexedX),
+            (0x7F, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.acc = Wrapping(0x1111);
+            test_mem._put_word(address, 0x1234).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.acc.0, 0x2345);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+    }
 }
 
 /**************************************** Test Helpers **************************************************************/
@@ -0,0 +1,190 @@
+use super::{memory, registers::StatusFlags, CpuInstructionFnArguments};
+use std::num::Wrapping;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Length of a block move instruction: the opcode and the two bank bytes.
+const BLOCK_MOVE_INST_LEN: u16 = 3;
+
+/// Cycles taken to move each byte: the three instruction bytes, the read and write, and two internal cycles.
+const BLOCK_MOVE_CYCLES: u8 = 7;
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Move one byte of a block, and step the index registers.
+/// The instruction moves a single byte each time it executes, then rewinds the PC to itself until the accumulator
+/// wraps to $FFFF, so that interrupts can still be taken part way through a move.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `step`:   Amount to step X and Y by after the byte is moved, 1 for MVN, -1 for MVP.
+/// # Returns:
+///     - The number of cycles the move took.
+fn block_move(arg: &mut CpuInstructionFnArguments, step: Wrapping<u16>) -> u8 {
+    // The operand is encoded as the destination bank, followed by the source bank.
+    let [dest_bank, source_bank] = arg.param.to_le_bytes();
+
+    let source = memory::compose_address(source_bank, arg.cpu.registers.index_x.0);
+    let dest = memory::compose_address(dest_bank, arg.cpu.registers.index_y.0);
+    let value = arg.memory.read(source);
+    arg.record_access(source, 1);
+    arg.memory.write(dest, value);
+    arg.record_access(dest, 1);
+
+    // The data bank is left pointing at the destination.
+    let registers = &mut arg.cpu.registers;
+    registers.data_bank = Wrapping(dest_bank);
+
+    registers.index_x += step;
+    registers.index_y += step;
+    if registers.get_flag(StatusFlags::IndexSize) {
+        registers.index_x &= 0x00FF;
+        registers.index_y &= 0x00FF;
+    }
+
+    // The count is always the full 16-bit accumulator, whatever its width.
+    registers.acc -= 1;
+    if registers.acc.0 != 0xFFFF {
+        registers.pc -= BLOCK_MOVE_INST_LEN;
+    }
+
+    BLOCK_MOVE_CYCLES
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// MVN Block Move Negative
+/// Moves C + 1 bytes from srcbk:X to destbk:Y, incrementing X and Y after each byte.
+/// Syntax: MVN srcbk,destbk
+/// Opcodes: 0x54
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn mvn(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(block_move(arg, Wrapping(1)))
+}
+
+/// MVP Block Move Positive
+/// Moves C + 1 bytes from srcbk:X to destbk:Y, decrementing X and Y after each byte.
+/// Syntax: MVP srcbk,destbk
+/// Opcodes: 0x44
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn mvp(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(block_move(arg, Wrapping(0xFFFF)))
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::super::CpuState;
+    use super::*;
+
+    /// Run a block move until it finishes.
+    /// # Returns:
+    ///     - The number of times the instruction executed.
+    fn run_block_move(
+        cpu: &mut CpuState, memory: &mut memory::Memory, opcode: u8, operand: &[u8],
+    ) -> usize {
+        let mut count = 1;
+        run_opcode(cpu, memory, opcode, operand);
+        while cpu.get_pc() == 0x808000 {
+            cpu.step(memory).unwrap();
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_mvn() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        for (offset, byte) in [0x11, 0x22, 0x33, 0x44].iter().enumerate() {
+            test_mem.put_byte(0x7F1000 + offset, *byte).unwrap();
+        }
+        test_cpu.registers.acc = Wrapping(0x0002);
+        test_cpu.registers.index_x = Wrapping(0x1000);
+        test_cpu.registers.index_y = Wrapping(0x2000);
+
+        // MVN $7F,$7E is encoded with the destination bank first.
+        assert_eq!(
+            run_block_move(&mut test_cpu, &mut test_mem, 0x54, &[0x7E, 0x7F]),
+            3
+        );
+        assert_eq!(test_mem.get_byte(0x7E2000).unwrap(), 0x11);
+        assert_eq!(test_mem.get_byte(0x7E2001).unwrap(), 0x22);
+        assert_eq!(test_mem.get_byte(0x7E2002).unwrap(), 0x33);
+        assert_eq!(test_mem.get_byte(0x7E2003).unwrap(), 0x00);
+
+        assert_eq!(test_cpu.registers.acc.0, 0xFFFF);
+        assert_eq!(test_cpu.registers.index_x.0, 0x1003);
+        assert_eq!(test_cpu.registers.index_y.0, 0x2003);
+        assert_eq!(test_cpu.registers.data_bank.0, 0x7E);
+        assert_eq!(test_cpu.get_pc(), 0x808003);
+    }
+
+    #[test]
+    fn test_mvp() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        for (offset, byte) in [0x11, 0x22, 0x33].iter().enumerate() {
+            test_mem.put_byte(0x7E1000 + offset, *byte).unwrap();
+        }
+        test_cpu.registers.acc = Wrapping(0x0002);
+        test_cpu.registers.index_x = Wrapping(0x1002);
+        test_cpu.registers.index_y = Wrapping(0x1003);
+        test_cpu.registers.data_bank = Wrapping(0x00);
+
+        // Overlapping moves to a higher address work when copying from the end.
+        assert_eq!(
+            run_block_move(&mut test_cpu, &mut test_mem, 0x44, &[0x7E, 0x7E]),
+            3
+        );
+        assert_eq!(test_mem.get_byte(0x7E1000).unwrap(), 0x11);
+        assert_eq!(test_mem.get_byte(0x7E1001).unwrap(), 0x11);
+        assert_eq!(test_mem.get_byte(0x7E1002).unwrap(), 0x22);
+        assert_eq!(test_mem.get_byte(0x7E1003).unwrap(), 0x33);
+
+        assert_eq!(test_cpu.registers.acc.0, 0xFFFF);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0FFF);
+        assert_eq!(test_cpu.registers.index_y.0, 0x1000);
+        assert_eq!(test_cpu.registers.data_bank.0, 0x7E);
+        assert_eq!(test_cpu.get_pc(), 0x808003);
+    }
+
+    #[test]
+    fn test_block_move_8bit_index() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.set_flag(StatusFlags::IndexSize);
+        test_cpu.registers.set_flag(StatusFlags::AccSize);
+        test_mem.put_byte(0x7F00FF, 0xAA).unwrap();
+        test_mem.put_byte(0x7F0000, 0xBB).unwrap();
+
+        // The count uses the hidden B accumulator too, and X and Y wrap within a page.
+        test_cpu.registers.acc = Wrapping(0x0001);
+        test_cpu.registers.index_x = Wrapping(0x00FF);
+        test_cpu.registers.index_y = Wrapping(0x0010);
+        assert_eq!(
+            run_block_move(&mut test_cpu, &mut test_mem, 0x54, &[0x7E, 0x7F]),
+            2
+        );
+        assert_eq!(test_mem.get_byte(0x7E0010).unwrap(), 0xAA);
+        assert_eq!(test_mem.get_byte(0x7E0011).unwrap(), 0xBB);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0001);
+        assert_eq!(test_cpu.registers.acc.0, 0xFFFF);
+    }
+
+    #[test]
+    fn test_block_move_master_clocks() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_mem.put_byte(0x808000, 0x54).unwrap();
+        test_mem.put_byte(0x808001, 0x7E).unwrap();
+        test_mem.put_byte(0x808002, 0x7F).unwrap();
+        test_cpu.registers.acc = Wrapping(0x0000);
+        test_cpu.registers.program_bank = Wrapping(0x80);
+        test_cpu.registers.pc = Wrapping(0x8000);
+
+        // Three SlowROM fetches, a read and a write to WRAM, and two internal cycles.
+        let step = test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(step.clocks, 3 * 8 + 2 * 8 + 2 * 6);
+        assert_eq!(step.cycles, 7);
+    }
+}
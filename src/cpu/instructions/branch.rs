@@ -1 +1,230 @@
+use std::num::Wrapping;
 
+use super::{registers::StatusFlags, CpuInstructionFnArguments};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Take a relative branch if a condition is met.
+/// Taking a branch costs an extra cycle, and in emulation mode crossing into another page costs one more.
+/// # Parameters:
+///     - `arg`:        Arguments of the instruction being executed.
+///     - `condition`:  Whether to take the branch.
+/// # Returns:
+///     - The number of cycles the branch took.
+fn branch(arg: &mut CpuInstructionFnArguments, condition: bool) -> u8 {
+    let mut cycles = 2;
+
+    if condition {
+        let target = arg
+            .effective_address()
+            .expect("Branch was mapped to an addressing mode without an address.");
+        cycles += 1;
+        if arg.cpu.registers.emulation && target.page_crossed {
+            cycles += 1;
+        }
+        arg.cpu.registers.pc = Wrapping(target.address as u16);
+    }
+
+    cycles
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// BCC Branch if Carry Clear
+/// Syntax: BCC nearlabel
+/// Opcodes: 0x90
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bcc(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = !arg.cpu.registers.get_flag(StatusFlags::Carry);
+    Some(branch(arg, condition))
+}
+
+/// BCS Branch if Carry Set
+/// Syntax: BCS nearlabel
+/// Opcodes: 0xB0
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bcs(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = arg.cpu.registers.get_flag(StatusFlags::Carry);
+    Some(branch(arg, condition))
+}
+
+/// BEQ Branch if Equal
+/// Syntax: BEQ nearlabel
+/// Opcodes: 0xF0
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn beq(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = arg.cpu.registers.get_flag(StatusFlags::Zero);
+    Some(branch(arg, condition))
+}
+
+/// BNE Branch if Not Equal
+/// Syntax: BNE nearlabel
+/// Opcodes: 0xD0
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bne(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = !arg.cpu.registers.get_flag(StatusFlags::Zero);
+    Some(branch(arg, condition))
+}
+
+/// BMI Branch if Minus
+/// Syntax: BMI nearlabel
+/// Opcodes: 0x30
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bmi(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = arg.cpu.registers.get_flag(StatusFlags::Negative);
+    Some(branch(arg, condition))
+}
+
+/// BPL Branch if Plus
+/// Syntax: BPL nearlabel
+/// Opcodes: 0x10
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bpl(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = !arg.cpu.registers.get_flag(StatusFlags::Negative);
+    Some(branch(arg, condition))
+}
+
+/// BVC Branch if Overflow Clear
+/// Syntax: BVC nearlabel
+/// Opcodes: 0x50
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bvc(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = !arg.cpu.registers.get_flag(StatusFlags::Overflow);
+    Some(branch(arg, condition))
+}
+
+/// BVS Branch if Overflow Set
+/// Syntax: BVS nearlabel
+/// Opcodes: 0x70
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bvs(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let condition = arg.cpu.registers.get_flag(StatusFlags::Overflow);
+    Some(branch(arg, condition))
+}
+
+/// BRA Branch Always
+/// Syntax: BRA nearlabel
+/// Opcodes: 0x80
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn bra(arg: &mut CpuInstructionFnArguments) -> Option<u8> { Some(branch(arg, true)) }
+
+/// BRL Branch Long Always
+/// Syntax: BRL label
+/// Opcodes: 0x82
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn brl(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let target = arg
+        .effective_address()
+        .expect("Branch was mapped to an addressing mode without an address.");
+    arg.cpu.registers.pc = Wrapping(target.address as u16);
+    Some(4)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_conditional_branches() {
+        let test_cases = vec![
+            // opcode, flag, branch when set
+            (0x90, StatusFlags::Carry, false),
+            (0xB0, StatusFlags::Carry, true),
+            (0xF0, StatusFlags::Zero, true),
+            (0xD0, StatusFlags::Zero, false),
+            (0x30, StatusFlags::Negative, true),
+            (0x10, StatusFlags::Negative, false),
+            (0x50, StatusFlags::Overflow, false),
+            (0x70, StatusFlags::Overflow, true),
+        ];
+
+        for (opcode, flag, when_set) in test_cases {
+            for set in [false, true] {
+                println!("Test Case: {:#04X} {:?} set: {}", opcode, flag, set);
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                match set {
+                    true => test_cpu.registers.set_flag(flag),
+                    false => test_cpu.registers.clear_flag(flag),
+                }
+
+                run_opcode(&mut test_cpu, &mut test_mem, opcode, &[0x10]);
+                let expected = match set == when_set {
+                    true => 0x8012,
+                    false => 0x8002,
+                };
+                assert_eq!(test_cpu.registers.pc.0, expected);
+                assert_eq!(test_cpu.registers.program_bank.0, 0x80);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bra_brl() {
+        let test_cases = vec![
+            // opcode, operand, target
+            (0x80, vec![0x7F], 0x8081),
+            (0x80, vec![0xFE], 0x8000),
+            (0x80, vec![0x80], 0x7F82),
+            (0x82, vec![0x00, 0x10], 0x9003),
+            (0x82, vec![0xFD, 0xFF], 0x8000),
+            // BRL wraps within the program bank.
+            (0x82, vec![0x00, 0x80], 0x0003),
+        ];
+
+        for (opcode, operand, target) in test_cases {
+            println!("Test Case: {:#04X} {:02X?}", opcode, operand);
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.pc.0, target);
+            assert_eq!(test_cpu.registers.program_bank.0, 0x80);
+        }
+    }
+
+    #[test]
+    fn test_branch_cycles() {
+        let test_cases = vec![
+            // emulation, zero set, offset, cycles
+            (false, false, 0x10, 2),
+            (false, true, 0x10, 3),
+            (false, true, 0x80, 3),
+            (true, false, 0x80, 2),
+            (true, true, 0x10, 3),
+            // In emulation mode, branching into another page takes an extra cycle.
+            (true, true, 0x80, 4),
+        ];
+
+        for (emulation, zero, offset, cycles) in test_cases {
+            println!(
+                "Test Case: e: {} z: {} offset: {:#04X}",
+                emulation, zero, offset
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_emulation(emulation);
+            if zero {
+                test_cpu.registers.set_flag(StatusFlags::Zero);
+            }
+            test_cpu.registers.pc = Wrapping(0x8002);
+
+            let mut arg = CpuInstructionFnArguments::new(
+                &mut test_cpu,
+                &mut test_mem,
+                super::super::CpuAddressMode::ProgramCounterRelative,
+            );
+            arg.param = offset;
+            assert_eq!(beq(&mut arg), Some(cycles));
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use super::{
+    addressing,
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Compare a register against the operand of a compare instruction, and set the flags to match.
+/// The operand is subtracted from the register without borrow, and the result is discarded.
+/// # Parameters:
+///     - `arg`:        Arguments of the instruction being executed.
+///     - `register`:   Value of the register to compare.
+///     - `width`:      `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the register.
+/// # Returns:
+///     - The number of cycles the compare took.
+fn compare(arg: &mut CpuInstructionFnArguments, register: u16, width: bool) -> u8 {
+    let (value, address) = arg.read_operand(width);
+    let register = match width {
+        REGISTER_MODE_8_BIT => register & 0x00FF,
+        REGISTER_MODE_16_BIT => register,
+    };
+
+    // Carry is set when no borrow was needed.
+    match register >= value {
+        true => arg.cpu.registers.set_flag(StatusFlags::Carry),
+        false => arg.cpu.registers.clear_flag(StatusFlags::Carry),
+    }
+    arg.cpu
+        .registers
+        .set_nz_flags(register.wrapping_sub(value), width);
+
+    let mut cycles = addressing::read_cycles(arg.cpu, arg.mode, address);
+    if width == REGISTER_MODE_16_BIT {
+        cycles += 1;
+    }
+
+    cycles
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// CMP Compare Accumulator with Memory
+/// Syntax: CMP #const, CMP dp, CMP addr, CMP long, and their indexed and indirect forms.
+/// Opcodes: 0xC1, 0xC3, 0xC5, 0xC7, 0xC9, 0xCD, 0xCF, 0xD1, 0xD2, 0xD3, 0xD5, 0xD7, 0xD9, 0xDD, 0xDF
+/// Bytes: 2-4 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----zc
+pub(super) fn cmp(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let register = arg.cpu.registers.acc.0;
+    Some(compare(arg, register, width))
+}
+
+/// CPX Compare Index Register X with Memory
+/// Syntax: CPX #const, CPX dp, CPX addr
+/// Opcodes: 0xE0, 0xE4, 0xEC
+/// Bytes: 2-3 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----zc
+pub(super) fn cpx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let register = arg.cpu.registers.index_x.0;
+    Some(compare(arg, register, width))
+}
+
+/// CPY Compare Index Register Y with Memory
+/// Syntax: CPY #const, CPY dp, CPY addr
+/// Opcodes: 0xC0, 0xC4, 0xCC
+/// Bytes: 2-3 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----zc
+pub(super) fn cpy(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let register = arg.cpu.registers.index_y.0;
+    Some(compare(arg, register, width))
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_cmp_immediate() {
+        let test_cases = vec![
+            // register, operand, 8-bit, n, z, c
+            (0x0050, 0x0030, true, 0, 0, 1),
+            (0x0050, 0x0050, true, 0, 1, 1),
+            (0x0030, 0x0050, true, 1, 0, 0),
+            (0x00FF, 0x0000, true, 1, 0, 1),
+            // Only the low byte is compared in 8-bit mode.
+            (0x1250, 0x0050, true, 0, 1, 1),
+            (0x5000, 0x3000, false, 0, 0, 1),
+            (0x5000, 0x5000, false, 0, 1, 1),
+            (0x3000, 0x5000, false, 1, 0, 0),
+            (0x0001, 0x8001, false, 1, 0, 0),
+        ];
+
+        for (register, operand, narrow, n, z, c) in test_cases {
+            println!(
+                "Test Case: {:#06X} {:#06X} 8-bit: {}",
+                register, operand, narrow
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            let bytes = (operand as u16).to_le_bytes();
+            let bytes = match narrow {
+                true => {
+                    test_cpu.registers.set_flag(StatusFlags::AccSize);
+                    &bytes[..1]
+                }
+                false => &bytes[..],
+            };
+            test_cpu.registers.acc = Wrapping(register);
+
+            run_opcode(&mut test_cpu, &mut test_mem, 0xC9, bytes);
+            assert_eq!(test_cpu.registers.acc.0, register);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Carry) as u8, c);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + bytes.len() as u16);
+        }
+    }
+
+    #[test]
+    fn test_cmp_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0xC1, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0xC3, CpuAddressMode::StackRelative),
+            (0xC5, CpuAddressMode::DirectPage),
+            (0xC7, CpuAddressMode::DirectPageIndirectLong),
+            (0xCD, CpuAddressMode::Absolute),
+            (0xCF, CpuAddressMode::AbsoluteLong),
+            (0xD1, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0xD2, CpuAddressMode::DirectPageIndirect),
+            (0xD3, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0xD5, CpuAddressMode::DirectPageIndexedX),
+            (0xD7, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0xD9, CpuAddressMode::AbsoluteIndexedY),
+            (0xDD, CpuAddressMode::AbsoluteIndexedX),
+            (0xDF, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.acc = Wrapping(0x1234);
+            test_mem._put_word(address, 0x1234).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+            assert!(test_cpu.registers.get_flag(StatusFlags::Carry));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+    }
+
+    #[test]
+    fn test_cpx_cpy() {
+        let test_cases = vec![
+            // opcode, mode, compares Y
+            (0xE0, CpuAddressMode::Immediate, false),
+            (0xE4, CpuAddressMode::DirectPage, false),
+            (0xEC, CpuAddressMode::Absolute, false),
+            (0xC0, CpuAddressMode::Immediate, true),
+            (0xC4, CpuAddressMode::DirectPage, true),
+            (0xCC, CpuAddressMode::Absolute, true),
+        ];
+
+        for (opcode, mode, compares_y) in test_cases {
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            for narrow in [false, true] {
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                // The accumulator width must not matter.
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+                if narrow {
+                    test_cpu.registers.set_flag(StatusFlags::IndexSize);
+                }
+                test_cpu.registers.index_x = Wrapping(0x0080);
+                test_cpu.registers.index_y = Wrapping(0x0080);
+
+                let operand = match mode {
+                    CpuAddressMode::Immediate => match narrow {
+                        true => vec![0x81],
+                        false => vec![0x81, 0x00],
+                    },
+                    _ => {
+                        let (operand, address) = operand_for(mode);
+                        test_mem._put_word(address, 0x0081).unwrap();
+                        operand
+                    }
+                };
+                run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+
+                // $80 - $81 borrows, and is negative in both widths.
+                assert!(!test_cpu.registers.get_flag(StatusFlags::Carry));
+                assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+                assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+                assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+
+                let register = match compares_y {
+                    true => test_cpu.registers.index_y.0,
+                    false => test_cpu.registers.index_x.0,
+                };
+                assert_eq!(register, 0x0080);
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use super::{registers::StatusFlags, CpuInstructionFnArguments};
+
+/**************************************** Public Functions **************************************************************/
+
+/// CLC Clear Carry Flag
+/// Syntax: CLC
+/// Opcodes: 0x18
+/// Bytes: 1
+/// Flags affected: -------c
+pub(super) fn clc(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.clear_flag(StatusFlags::Carry);
+    Some(2)
+}
+
+/// SEC Set Carry Flag
+/// Syntax: SEC
+/// Opcodes: 0x38
+/// Bytes: 1
+/// Flags affected: -------c
+pub(super) fn sec(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.set_flag(StatusFlags::Carry);
+    Some(2)
+}
+
+/// CLI Clear Interrupt Disable Flag
+/// Syntax: CLI
+/// Opcodes: 0x58
+/// Bytes: 1
+/// Flags affected: -----i--
+pub(super) fn cli(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.clear_flag(StatusFlags::IRQDisable);
+    Some(2)
+}
+
+/// SEI Set Interrupt Disable Flag
+/// Syntax: SEI
+/// Opcodes: 0x78
+/// Bytes: 1
+/// Flags affected: -----i--
+pub(super) fn sei(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.set_flag(StatusFlags::IRQDisable);
+    Some(2)
+}
+
+/// CLD Clear Decimal Mode Flag
+/// Syntax: CLD
+/// Opcodes: 0xD8
+/// Bytes: 1
+/// Flags affected: ----d---
+pub(super) fn cld(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.clear_flag(StatusFlags::Decimal);
+    Some(2)
+}
+
+/// SED Set Decimal Mode Flag
+/// Syntax: SED
+/// Opcodes: 0xF8
+/// Bytes: 1
+/// Flags affected: ----d---
+pub(super) fn sed(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.set_flag(StatusFlags::Decimal);
+    Some(2)
+}
+
+/// CLV Clear Overflow Flag
+/// Syntax: CLV
+/// Opcodes: 0xB8
+/// Bytes: 1
+/// Flags affected: -v------
+pub(super) fn clv(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.clear_flag(StatusFlags::Overflow);
+    Some(2)
+}
+
+/// REP Reset Processor Status Bits
+/// Clears every flag whose bit is set in the operand. M and X stay set in emulation mode.
+/// Syntax: REP #const
+/// Opcodes: 0xC2
+/// Bytes: 2
+/// Flags affected: nvmxdizc
+pub(super) fn rep(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let status = arg.cpu.registers.get_flag_vals() & !(arg.param as u8);
+    arg.cpu.registers.set_flag_vals(status);
+    Some(3)
+}
+
+/// SEP Set Processor Status Bits
+/// Sets every flag whose bit is set in the operand.
+/// Syntax: SEP #const
+/// Opcodes: 0xE2
+/// Bytes: 2
+/// Flags affected: nvmxdizc
+pub(super) fn sep(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let status = arg.cpu.registers.get_flag_vals() | arg.param as u8;
+    arg.cpu.registers.set_flag_vals(status);
+    Some(3)
+}
+
+/// XCE Exchange Carry and Emulation Flags
+/// Syntax: XCE
+/// Opcodes: 0xFB
+/// Bytes: 1
+/// Flags affected: --mx---c e
+pub(super) fn xce(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let carry = arg.cpu.registers.get_flag(StatusFlags::Carry);
+
+    match arg.cpu.registers.emulation {
+        true => arg.cpu.registers.set_flag(StatusFlags::Carry),
+        false => arg.cpu.registers.clear_flag(StatusFlags::Carry),
+    }
+    arg.cpu.registers.set_emulation(carry);
+
+    Some(2)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::run_opcode;
+    use super::super::CpuState;
+    use super::*;
+    use crate::memory::Memory;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_clc_sec() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0x38, &[]);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Carry));
+        assert_eq!(test_cpu.registers.pc.0, 0x8001);
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0x18, &[]);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Carry));
+        assert_eq!(test_cpu.registers.pc.0, 0x8001);
+    }
+
+    #[test]
+    fn test_xce_boot_sequence() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        assert!(test_cpu.registers.emulation);
+
+        // CLC; XCE switches to native mode, and the old E flag ends up in carry.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x18, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xFB, &[]);
+        assert!(!test_cpu.registers.emulation);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Carry));
+
+        // The registers stay 8-bit until they are explicitly widened.
+        assert!(test_cpu.registers.get_flag(StatusFlags::AccSize));
+        assert!(test_cpu.registers.get_flag(StatusFlags::IndexSize));
+
+        // Once in native mode, the widths can change.
+        test_cpu.registers.clear_flag(StatusFlags::AccSize);
+        test_cpu.registers.clear_flag(StatusFlags::IndexSize);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::AccSize));
+        assert!(!test_cpu.registers.get_flag(StatusFlags::IndexSize));
+    }
+
+    #[test]
+    fn test_xce_enter_emulation() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        test_cpu.registers.set_emulation(false);
+        test_cpu.registers.clear_flag(StatusFlags::AccSize);
+        test_cpu.registers.clear_flag(StatusFlags::IndexSize);
+        test_cpu.registers.acc = Wrapping(0x1234);
+        test_cpu.registers.index_x = Wrapping(0x5678);
+        test_cpu.registers.index_y = Wrapping(0x9ABC);
+        test_cpu.registers.stack_ptr = Wrapping(0x1FF0);
+
+        // SEC; XCE switches to emulation mode, and the old E flag ends up in carry.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x38, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xFB, &[]);
+        assert!(test_cpu.registers.emulation);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Carry));
+
+        // Emulation mode forces 8-bit registers and a page 1 stack. B is kept.
+        assert!(test_cpu.registers.get_flag(StatusFlags::AccSize));
+        assert!(test_cpu.registers.get_flag(StatusFlags::IndexSize));
+        assert_eq!(test_cpu.registers.acc.0, 0x1234);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0078);
+        assert_eq!(test_cpu.registers.index_y.0, 0x00BC);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x01F0);
+
+        // M and X cannot be cleared while in emulation mode.
+        test_cpu.registers.clear_flag(StatusFlags::AccSize);
+        test_cpu.registers.clear_flag(StatusFlags::IndexSize);
+        assert!(test_cpu.registers.get_flag(StatusFlags::AccSize));
+        assert!(test_cpu.registers.get_flag(StatusFlags::IndexSize));
+    }
+
+    #[test]
+    fn test_xce_native_to_native() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        test_cpu.registers.set_emulation(false);
+
+        // CLC; XCE while already native leaves the CPU in native mode with carry clear.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x18, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xFB, &[]);
+        assert!(!test_cpu.registers.emulation);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Carry));
+    }
+
+    #[test]
+    fn test_flag_set_clear() {
+        let test_cases = vec![
+            // set opcode, clear opcode, flag
+            (0x78, 0x58, StatusFlags::IRQDisable),
+            (0xF8, 0xD8, StatusFlags::Decimal),
+        ];
+
+        for (set, clear, flag) in test_cases {
+            println!("Test Case: {:?}", flag);
+            let mut test_cpu = CpuState::new();
+            let mut test_mem = Memory::new();
+
+            run_opcode(&mut test_cpu, &mut test_mem, set, &[]);
+            assert!(test_cpu.registers.get_flag(flag));
+            run_opcode(&mut test_cpu, &mut test_mem, clear, &[]);
+            assert!(!test_cpu.registers.get_flag(flag));
+        }
+
+        // There is no SEV, only CLV.
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        test_cpu.registers.set_flag(StatusFlags::Overflow);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xB8, &[]);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Overflow));
+    }
+
+    #[test]
+    fn test_rep_sep() {
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        test_cpu.registers.set_emulation(false);
+
+        // REP #$30 switches to 16-bit registers, and leaves the other flags alone.
+        test_cpu.registers.set_flag(StatusFlags::Carry);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xC2, &[0x30]);
+        assert_eq!(test_cpu.registers.get_flag_vals(), 0b0000_0101);
+        assert_eq!(test_cpu.registers.pc.0, 0x8002);
+
+        // The new widths are used for the next immediate operand.
+        run_opcode(&mut test_cpu, &mut test_mem, 0xA9, &[0x34, 0x12]);
+        assert_eq!(test_cpu.registers.acc.0, 0x1234);
+        assert_eq!(test_cpu.registers.pc.0, 0x8003);
+
+        // SEP #$21 switches to an 8-bit accumulator and sets carry.
+        test_cpu.registers.clear_flag(StatusFlags::Carry);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xE2, &[0x21]);
+        assert_eq!(test_cpu.registers.get_flag_vals(), 0b0010_0101);
+        assert_eq!(test_cpu.registers.acc.0, 0x1234);
+
+        // REP cannot clear M or X in emulation mode.
+        test_cpu.registers.set_emulation(true);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xC2, &[0xFF]);
+        assert_eq!(test_cpu.registers.get_flag_vals(), 0b0011_0000);
+    }
+}
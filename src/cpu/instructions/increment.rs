@@ -0,0 +1,260 @@
+use std::num::Wrapping;
+
+use super::{
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Add `amount` to a value, wrapping within the width of the register, and keep the high byte in 8-bit mode.
+/// # Parameters:
+///     - `value`:  Value to step.
+///     - `amount`: Amount to add, `1` to increment or `0xFFFF` to decrement.
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the value.
+/// # Returns:
+///     - The stepped value.
+fn step(value: u16, amount: u16, width: bool) -> u16 {
+    match width {
+        REGISTER_MODE_8_BIT => (value & 0xFF00) | (value.wrapping_add(amount) & 0x00FF),
+        REGISTER_MODE_16_BIT => value.wrapping_add(amount),
+    }
+}
+
+/// Step the accumulator or a value in memory, and set the flags to match.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `amount`: Amount to add, `1` to increment or `0xFFFF` to decrement.
+/// # Returns:
+///     - The number of cycles the instruction took.
+fn modify(arg: &mut CpuInstructionFnArguments, amount: u16) -> u8 {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+
+    arg.modify_operand(width, |cpu, value| {
+        let result = step(value, amount, width);
+        cpu.registers.set_nz_flags(result, width);
+        result
+    })
+}
+
+/// Step an index register, and set the flags to match.
+/// # Parameters:
+///     - `arg`:        Arguments of the instruction being executed.
+///     - `register`:   Value of the index register.
+///     - `amount`:     Amount to add, `1` to increment or `0xFFFF` to decrement.
+/// # Returns:
+///     - The new value of the index register.
+fn step_index(
+    arg: &mut CpuInstructionFnArguments, register: Wrapping<u16>, amount: u16,
+) -> Wrapping<u16> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let result = step(register.0, amount, width);
+    arg.cpu.registers.set_nz_flags(result, width);
+    Wrapping(result)
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// INC Increment
+/// Syntax: INC A, INC dp, INC addr, INC dp,X, INC addr,X
+/// Opcodes: 0x1A, 0xE6, 0xEE, 0xF6, 0xFE
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----z-
+pub(super) fn inc(arg: &mut CpuInstructionFnArguments) -> Option<u8> { Some(modify(arg, 1)) }
+
+/// DEC Decrement
+/// Syntax: DEC A, DEC dp, DEC addr, DEC dp,X, DEC addr,X
+/// Opcodes: 0x3A, 0xC6, 0xCE, 0xD6, 0xDE
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----z-
+pub(super) fn dec(arg: &mut CpuInstructionFnArguments) -> Option<u8> { Some(modify(arg, 0xFFFF)) }
+
+/// INX Increment Index Register X
+/// Syntax: INX
+/// Opcodes: 0xE8
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn inx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.index_x = step_index(arg, arg.cpu.registers.index_x, 1);
+    Some(2)
+}
+
+/// INY Increment Index Register Y
+/// Syntax: INY
+/// Opcodes: 0xC8
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn iny(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.index_y = step_index(arg, arg.cpu.registers.index_y, 1);
+    Some(2)
+}
+
+/// DEX Decrement Index Register X
+/// Syntax: DEX
+/// Opcodes: 0xCA
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn dex(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.index_x = step_index(arg, arg.cpu.registers.index_x, 0xFFFF);
+    Some(2)
+}
+
+/// DEY Decrement Index Register Y
+/// Syntax: DEY
+/// Opcodes: 0x88
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn dey(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.index_y = step_index(arg, arg.cpu.registers.index_y, 0xFFFF);
+    Some(2)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_inc_dec_accumulator() {
+        let test_cases = vec![
+            // opcode, acc, 8-bit, result, n, z
+            (0x1A, 0x0000, false, 0x0001, 0, 0),
+            (0x1A, 0x7FFF, false, 0x8000, 1, 0),
+            (0x1A, 0xFFFF, false, 0x0000, 0, 1),
+            (0x1A, 0x007F, true, 0x0080, 1, 0),
+            // The high byte is not touched in 8-bit mode.
+            (0x1A, 0x12FF, true, 0x1200, 0, 1),
+            (0x3A, 0x0001, false, 0x0000, 0, 1),
+            (0x3A, 0x0000, false, 0xFFFF, 1, 0),
+            (0x3A, 0x8000, false, 0x7FFF, 0, 0),
+            (0x3A, 0x1200, true, 0x12FF, 1, 0),
+        ];
+
+        for (opcode, acc, narrow, result, n, z) in test_cases {
+            println!("Test Case: {:#04X} {:#06X} 8-bit: {}", opcode, acc, narrow);
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if narrow {
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+            }
+            test_cpu.registers.acc = Wrapping(acc);
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &[]);
+            assert_eq!(test_cpu.registers.acc.0, result);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001);
+        }
+    }
+
+    #[test]
+    fn test_inc_dec_memory() {
+        let test_cases = vec![
+            // opcode, mode, 16-bit cycles
+            (0xE6, CpuAddressMode::DirectPage, 7),
+            (0xEE, CpuAddressMode::Absolute, 8),
+            (0xF6, CpuAddressMode::DirectPageIndexedX, 8),
+            (0xFE, CpuAddressMode::AbsoluteIndexedX, 9),
+            (0xC6, CpuAddressMode::DirectPage, 7),
+            (0xCE, CpuAddressMode::Absolute, 8),
+            (0xD6, CpuAddressMode::DirectPageIndexedX, 8),
+            (0xDE, CpuAddressMode::AbsoluteIndexedX, 9),
+        ];
+
+        for (opcode, mode, cycles) in test_cases {
+            let (operand, address) = operand_for(mode);
+            let increment = opcode & 0x20 != 0;
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            // 16-bit: the carry ripples into the high byte.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            let start = match increment {
+                true => 0x00FF,
+                false => 0x0100,
+            };
+            test_mem._put_word(address, start).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            let expected = match increment {
+                true => 0x0100,
+                false => 0x00FF,
+            };
+            assert_eq!(test_mem.get_word(address).unwrap(), expected);
+            assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+
+            // Run the handler again directly to check its cycle count.
+            let mut arg = CpuInstructionFnArguments::new(&mut test_cpu, &mut test_mem, mode);
+            arg.param = operand[0] as u16;
+            if operand.len() > 1 {
+                arg.param = u16::from_le_bytes([operand[0], operand[1]]);
+            }
+            let taken = match increment {
+                true => inc(&mut arg),
+                false => dec(&mut arg),
+            };
+            assert_eq!(taken, Some(cycles));
+
+            // 8-bit: only the low byte changes.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+            let start = match increment {
+                true => 0x12FF,
+                false => 0x1200,
+            };
+            test_mem._put_word(address, start).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            let expected = match increment {
+                true => 0x1200,
+                false => 0x12FF,
+            };
+            assert_eq!(test_mem.get_word(address).unwrap(), expected);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero), increment);
+            assert_eq!(
+                test_cpu.registers.get_flag(StatusFlags::Negative),
+                !increment
+            );
+        }
+    }
+
+    #[test]
+    fn test_index_registers() {
+        let test_cases = vec![
+            // opcode, start, 8-bit, result, n, z
+            (0xE8, 0x7FFF, false, 0x8000, 1, 0),
+            (0xE8, 0xFFFF, false, 0x0000, 0, 1),
+            (0xE8, 0x00FF, true, 0x0000, 0, 1),
+            (0xC8, 0x0001, false, 0x0002, 0, 0),
+            (0xC8, 0x007F, true, 0x0080, 1, 0),
+            (0xCA, 0x0000, false, 0xFFFF, 1, 0),
+            (0xCA, 0x0000, true, 0x00FF, 1, 0),
+            (0x88, 0x0001, false, 0x0000, 0, 1),
+            (0x88, 0x0080, true, 0x007F, 0, 0),
+        ];
+
+        for (opcode, start, narrow, result, n, z) in test_cases {
+            println!(
+                "Test Case: {:#04X} {:#06X} 8-bit: {}",
+                opcode, start, narrow
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if narrow {
+                test_cpu.registers.set_flag(StatusFlags::IndexSize);
+            }
+            test_cpu.registers.index_x = Wrapping(start);
+            test_cpu.registers.index_y = Wrapping(start);
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &[]);
+            let (changed, unchanged) = match opcode {
+                0xE8 | 0xCA => (test_cpu.registers.index_x.0, test_cpu.registers.index_y.0),
+                _ => (test_cpu.registers.index_y.0, test_cpu.registers.index_x.0),
+            };
+            assert_eq!(changed, result);
+            assert_eq!(unchanged, start);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use super::{registers::StatusFlags, CpuInstructionFnArguments, CpuRunState};
+use crate::romdata::ExceptionVector;
+use std::num::Wrapping;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Address of the exception vector table in bank $00.
+const EXCEPTION_VECTORS_ADDR: usize = 0x00FFE0;
+
+/// In emulation mode bit 4 of the status pushed by an interrupt is the B flag, which tells BRK apart from IRQ.
+const EMULATION_BREAK_BIT: u8 = 1 << StatusFlags::IndexSize as u8;
+
+/**************************************** Public Functions **************************************************************/
+
+/// Enter an interrupt or exception handler.
+/// In native mode the program bank, PC and status are pushed. In emulation mode only the PC and status are pushed, with
+/// the B flag clear for hardware interrupts. Interrupts are then disabled, decimal mode is cleared, and the CPU jumps to
+/// the handler in bank $00.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed. The PC must point at the instruction to return to.
+///     - `vector`: Exception being taken.
+/// # Returns:
+///     - The number of cycles taken to enter the handler.
+pub(super) fn enter(arg: &mut CpuInstructionFnArguments, vector: ExceptionVector) -> u8 {
+    let emulation = arg.cpu.registers.emulation;
+    if !emulation {
+        arg.push_byte(arg.cpu.registers.program_bank.0);
+    }
+    arg.push_word(arg.cpu.registers.pc.0);
+
+    let mut status = arg.cpu.registers.get_flag_vals();
+    let software = matches!(vector, ExceptionVector::Brk | ExceptionVector::Cop);
+    if emulation && !software {
+        status &= !EMULATION_BREAK_BIT;
+    }
+    arg.push_byte(status);
+
+    arg.cpu.registers.set_flag(StatusFlags::IRQDisable);
+    arg.cpu.registers.clear_flag(StatusFlags::Decimal);
+    arg.cpu.registers.program_bank = Wrapping(0x00);
+    arg.cpu.registers.pc = Wrapping(arg.cpu.interrupts.vector(vector, emulation));
+    arg.record_access(EXCEPTION_VECTORS_ADDR, 2);
+
+    match emulation {
+        true => 7,
+        false => 8,
+    }
+}
+
+/// BRK Software Break
+/// The signature byte after the opcode is skipped, so the handler returns to the instruction after it.
+/// Syntax: BRK #sig
+/// Opcodes: 0x00
+/// Bytes: 2
+/// Flags affected: ----di--
+pub(super) fn brk(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(enter(arg, ExceptionVector::Brk))
+}
+
+/// COP Coprocessor Enable
+/// The signature byte after the opcode is skipped, so the handler returns to the instruction after it.
+/// Syntax: COP #sig
+/// Opcodes: 0x02
+/// Bytes: 2
+/// Flags affected: ----di--
+pub(super) fn cop(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(enter(arg, ExceptionVector::Cop))
+}
+
+/// RTI Return from Interrupt
+/// Syntax: RTI
+/// Opcodes: 0x40
+/// Bytes: 1
+/// Flags affected: nvmxdizc
+pub(super) fn rti(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let status = arg.pull_byte();
+    arg.cpu.registers.set_flag_vals(status);
+    arg.cpu.registers.pc = Wrapping(arg.pull_word());
+
+    match arg.cpu.registers.emulation {
+        true => Some(6),
+        false => {
+            arg.cpu.registers.program_bank = Wrapping(arg.pull_byte());
+            Some(7)
+        }
+    }
+}
+
+/// WAI Wait for Interrupt
+/// Stops executing until an NMI, ABORT or IRQ arrives. A masked IRQ wakes the CPU without being serviced.
+/// Syntax: WAI
+/// Opcodes: 0xCB
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn wai(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.run_state = CpuRunState::Waiting;
+    Some(3)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::super::CpuState;
+    use super::*;
+    use crate::memory::Memory;
+
+    /// Build a CPU with an exception vector table where every vector is distinct.
+    fn setup_interrupt_test() -> (CpuState, Memory) {
+        let (mut cpu, memory) = setup_addressing_test();
+        let mut table = [0; crate::romdata::EV_LEN_BYTES];
+        for (index, byte) in table.iter_mut().enumerate() {
+            *byte = index as u8;
+        }
+        cpu.set_exception_vectors(table);
+        cpu.registers.set_flag(StatusFlags::Decimal);
+        cpu.registers.clear_flag(StatusFlags::IRQDisable);
+        (cpu, memory)
+    }
+
+    #[test]
+    fn test_brk_cop_native() {
+        for (opcode, vector) in [(0x00, 0x000706), (0x02, 0x000504)] {
+            println!("Test Case: {:#04X}", opcode);
+            let (mut test_cpu, mut test_mem) = setup_interrupt_test();
+            let status = test_cpu.registers.get_flag_vals();
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &[0xEA]);
+            assert_eq!(test_cpu.get_pc(), vector);
+            assert!(test_cpu.registers.get_flag(StatusFlags::IRQDisable));
+            assert!(!test_cpu.registers.get_flag(StatusFlags::Decimal));
+
+            // The bank, the address after the signature byte, and the status are pushed.
+            assert_eq!(test_mem.get_byte(0x001FF0).unwrap(), 0x80);
+            assert_eq!(test_mem.get_word(0x001FEE).unwrap(), 0x8002);
+            assert_eq!(test_mem.get_byte(0x001FED).unwrap(), status);
+            assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FEC);
+
+            // RTI restores all of it.
+            test_mem.put_byte(vector, 0x40).unwrap();
+            test_cpu.step(&mut test_mem).unwrap();
+            assert_eq!(test_cpu.get_pc(), 0x808002);
+            assert_eq!(test_cpu.registers.get_flag_vals(), status);
+            assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FF0);
+        }
+    }
+
+    #[test]
+    fn test_brk_emulation() {
+        let (mut test_cpu, mut test_mem) = setup_interrupt_test();
+        test_cpu.registers.set_emulation(true);
+
+        // BRK shares the IRQ vector in emulation mode, and pushes no bank.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x00, &[0xEA]);
+        assert_eq!(test_cpu.get_pc(), 0x001F1E);
+        assert_eq!(test_mem.get_word(0x0001EF).unwrap(), 0x8002);
+        // The B flag is set in the pushed status.
+        assert_ne!(
+            test_mem.get_byte(0x0001EE).unwrap() & EMULATION_BREAK_BIT,
+            0
+        );
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x01ED);
+
+        // RTI does not pull a bank in emulation mode.
+        test_mem.put_byte(0x001F1E, 0x40).unwrap();
+        test_cpu.registers.program_bank = Wrapping(0x00);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x008002);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x01F0);
+    }
+
+    #[test]
+    fn test_nmi_irq() {
+        let (mut test_cpu, mut test_mem) = setup_interrupt_test();
+        test_mem.put_byte(0x808000, 0xEA).unwrap();
+        test_cpu.registers.program_bank = Wrapping(0x80);
+        test_cpu.registers.pc = Wrapping(0x8000);
+
+        // NMI is taken before the next instruction, and returns to it.
+        test_cpu.request_nmi();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x000B0A);
+        assert_eq!(test_mem.get_word(0x001FEE).unwrap(), 0x8000);
+        test_mem.put_byte(0x000B0A, 0x40).unwrap();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808000);
+
+        // IRQ is ignored while I is set.
+        test_cpu.set_irq(true);
+        test_cpu.registers.set_flag(StatusFlags::IRQDisable);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808001);
+
+        // Emulation mode hardware interrupts push the status with B clear.
+        test_cpu.registers.set_emulation(true);
+        test_cpu.registers.clear_flag(StatusFlags::IRQDisable);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x001F1E);
+        assert_eq!(
+            test_mem.get_byte(0x0001F0).unwrap() & EMULATION_BREAK_BIT,
+            0
+        );
+    }
+
+    #[test]
+    fn test_abort() {
+        let (mut test_cpu, mut test_mem) = setup_interrupt_test();
+        test_cpu.registers.program_bank = Wrapping(0x80);
+        test_cpu.registers.pc = Wrapping(0x8000);
+
+        // ABORT is taken ahead of a pending NMI, which is then taken before the ABORT handler runs.
+        test_cpu.request_nmi();
+        test_cpu.request_abort();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x000908);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x000B0A);
+        assert_eq!(test_mem.get_word(0x001FEA).unwrap(), 0x0908);
+    }
+
+    #[test]
+    fn test_wai() {
+        let (mut test_cpu, mut test_mem) = setup_interrupt_test();
+        test_mem.put_byte(0x808001, 0xEA).unwrap();
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0xCB, &[]);
+        assert_eq!(test_cpu.get_pc(), 0x808001);
+
+        // Nothing runs while waiting.
+        test_cpu.step(&mut test_mem).unwrap();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808001);
+
+        // A masked IRQ wakes the CPU, which carries on without servicing it.
+        test_cpu.registers.set_flag(StatusFlags::IRQDisable);
+        test_cpu.set_irq(true);
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808002);
+
+        // An NMI wakes the CPU and is serviced.
+        test_cpu.set_irq(false);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xCB, &[]);
+        test_cpu.request_nmi();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x000B0A);
+        assert_eq!(test_mem.get_word(0x001FEE).unwrap(), 0x8001);
+    }
+}
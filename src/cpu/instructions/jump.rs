@@ -0,0 +1,188 @@
+use std::num::Wrapping;
+
+use super::{addressing::CpuAddressMode, CpuInstructionFnArguments};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Find the target of a jump or subroutine call.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+/// # Returns:
+///     - `(bank, pc)`: Bank and address to jump to.
+fn target(arg: &mut CpuInstructionFnArguments) -> (u8, u16) {
+    match arg.mode {
+        // Absolute jumps stay in the program bank, rather than using the data bank like other absolute accesses.
+        CpuAddressMode::Absolute => (arg.cpu.registers.program_bank.0, arg.param),
+        _ => {
+            let target = arg
+                .effective_address()
+                .expect("Jump was mapped to an addressing mode without an address.");
+            ((target.address >> 16) as u8, target.address as u16)
+        }
+    }
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// JMP Jump
+/// Syntax: JMP addr, JMP long, JMP (addr), JMP [addr], JMP (addr,X)
+/// Opcodes: 0x4C, 0x5C, 0x6C, 0x7C, 0xDC
+/// Bytes: 3-4 depending on addressing mode.
+/// Flags affected: --------
+pub(super) fn jmp(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (bank, pc) = target(arg);
+    arg.cpu.registers.program_bank = Wrapping(bank);
+    arg.cpu.registers.pc = Wrapping(pc);
+
+    match arg.mode {
+        CpuAddressMode::Absolute => Some(3),
+        CpuAddressMode::AbsoluteLong => Some(4),
+        CpuAddressMode::AbsoluteIndirect => Some(5),
+        _ => Some(6),
+    }
+}
+
+/// JSR Jump to Subroutine
+/// Pushes the address of the last byte of the instruction, and jumps within the program bank.
+/// Syntax: JSR addr, JSR (addr,X)
+/// Opcodes: 0x20, 0xFC
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn jsr(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (_, pc) = target(arg);
+    let return_address = arg.cpu.registers.pc.0.wrapping_sub(1);
+    arg.push_word(return_address);
+    arg.cpu.registers.pc = Wrapping(pc);
+
+    match arg.mode {
+        CpuAddressMode::Absolute => Some(6),
+        _ => Some(8),
+    }
+}
+
+/// JSL Jump to Subroutine Long
+/// Pushes the program bank and the address of the last byte of the instruction.
+/// Syntax: JSL long
+/// Opcodes: 0x22
+/// Bytes: 4
+/// Flags affected: --------
+pub(super) fn jsl(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (bank, pc) = target(arg);
+    let return_address = arg.cpu.registers.pc.0.wrapping_sub(1);
+    arg.push_byte(arg.cpu.registers.program_bank.0);
+    arg.push_word(return_address);
+    arg.cpu.registers.program_bank = Wrapping(bank);
+    arg.cpu.registers.pc = Wrapping(pc);
+    Some(8)
+}
+
+/// RTS Return from Subroutine
+/// Syntax: RTS
+/// Opcodes: 0x60
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn rts(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let return_address = arg.pull_word();
+    arg.cpu.registers.pc = Wrapping(return_address.wrapping_add(1));
+    Some(6)
+}
+
+/// RTL Return from Subroutine Long
+/// Syntax: RTL
+/// Opcodes: 0x6B
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn rtl(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let return_address = arg.pull_word();
+    arg.cpu.registers.program_bank = Wrapping(arg.pull_byte());
+    arg.cpu.registers.pc = Wrapping(return_address.wrapping_add(1));
+    Some(6)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_jmp() {
+        let test_cases = vec![
+            // opcode, operand, target
+            (0x4C, vec![0x34, 0x12], 0x801234),
+            (0x5C, vec![0x34, 0x12, 0xC0], 0xC01234),
+            // Pointer at $00:0300 is $9000, bank $C1.
+            (0x6C, vec![0x00, 0x03], 0x809000),
+            (0xDC, vec![0x00, 0x03], 0xC19000),
+            // Pointer at $80:0312 (X is 2) is $A000. Low RAM is mirrored into bank $80.
+            (0x7C, vec![0x10, 0x03], 0x80A000),
+        ];
+
+        for (opcode, operand, target) in test_cases {
+            println!("Test Case: {:#04X} {:02X?}", opcode, operand);
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(0x000300, 0x9000).unwrap();
+            test_mem.put_byte(0x000302, 0xC1).unwrap();
+            test_mem._put_word(0x800312, 0xA000).unwrap();
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.get_pc(), target);
+        }
+    }
+
+    #[test]
+    fn test_jsr_rts() {
+        for (opcode, operand) in [(0x20, vec![0x00, 0x90]), (0xFC, vec![0x00, 0x03])] {
+            println!("Test Case: {:#04X} {:02X?}", opcode, operand);
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(0x800302, 0x9000).unwrap();
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.get_pc(), 0x809000);
+            // The address of the last byte of the JSR is pushed.
+            assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FEE);
+            assert_eq!(test_mem.get_word(0x001FEF).unwrap(), 0x8002);
+
+            // RTS returns to the instruction after the JSR.
+            test_mem.put_byte(0x809000, 0x60).unwrap();
+            test_cpu.step(&mut test_mem).unwrap();
+            assert_eq!(test_cpu.get_pc(), 0x808003);
+            assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FF0);
+        }
+    }
+
+    #[test]
+    fn test_jsl_rtl() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0x22, &[0x00, 0x90, 0xC0]);
+        assert_eq!(test_cpu.get_pc(), 0xC09000);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FED);
+        assert_eq!(test_mem.get_byte(0x001FF0).unwrap(), 0x80);
+        assert_eq!(test_mem.get_word(0x001FEE).unwrap(), 0x8003);
+
+        test_mem.put_byte(0xC09000, 0x6B).unwrap();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808004);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FF0);
+    }
+
+    #[test]
+    fn test_emulation_stack_wrap() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.set_emulation(true);
+        test_cpu.registers.stack_ptr = Wrapping(0x0100);
+
+        // The stack pointer wraps within page 1 in emulation mode.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x20, &[0x00, 0x90]);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x01FE);
+        assert_eq!(test_mem.get_byte(0x000100).unwrap(), 0x80);
+        assert_eq!(test_mem.get_byte(0x0001FF).unwrap(), 0x02);
+
+        test_mem.put_byte(0x809000, 0x60).unwrap();
+        test_cpu.step(&mut test_mem).unwrap();
+        assert_eq!(test_cpu.get_pc(), 0x808003);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x0100);
+    }
+}
@@ -0,0 +1,277 @@
+use super::{
+    addressing,
+    registers::{StatusFlags, REGISTER_MODE_16_BIT},
+    CpuInstructionFnArguments,
+};
+use std::num::Wrapping;
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Read the operand of a load instruction, and set the flags to match it.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the target register.
+/// # Returns:
+///     - `(value, cycles)`:    The value loaded, and the number of cycles the load took.
+fn load(arg: &mut CpuInstructionFnArguments, width: bool) -> (u16, u8) {
+    let (value, address) = arg.read_operand(width);
+    arg.cpu.registers.set_nz_flags(value, width);
+
+    let mut cycles = addressing::read_cycles(arg.cpu, arg.mode, address);
+    if width == REGISTER_MODE_16_BIT {
+        cycles += 1;
+    }
+
+    (value, cycles)
+}
+
+/// Merge a loaded value into a register.
+/// In 8-bit mode only the low byte of the register is replaced.
+/// # Parameters:
+///     - `register`:   Current value of the register.
+///     - `value`:      Value that was loaded.
+///     - `width`:      `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the register.
+/// # Returns:
+///     - The new value of the register.
+pub(super) fn merge(register: Wrapping<u16>, value: u16, width: bool) -> Wrapping<u16> {
+    match width {
+        REGISTER_MODE_16_BIT => Wrapping(value),
+        _ => Wrapping((register.0 & 0xFF00) | (value & 0x00FF)),
+    }
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// LDA Load Accumulator from Memory
+/// Syntax: LDA #const, LDA dp, LDA addr, LDA long, and their indexed and indirect forms.
+/// Opcodes: 0xA1, 0xA3, 0xA5, 0xA7, 0xA9, 0xAD, 0xAF, 0xB1, 0xB2, 0xB3, 0xB5, 0xB7, 0xB9, 0xBD, 0xBF
+/// Bytes: 2-4 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn lda(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, cycles) = load(arg, width);
+    arg.cpu.registers.acc = merge(arg.cpu.registers.acc, value, width);
+    Some(cycles)
+}
+
+/// LDX Load Index Register X from Memory
+/// Syntax: LDX #const, LDX dp, LDX dp,Y, LDX addr, LDX addr,Y
+/// Opcodes: 0xA2, 0xA6, 0xAE, 0xB6, 0xBE
+/// Bytes: 2-3 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn ldx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let (value, cycles) = load(arg, width);
+    arg.cpu.registers.index_x = merge(arg.cpu.registers.index_x, value, width);
+    Some(cycles)
+}
+
+/// LDY Load Index Register Y from Memory
+/// Syntax: LDY #const, LDY dp, LDY dp,X, LDY addr, LDY addr,X
+/// Opcodes: 0xA0, 0xA4, 0xAC, 0xB4, 0xBC
+/// Bytes: 2-3 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn ldy(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let (value, cycles) = load(arg, width);
+    arg.cpu.registers.index_y = merge(arg.cpu.registers.index_y, value, width);
+    Some(cycles)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::memory::Memory;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::super::CpuState;
+    use super::*;
+    use crate::cpu::instructions::load;
+
+    #[test]
+    fn test_immediate() {
+        let test_cases = vec![
+            //acc, n, z
+            [0x00, 0, 1],
+            [0x01, 0, 0],
+            [0x7F, 0, 0],
+            [0x80, 1, 0],
+            [0xFF, 1, 0],
+        ];
+
+        let mut test_cpu = CpuState::new();
+        let mut test_mem = Memory::new();
+        test_cpu.registers.set_flag(StatusFlags::AccSize);
+
+        let mut test_args =
+            CpuInstructionFnArguments::new(&mut test_cpu, &mut test_mem, CpuAddressMode::Immediate);
+        test_args.param = 0;
+
+        for case in test_cases {
+            test_args.param = case[0];
+            load::lda(&mut test_args);
+
+            println!("Test Case: {:?}", case);
+            print!("Testing Result");
+            assert_eq!(case[0], test_args.cpu.registers.acc.0);
+
+            print!(" Testing Flags: ");
+            print!("n, ");
+            assert_eq!(
+                case[1],
+                test_args.cpu.registers.get_flag(StatusFlags::Negative) as u16
+            );
+            println!("z");
+            assert_eq!(
+                case[2],
+                test_args.cpu.registers.get_flag(StatusFlags::Zero) as u16
+            );
+        }
+    }
+
+    #[test]
+    fn test_immediate_16bit() {
+        let test_cases = vec![
+            //value, n, z
+            [0x0000u16, 0, 1],
+            [0x00FF, 0, 0],
+            [0x7FFF, 0, 0],
+            [0x8000, 1, 0],
+            [0xFFFF, 1, 0],
+        ];
+
+        for case in test_cases {
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.clear_flag(StatusFlags::AccSize);
+
+            let operand = case[0].to_le_bytes();
+            run_opcode(&mut test_cpu, &mut test_mem, 0xA9, &operand);
+
+            println!("Test Case: {:?}", case);
+            assert_eq!(case[0], test_cpu.registers.acc.0);
+            assert_eq!(
+                case[1],
+                test_cpu.registers.get_flag(StatusFlags::Negative) as u16
+            );
+            assert_eq!(
+                case[2],
+                test_cpu.registers.get_flag(StatusFlags::Zero) as u16
+            );
+            // The PC must step past the full 16-bit parameter.
+            assert_eq!(test_cpu.registers.pc.0, 0x8003);
+        }
+    }
+
+    #[test]
+    fn test_lda_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0xA1, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0xA3, CpuAddressMode::StackRelative),
+            (0xA5, CpuAddressMode::DirectPage),
+            (0xA7, CpuAddressMode::DirectPageIndirectLong),
+            (0xAD, CpuAddressMode::Absolute),
+            (0xAF, CpuAddressMode::AbsoluteLong),
+            (0xB1, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0xB2, CpuAddressMode::DirectPageIndirect),
+            (0xB3, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0xB5, CpuAddressMode::DirectPageIndexedX),
+            (0xB7, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0xB9, CpuAddressMode::AbsoluteIndexedY),
+            (0xBD, CpuAddressMode::AbsoluteIndexedX),
+            (0xBF, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            // 16-bit: the whole word is loaded.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(address, 0x8001).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.acc.0, 0x8001);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+            assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+
+            // 8-bit: only the low byte is loaded, and the high byte of the accumulator is kept.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+            test_cpu.registers.acc = Wrapping(0x1200);
+            test_mem._put_word(address, 0x8000).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.acc.0, 0x1200);
+            assert!(!test_cpu.registers.get_flag(StatusFlags::Negative));
+            assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+        }
+    }
+
+    #[test]
+    fn test_ldx_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0xA6, CpuAddressMode::DirectPage),
+            (0xAE, CpuAddressMode::Absolute),
+            (0xB6, CpuAddressMode::DirectPageIndexedY),
+            (0xBE, CpuAddressMode::AbsoluteIndexedY),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(address, 0x8001).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.index_x.0, 0x8001);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+
+        // Immediate follows the width of the index registers, not the accumulator.
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.set_flag(StatusFlags::AccSize);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xA2, &[0x34, 0x12]);
+        assert_eq!(test_cpu.registers.index_x.0, 0x1234);
+        assert_eq!(test_cpu.registers.pc.0, 0x8003);
+
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.set_flag(StatusFlags::IndexSize);
+        test_cpu.registers.index_x = Wrapping(0x0000);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xA2, &[0x00]);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0000);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+        assert_eq!(test_cpu.registers.pc.0, 0x8002);
+    }
+
+    #[test]
+    fn test_ldy_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0xA4, CpuAddressMode::DirectPage),
+            (0xAC, CpuAddressMode::Absolute),
+            (0xB4, CpuAddressMode::DirectPageIndexedX),
+            (0xBC, CpuAddressMode::AbsoluteIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(address, 0x8001).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.index_y.0, 0x8001);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        run_opcode(&mut test_cpu, &mut test_mem, 0xA0, &[0x00, 0x00]);
+        assert_eq!(test_cpu.registers.index_y.0, 0x0000);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+        assert_eq!(test_cpu.registers.pc.0, 0x8003);
+    }
+}
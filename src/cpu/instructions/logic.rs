@@ -0,0 +1,408 @@
+use std::num::Wrapping;
+
+use super::{
+    addressing::{self, CpuAddressMode},
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Combine the operand of a logical instruction with the accumulator, and set the flags to match.
+/// # Parameters:
+///     - `arg`:        Arguments of the instruction being executed.
+///     - `operation`:  Function which combines the accumulator and the operand.
+/// # Returns:
+///     - The number of cycles the instruction took.
+fn logical(arg: &mut CpuInstructionFnArguments, operation: fn(u16, u16) -> u16) -> u8 {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, address) = arg.read_operand(width);
+
+    let acc = arg.cpu.registers.acc.0;
+    let result = match width {
+        REGISTER_MODE_8_BIT => (acc & 0xFF00) | (operation(acc, value) & 0x00FF),
+        REGISTER_MODE_16_BIT => operation(acc, value),
+    };
+    arg.cpu.registers.acc = Wrapping(result);
+    arg.cpu.registers.set_nz_flags(result, width);
+
+    let mut cycles = addressing::read_cycles(arg.cpu, arg.mode, address);
+    if width == REGISTER_MODE_16_BIT {
+        cycles += 1;
+    }
+
+    cycles
+}
+
+/// Test the bits of a value in memory against the accumulator, then set or reset them.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `set`:    `true` to set the accumulator's bits in memory (TSB), `false` to clear them (TRB).
+/// # Returns:
+///     - The number of cycles the instruction took.
+fn test_and_modify(arg: &mut CpuInstructionFnArguments, set: bool) -> u8 {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+
+    arg.modify_operand(width, |cpu, value| {
+        let acc = cpu.registers.acc.0;
+        match acc & value == 0 {
+            true => cpu.registers.set_flag(StatusFlags::Zero),
+            false => cpu.registers.clear_flag(StatusFlags::Zero),
+        }
+
+        match set {
+            true => value | acc,
+            false => value & !acc,
+        }
+    })
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// AND And Accumulator with Memory
+/// Syntax: AND #const, AND dp, AND addr, AND long, and their indexed and indirect forms.
+/// Opcodes: 0x21, 0x23, 0x25, 0x27, 0x29, 0x2D, 0x2F, 0x31, 0x32, 0x33, 0x35, 0x37, 0x39, 0x3D, 0x3F
+/// Bytes: 2-4 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn and(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(logical(arg, |acc, value| acc & value))
+}
+
+/// ORA OR Accumulator with Memory
+/// Syntax: ORA #const, ORA dp, ORA addr, ORA long, and their indexed and indirect forms.
+/// Opcodes: 0x01, 0x03, 0x05, 0x07, 0x09, 0x0D, 0x0F, 0x11, 0x12, 0x13, 0x15, 0x17, 0x19, 0x1D, 0x1F
+/// Bytes: 2-4 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn ora(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(logical(arg, |acc, value| acc | value))
+}
+
+/// EOR Exclusive-OR Accumulator with Memory
+/// Syntax: EOR #const, EOR dp, EOR addr, EOR long, and their indexed and indirect forms.
+/// Opcodes: 0x41, 0x43, 0x45, 0x47, 0x49, 0x4D, 0x4F, 0x51, 0x52, 0x53, 0x55, 0x57, 0x59, 0x5D, 0x5F
+/// Bytes: 2-4 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: n-----z-
+pub(super) fn eor(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(logical(arg, |acc, value| acc ^ value))
+}
+
+/// BIT Test Bits
+/// Syntax: BIT #const, BIT dp, BIT addr, BIT dp,X, BIT addr,X
+/// Opcodes: 0x24, 0x2C, 0x34, 0x3C, 0x89
+/// Bytes: 2-3 depending on addressing mode, +1 for 16-bit immediate.
+/// Flags affected: nv----z- (------z- for immediate)
+pub(super) fn bit(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, address) = arg.read_operand(width);
+    let acc = arg.cpu.registers.acc.0;
+
+    let (zero, negative_bit, overflow_bit) = match width {
+        REGISTER_MODE_8_BIT => (acc & value & 0x00FF == 0, 0x0080, 0x0040),
+        REGISTER_MODE_16_BIT => (acc & value == 0, 0x8000, 0x4000),
+    };
+
+    match zero {
+        true => arg.cpu.registers.set_flag(StatusFlags::Zero),
+        false => arg.cpu.registers.clear_flag(StatusFlags::Zero),
+    }
+
+    // The immediate form only has the accumulator to test against, so it leaves n and v alone.
+    if arg.mode != CpuAddressMode::Immediate {
+        match value & negative_bit != 0 {
+            true => arg.cpu.registers.set_flag(StatusFlags::Negative),
+            false => arg.cpu.registers.clear_flag(StatusFlags::Negative),
+        }
+        match value & overflow_bit != 0 {
+            true => arg.cpu.registers.set_flag(StatusFlags::Overflow),
+            false => arg.cpu.registers.clear_flag(StatusFlags::Overflow),
+        }
+    }
+
+    let mut cycles = addressing::read_cycles(arg.cpu, arg.mode, address);
+    if width == REGISTER_MODE_16_BIT {
+        cycles += 1;
+    }
+
+    Some(cycles)
+}
+
+/// TSB Test and Set Memory Bits Against Accumulator
+/// Syntax: TSB dp, TSB addr
+/// Opcodes: 0x04, 0x0C
+/// Bytes: 2-3 depending on addressing mode.
+/// Flags affected: ------z-
+pub(super) fn tsb(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(test_and_modify(arg, true))
+}
+
+/// TRB Test and Reset Memory Bits Against Accumulator
+/// Syntax: TRB dp, TRB addr
+/// Opcodes: 0x14, 0x1C
+/// Bytes: 2-3 depending on addressing mode.
+/// Flags affected: ------z-
+pub(super) fn trb(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(test_and_modify(arg, false))
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_logical_immediate() {
+        let test_cases = vec![
+            // opcode, acc, operand, 8-bit, result, n, z
+            (0x29, 0xFF0F, 0x0FF0, false, 0x0F00, 0, 0),
+            (0x29, 0xF0F0, 0x0F0F, false, 0x0000, 0, 1),
+            (0x29, 0x12F0, 0x0080, true, 0x1280, 1, 0),
+            (0x09, 0x0F00, 0x80F0, false, 0x8FF0, 1, 0),
+            (0x09, 0x0000, 0x0000, false, 0x0000, 0, 1),
+            (0x09, 0x1201, 0x0040, true, 0x1241, 0, 0),
+            (0x49, 0xFFFF, 0x0F0F, false, 0xF0F0, 1, 0),
+            (0x49, 0x1234, 0x1234, false, 0x0000, 0, 1),
+            (0x49, 0x12FF, 0x00FF, true, 0x1200, 0, 1),
+        ];
+
+        for (opcode, acc, operand, narrow, result, n, z) in test_cases {
+            println!(
+                "Test Case: {:#04X} {:#06X} {:#06X} 8-bit: {}",
+                opcode, acc, operand, narrow
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            let bytes = (operand as u16).to_le_bytes();
+            let bytes = match narrow {
+                true => {
+                    test_cpu.registers.set_flag(StatusFlags::AccSize);
+                    &bytes[..1]
+                }
+                false => &bytes[..],
+            };
+            test_cpu.registers.acc = Wrapping(acc);
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, bytes);
+            assert_eq!(test_cpu.registers.acc.0, result);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + bytes.len() as u16);
+        }
+    }
+
+    #[test]
+    fn test_logical_addressing_modes() {
+        let modes = vec![
+            // opcode offset from 0x01, mode
+            (0x00, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0x02, CpuAddressMode::StackRelative),
+            (0x04, CpuAddressMode::DirectPage),
+            (0x06, CpuAddressMode::DirectPageIndirectLong),
+            (0x0C, CpuAddressMode::Absolute),
+            (0x0E, CpuAddressMode::AbsoluteLong),
+            (0x10, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0x11, CpuAddressMode::DirectPageIndirect),
+            (0x12, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0x14, CpuAddressMode::DirectPageIndexedX),
+            (0x16, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0x18, CpuAddressMode::AbsoluteIndexedY),
+            (0x1C, CpuAddressMode::AbsoluteIndexedX),
+            (0x1E, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+        let test_cases = vec![
+            // base opcode, result of $FF00 with $0FF0
+            (0x01, 0xFFF0),
+            (0x21, 0x0F00),
+            (0x41, 0xF0F0),
+        ];
+
+        for (base, result) in test_cases {
+            for (offset, mode) in modes.iter() {
+                let opcode = base + offset;
+                let (operand, address) = operand_for(*mode);
+                println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                test_cpu.registers.acc = Wrapping(0xFF00);
+                test_mem._put_word(address, 0x0FF0).unwrap();
+                run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+                assert_eq!(test_cpu.registers.acc.0, result);
+                assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit() {
+        let test_cases = vec![
+            // opcode, mode, acc, operand, 8-bit, n, v, z
+            (
+                0x24,
+                CpuAddressMode::DirectPage,
+                0x0001,
+                0xC000,
+                false,
+                1,
+                1,
+                1,
+            ),
+            (
+                0x2C,
+                CpuAddressMode::Absolute,
+                0x4000,
+                0x4000,
+                false,
+                0,
+                1,
+                0,
+            ),
+            (
+                0x34,
+                CpuAddressMode::DirectPageIndexedX,
+                0x00FF,
+                0x0080,
+                true,
+                1,
+                0,
+                0,
+            ),
+            (
+                0x3C,
+                CpuAddressMode::AbsoluteIndexedX,
+                0x0001,
+                0x8140,
+                true,
+                0,
+                1,
+                1,
+            ),
+        ];
+
+        for (opcode, mode, acc, value, narrow, n, v, z) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if narrow {
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+            }
+            test_cpu.registers.acc = Wrapping(acc);
+            test_mem._put_word(address, value).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+
+            // BIT never changes the accumulator.
+            assert_eq!(test_cpu.registers.acc.0, acc);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Overflow) as u8, v);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+        }
+    }
+
+    #[test]
+    fn test_bit_immediate() {
+        // The immediate form only changes z, even when the operand has its top bits set.
+        for (n, v) in [(false, false), (true, true)] {
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if n {
+                test_cpu.registers.set_flag(StatusFlags::Negative);
+            }
+            if v {
+                test_cpu.registers.set_flag(StatusFlags::Overflow);
+            }
+            test_cpu.registers.acc = Wrapping(0x0F0F);
+
+            run_opcode(&mut test_cpu, &mut test_mem, 0x89, &[0xF0, 0xF0]);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative), n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Overflow), v);
+            assert_eq!(test_cpu.registers.pc.0, 0x8003);
+
+            run_opcode(&mut test_cpu, &mut test_mem, 0x89, &[0x01, 0xC0]);
+            assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative), n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Overflow), v);
+        }
+    }
+
+    #[test]
+    fn test_tsb_trb() {
+        let test_cases = vec![
+            // opcode, mode, acc, value, 8-bit, result, z
+            (
+                0x04,
+                CpuAddressMode::DirectPage,
+                0x00F0,
+                0x0F00,
+                false,
+                0x0FF0,
+                1,
+            ),
+            (
+                0x0C,
+                CpuAddressMode::Absolute,
+                0x0110,
+                0x0100,
+                false,
+                0x0110,
+                0,
+            ),
+            (
+                0x04,
+                CpuAddressMode::DirectPage,
+                0xFF01,
+                0x1210,
+                true,
+                0x1211,
+                1,
+            ),
+            (
+                0x14,
+                CpuAddressMode::DirectPage,
+                0x00F0,
+                0x0FFF,
+                false,
+                0x0F0F,
+                0,
+            ),
+            (
+                0x1C,
+                CpuAddressMode::Absolute,
+                0x0F00,
+                0xF000,
+                false,
+                0xF000,
+                1,
+            ),
+            (
+                0x14,
+                CpuAddressMode::DirectPage,
+                0xFF0F,
+                0x12FF,
+                true,
+                0x12F0,
+                0,
+            ),
+        ];
+
+        for (opcode, mode, acc, value, narrow, result, z) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!(
+                "Test Case: {:#04X} {:#06X} {:#06X} 8-bit: {}",
+                opcode, acc, value, narrow
+            );
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if narrow {
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+            }
+            test_cpu.registers.acc = Wrapping(acc);
+            test_mem._put_word(address, value).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+
+            assert_eq!(test_mem.get_word(address).unwrap(), result);
+            assert_eq!(test_cpu.registers.acc.0, acc);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+    }
+}
@@ -9,8 +9,11 @@ use super::*;
 ///     - `param`   Unused.
 ///
 /// # Returns
-///     - None (stop running).
-pub(super) fn stp(_arg: &mut CpuInstructionFnArguments) -> Option<u8> { None }
+///     - Number of cycles taken. The CPU does nothing more until it is reset.
+pub(super) fn stp(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.run_state = CpuRunState::Stopped;
+    Some(3)
+}
 
 /// Implementation of NOP instruction
 /// https://undisbeliever.net/snesdev/65816-opcodes.html#nop-no-operation
@@ -0,0 +1,202 @@
+use super::{
+    adc::add_with_carry,
+    addressing,
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+
+/**************************************** Public Functions **************************************************************/
+
+/// SBC Immediate
+/// Syntax: SBC #const
+/// Opcode: 0xE9
+/// Bytes:  2 if 8-bit param, 3 if 16-bit
+/// Flags affected: nv----zc
+pub(super) fn immediate(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    add_with_carry(arg.cpu, arg.param, true);
+
+    match arg.cpu.registers.get_flag(StatusFlags::AccSize) {
+        REGISTER_MODE_8_BIT => Some(2),
+        REGISTER_MODE_16_BIT => Some(3),
+    }
+}
+
+/// SBC from memory
+/// Syntax: SBC dp, SBC addr, SBC long, and their indexed and indirect forms.
+/// Opcodes: 0xE1, 0xE3, 0xE5, 0xE7, 0xED, 0xEF, 0xF1, 0xF2, 0xF3, 0xF5, 0xF7, 0xF9, 0xFD, 0xFF
+/// Bytes: 2-4 depending on addressing mode.
+/// Flags Affected: nv----zc
+pub(super) fn absolute(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, address) = arg.read_operand(width);
+
+    add_with_carry(arg.cpu, value, true);
+
+    let mut cycles_to_pend = addressing::read_cycles(arg.cpu, arg.mode, address);
+    if width == REGISTER_MODE_16_BIT {
+        cycles_to_pend += 1;
+    }
+
+    Some(cycles_to_pend)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+    use std::num::Wrapping;
+
+    /// Run SBC #param against the accumulator, and check the result and flags.
+    /// # Parameters:
+    ///     - `case`:       acc, param, carry in, result, n, v, z, c
+    ///     - `width`:      Accumulator width to run in.
+    ///     - `decimal`:    Whether to run in decimal mode.
+    fn check_sbc(case: [u16; 8], width: bool, decimal: bool) {
+        println!("Test case: {:04X?} decimal: {}", case, decimal);
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        if width == REGISTER_MODE_8_BIT {
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+        }
+        if decimal {
+            test_cpu.registers.set_flag(StatusFlags::Decimal);
+        }
+        if case[2] != 0 {
+            test_cpu.registers.set_flag(StatusFlags::Carry);
+        }
+        test_cpu.registers.acc = Wrapping(case[0]);
+
+        let operand = case[1].to_le_bytes();
+        let operand = match width {
+            REGISTER_MODE_8_BIT => &operand[..1],
+            REGISTER_MODE_16_BIT => &operand[..],
+        };
+        run_opcode(&mut test_cpu, &mut test_mem, 0xE9, operand);
+
+        assert_eq!(test_cpu.registers.acc.0, case[3]);
+        assert_eq!(
+            test_cpu.registers.get_flag(StatusFlags::Negative) as u16,
+            case[4]
+        );
+        assert_eq!(
+            test_cpu.registers.get_flag(StatusFlags::Overflow) as u16,
+            case[5]
+        );
+        assert_eq!(
+            test_cpu.registers.get_flag(StatusFlags::Zero) as u16,
+            case[6]
+        );
+        assert_eq!(
+            test_cpu.registers.get_flag(StatusFlags::Carry) as u16,
+            case[7]
+        );
+    }
+
+    #[test]
+    fn test_immediate_8bit() {
+        let test_cases = vec![
+            //ACC -  B,   c in,  = C,    n, v, z, c
+            [0x0005, 0x0003, 1, 0x0002, 0, 0, 0, 1],
+            [0x0005, 0x0003, 0, 0x0001, 0, 0, 0, 1],
+            [0x0005, 0x0005, 1, 0x0000, 0, 0, 1, 1],
+            [0x0003, 0x0005, 1, 0x00FE, 1, 0, 0, 0],
+            [0x0080, 0x0001, 1, 0x007F, 0, 1, 0, 1],
+            [0x007F, 0x00FF, 1, 0x0080, 1, 1, 0, 0],
+            [0x0000, 0x0000, 0, 0x00FF, 1, 0, 0, 0],
+            // The high byte of the accumulator is left alone.
+            [0x1205, 0x0003, 1, 0x1202, 0, 0, 0, 1],
+        ];
+
+        for case in test_cases {
+            check_sbc(case, REGISTER_MODE_8_BIT, false);
+        }
+    }
+
+    #[test]
+    fn test_immediate_16bit() {
+        let test_cases = vec![
+            //ACC -  B,   c in,  = C,    n, v, z, c
+            [0x1234, 0x0234, 1, 0x1000, 0, 0, 0, 1],
+            [0x0000, 0x0001, 1, 0xFFFF, 1, 0, 0, 0],
+            [0x8000, 0x0001, 1, 0x7FFF, 0, 1, 0, 1],
+            [0x7FFF, 0xFFFF, 1, 0x8000, 1, 1, 0, 0],
+            [0x0100, 0x0100, 1, 0x0000, 0, 0, 1, 1],
+            [0x0100, 0x00FF, 0, 0x0000, 0, 0, 1, 1],
+        ];
+
+        for case in test_cases {
+            check_sbc(case, REGISTER_MODE_16_BIT, false);
+        }
+    }
+
+    #[test]
+    fn test_immediate_decimal_8bit() {
+        let test_cases = vec![
+            //ACC -  B,   c in,  = C,    n, v, z, c
+            [0x0046, 0x0012, 1, 0x0034, 0, 0, 0, 1],
+            [0x0040, 0x0013, 1, 0x0027, 0, 0, 0, 1],
+            [0x0032, 0x0002, 0, 0x0029, 0, 0, 0, 1],
+            [0x0012, 0x0021, 1, 0x0091, 1, 0, 0, 0],
+            [0x0021, 0x0021, 1, 0x0000, 0, 0, 1, 1],
+            [0x0000, 0x0001, 1, 0x0099, 1, 0, 0, 0],
+            [0x0080, 0x0001, 1, 0x0079, 0, 1, 0, 1],
+        ];
+
+        for case in test_cases {
+            check_sbc(case, REGISTER_MODE_8_BIT, true);
+        }
+    }
+
+    #[test]
+    fn test_immediate_decimal_16bit() {
+        let test_cases = vec![
+            //ACC -  B,   c in,  = C,    n, v, z, c
+            [0x1234, 0x0235, 1, 0x0999, 0, 0, 0, 1],
+            [0x1000, 0x0001, 1, 0x0999, 0, 0, 0, 1],
+            [0x0000, 0x0001, 1, 0x9999, 1, 0, 0, 0],
+            [0x5000, 0x5000, 1, 0x0000, 0, 0, 1, 1],
+            [0x9999, 0x9998, 0, 0x0000, 0, 0, 1, 1],
+        ];
+
+        for case in test_cases {
+            check_sbc(case, REGISTER_MODE_16_BIT, true);
+        }
+    }
+
+    #[test]
+    fn test_sbc_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0xE1, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0xE3, CpuAddressMode::StackRelative),
+            (0xE5, CpuAddressMode::DirectPage),
+            (0xE7, CpuAddressMode::DirectPageIndirectLong),
+            (0xED, CpuAddressMode::Absolute),
+            (0xEF, CpuAddressMode::AbsoluteLong),
+            (0xF1, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0xF2, CpuAddressMode::DirectPageIndirect),
+            (0xF3, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0xF5, CpuAddressMode::DirectPageIndexedX),
+            (0xF7, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0xF9, CpuAddressMode::AbsoluteIndexedY),
+            (0xFD, CpuAddressMode::AbsoluteIndexedX),
+            (0xFF, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::Carry);
+            test_cpu.registers.acc = Wrapping(0x5000);
+            test_mem._put_word(address, 0x1234).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_cpu.registers.acc.0, 0x3DCC);
+            assert!(test_cpu.registers.get_flag(StatusFlags::Carry));
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use super::{
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments, CpuState,
+};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Shift or rotate the accumulator or a value in memory by one bit, and set the flags to match.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `left`:   `true` to shift towards the most significant bit, `false` to shift towards the least.
+///     - `rotate`: `true` to shift the carry flag in, `false` to shift a zero in.
+/// # Returns:
+///     - The number of cycles the instruction took.
+fn shift(arg: &mut CpuInstructionFnArguments, left: bool, rotate: bool) -> u8 {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let top_bit: u16 = match width {
+        REGISTER_MODE_8_BIT => 0x0080,
+        REGISTER_MODE_16_BIT => 0x8000,
+    };
+
+    arg.modify_operand(width, |cpu: &mut CpuState, value| {
+        let carry_in = rotate && cpu.registers.get_flag(StatusFlags::Carry);
+
+        let (result, carry_out) = match left {
+            true => ((value << 1) | carry_in as u16, value & top_bit != 0),
+            false => (
+                (value >> 1) | (carry_in as u16 * top_bit),
+                value & 0x0001 != 0,
+            ),
+        };
+
+        match carry_out {
+            true => cpu.registers.set_flag(StatusFlags::Carry),
+            false => cpu.registers.clear_flag(StatusFlags::Carry),
+        }
+        cpu.registers.set_nz_flags(result, width);
+
+        result
+    })
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// ASL Arithmetic Shift Left
+/// Syntax: ASL A, ASL dp, ASL addr, ASL dp,X, ASL addr,X
+/// Opcodes: 0x06, 0x0A, 0x0E, 0x16, 0x1E
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----zc
+pub(super) fn asl(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(shift(arg, true, false))
+}
+
+/// LSR Logical Shift Right
+/// Syntax: LSR A, LSR dp, LSR addr, LSR dp,X, LSR addr,X
+/// Opcodes: 0x46, 0x4A, 0x4E, 0x56, 0x5E
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----zc
+pub(super) fn lsr(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(shift(arg, false, false))
+}
+
+/// ROL Rotate Left
+/// Syntax: ROL A, ROL dp, ROL addr, ROL dp,X, ROL addr,X
+/// Opcodes: 0x26, 0x2A, 0x2E, 0x36, 0x3E
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----zc
+pub(super) fn rol(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(shift(arg, true, true))
+}
+
+/// ROR Rotate Right
+/// Syntax: ROR A, ROR dp, ROR addr, ROR dp,X, ROR addr,X
+/// Opcodes: 0x66, 0x6A, 0x6E, 0x76, 0x7E
+/// Bytes: 1-3 depending on addressing mode.
+/// Flags affected: n-----zc
+pub(super) fn ror(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(shift(arg, false, true))
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_accumulator() {
+        let test_cases = vec![
+            // opcode, acc, 8-bit, carry in, result, n, z, c
+            (0x0A, 0x4001, false, 1, 0x8002, 1, 0, 0),
+            (0x0A, 0x8000, false, 0, 0x0000, 0, 1, 1),
+            (0x0A, 0x12C0, true, 0, 0x1280, 1, 0, 1),
+            (0x4A, 0x8001, false, 1, 0x4000, 0, 0, 1),
+            (0x4A, 0x1201, true, 0, 0x1200, 0, 1, 1),
+            (0x2A, 0x8000, false, 1, 0x0001, 0, 0, 1),
+            (0x2A, 0x4000, false, 0, 0x8000, 1, 0, 0),
+            (0x2A, 0x1280, true, 0, 0x1200, 0, 1, 1),
+            (0x2A, 0x1240, true, 1, 0x1281, 1, 0, 0),
+            (0x6A, 0x0001, false, 1, 0x8000, 1, 0, 1),
+            (0x6A, 0x0002, false, 0, 0x0001, 0, 0, 0),
+            (0x6A, 0x1201, true, 1, 0x1280, 1, 0, 1),
+            (0x6A, 0x1201, true, 0, 0x1200, 0, 1, 1),
+        ];
+
+        for (opcode, acc, narrow, carry, result, n, z, c) in test_cases {
+            println!(
+                "Test Case: {:#04X} {:#06X} 8-bit: {} c: {}",
+                opcode, acc, narrow, carry
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            if narrow {
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+            }
+            if carry != 0 {
+                test_cpu.registers.set_flag(StatusFlags::Carry);
+            }
+            test_cpu.registers.acc = Wrapping(acc);
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &[]);
+            assert_eq!(test_cpu.registers.acc.0, result);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative) as u8, n);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero) as u8, z);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Carry) as u8, c);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001);
+        }
+    }
+
+    #[test]
+    fn test_memory() {
+        let test_cases = vec![
+            // opcodes (dp, addr, dp,X, addr,X), value, 16-bit result, 8-bit result, carry out (16, 8)
+            (
+                [0x06, 0x0E, 0x16, 0x1E],
+                0x8181,
+                0x0302,
+                0x8102,
+                (true, true),
+            ),
+            (
+                [0x46, 0x4E, 0x56, 0x5E],
+                0x8181,
+                0x40C0,
+                0x8140,
+                (true, true),
+            ),
+            (
+                [0x26, 0x2E, 0x36, 0x3E],
+                0x4040,
+                0x8081,
+                0x4081,
+                (false, false),
+            ),
+            (
+                [0x66, 0x6E, 0x76, 0x7E],
+                0x0202,
+                0x8101,
+                0x0281,
+                (false, false),
+            ),
+        ];
+        let modes = [
+            CpuAddressMode::DirectPage,
+            CpuAddressMode::Absolute,
+            CpuAddressMode::DirectPageIndexedX,
+            CpuAddressMode::AbsoluteIndexedX,
+        ];
+
+        for (opcodes, value, result_16, result_8, carry) in test_cases {
+            for (opcode, mode) in opcodes.into_iter().zip(modes) {
+                let (operand, address) = operand_for(mode);
+                println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+                // Rotates shift in a set carry, shifts do not.
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                test_cpu.registers.set_flag(StatusFlags::Carry);
+                test_mem._put_word(address, value).unwrap();
+                run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+                assert_eq!(test_mem.get_word(address).unwrap(), result_16);
+                assert_eq!(test_cpu.registers.get_flag(StatusFlags::Carry), carry.0);
+                assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+                test_cpu.registers.set_flag(StatusFlags::Carry);
+                test_mem._put_word(address, value).unwrap();
+                run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+                assert_eq!(test_mem.get_word(address).unwrap(), result_8);
+                assert_eq!(test_cpu.registers.get_flag(StatusFlags::Carry), carry.1);
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use super::{
+    load::merge,
+    registers::{StatusFlags, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+use std::num::Wrapping;
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Push a register onto the stack.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `value`:  Value of the register.
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the register.
+/// # Returns:
+///     - The number of cycles the push took.
+fn push_register(arg: &mut CpuInstructionFnArguments, value: u16, width: bool) -> u8 {
+    match width {
+        REGISTER_MODE_8_BIT => {
+            arg.push_byte(value as u8);
+            3
+        }
+        REGISTER_MODE_16_BIT => {
+            arg.push_word(value);
+            4
+        }
+    }
+}
+
+/// Pull a register from the stack, and set the flags to match it.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the register.
+/// # Returns:
+///     - `(value, cycles)`:    The value pulled, and the number of cycles the pull took.
+fn pull_register(arg: &mut CpuInstructionFnArguments, width: bool) -> (u16, u8) {
+    let (value, cycles) = match width {
+        REGISTER_MODE_8_BIT => (arg.pull_byte() as u16, 4),
+        REGISTER_MODE_16_BIT => (arg.pull_word(), 5),
+    };
+
+    arg.cpu.registers.set_nz_flags(value, width);
+    (value, cycles)
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// PHA Push Accumulator
+/// Syntax: PHA
+/// Opcodes: 0x48
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn pha(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    Some(push_register(arg, arg.cpu.registers.acc.0, width))
+}
+
+/// PHX Push Index Register X
+/// Syntax: PHX
+/// Opcodes: 0xDA
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn phx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    Some(push_register(arg, arg.cpu.registers.index_x.0, width))
+}
+
+/// PHY Push Index Register Y
+/// Syntax: PHY
+/// Opcodes: 0x5A
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn phy(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    Some(push_register(arg, arg.cpu.registers.index_y.0, width))
+}
+
+/// PHP Push Processor Status Register
+/// Syntax: PHP
+/// Opcodes: 0x08
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn php(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(push_register(
+        arg,
+        arg.cpu.registers.get_flag_vals() as u16,
+        REGISTER_MODE_8_BIT,
+    ))
+}
+
+/// PHB Push Data Bank Register
+/// Syntax: PHB
+/// Opcodes: 0x8B
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn phb(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(push_register(
+        arg,
+        arg.cpu.registers.data_bank.0 as u16,
+        REGISTER_MODE_8_BIT,
+    ))
+}
+
+/// PHK Push Program Bank Register
+/// Syntax: PHK
+/// Opcodes: 0x4B
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn phk(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(push_register(
+        arg,
+        arg.cpu.registers.program_bank.0 as u16,
+        REGISTER_MODE_8_BIT,
+    ))
+}
+
+/// PHD Push Direct Page Register
+/// Syntax: PHD
+/// Opcodes: 0x0B
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn phd(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    Some(push_register(
+        arg,
+        arg.cpu.registers.direct_page.0,
+        REGISTER_MODE_16_BIT,
+    ))
+}
+
+/// PLA Pull Accumulator
+/// Syntax: PLA
+/// Opcodes: 0x68
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn pla(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let (value, cycles) = pull_register(arg, width);
+    arg.cpu.registers.acc = merge(arg.cpu.registers.acc, value, width);
+    Some(cycles)
+}
+
+/// PLX Pull Index Register X
+/// Syntax: PLX
+/// Opcodes: 0xFA
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn plx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let (value, cycles) = pull_register(arg, width);
+    arg.cpu.registers.index_x = merge(arg.cpu.registers.index_x, value, width);
+    Some(cycles)
+}
+
+/// PLY Pull Index Register Y
+/// Syntax: PLY
+/// Opcodes: 0x7A
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn ply(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let (value, cycles) = pull_register(arg, width);
+    arg.cpu.registers.index_y = merge(arg.cpu.registers.index_y, value, width);
+    Some(cycles)
+}
+
+/// PLP Pull Processor Status Register
+/// Syntax: PLP
+/// Opcodes: 0x28
+/// Bytes: 1
+/// Flags affected: nvmxdizc
+pub(super) fn plp(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let value = arg.pull_byte();
+    arg.cpu.registers.set_flag_vals(value);
+    Some(4)
+}
+
+/// PLB Pull Data Bank Register
+/// Syntax: PLB
+/// Opcodes: 0xAB
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn plb(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (value, cycles) = pull_register(arg, REGISTER_MODE_8_BIT);
+    arg.cpu.registers.data_bank = Wrapping(value as u8);
+    Some(cycles)
+}
+
+/// PLD Pull Direct Page Register
+/// Syntax: PLD
+/// Opcodes: 0x2B
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn pld(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (value, cycles) = pull_register(arg, REGISTER_MODE_16_BIT);
+    arg.cpu.registers.direct_page = Wrapping(value);
+    Some(cycles)
+}
+
+/// PEA Push Effective Absolute Address
+/// Pushes the 16-bit operand itself.
+/// Syntax: PEA addr
+/// Opcodes: 0xF4
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn pea(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.push_word(arg.param);
+    Some(5)
+}
+
+/// PEI Push Effective Indirect Address
+/// Pushes the 16-bit pointer stored in the direct page.
+/// Syntax: PEI (dp)
+/// Opcodes: 0xD4
+/// Bytes: 2
+/// Flags affected: --------
+pub(super) fn pei(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let (pointer, _) = arg.read_operand(REGISTER_MODE_16_BIT);
+    arg.push_word(pointer);
+
+    match arg.cpu.registers.direct_page.0 & 0x00FF {
+        0 => Some(6),
+        _ => Some(7),
+    }
+}
+
+/// PER Push Effective PC Relative Indirect Address
+/// Pushes the address of the 16-bit offset from the next instruction.
+/// Syntax: PER label
+/// Opcodes: 0x62
+/// Bytes: 3
+/// Flags affected: --------
+pub(super) fn per(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let target = arg
+        .effective_address()
+        .expect("PER was mapped to an addressing mode without an address.");
+    arg.push_word(target.address as u16);
+    Some(6)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_push_pull_registers() {
+        let test_cases = vec![
+            // push opcode, pull opcode
+            (0x48, 0x68),
+            (0xDA, 0xFA),
+            (0x5A, 0x7A),
+        ];
+
+        for (push, pull) in test_cases {
+            for width in [REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT] {
+                println!("Test Case: {:#04X} {:#04X} 8-bit: {}", push, pull, width);
+                let (mut test_cpu, mut test_mem) = setup_addressing_test();
+                if width == REGISTER_MODE_8_BIT {
+                    test_cpu.registers.set_flag(StatusFlags::AccSize);
+                    test_cpu.registers.set_flag(StatusFlags::IndexSize);
+                }
+                let value = match width {
+                    REGISTER_MODE_16_BIT => 0x8234,
+                    REGISTER_MODE_8_BIT => 0x0082,
+                };
+                test_cpu.registers.acc = Wrapping(value);
+                test_cpu.registers.index_x = Wrapping(value);
+                test_cpu.registers.index_y = Wrapping(value);
+
+                run_opcode(&mut test_cpu, &mut test_mem, push, &[]);
+                let (pushed, stack_ptr) = match width {
+                    REGISTER_MODE_16_BIT => (test_mem.get_word(0x001FEF).unwrap(), 0x1FEE),
+                    REGISTER_MODE_8_BIT => (test_mem.get_byte(0x001FF0).unwrap() as u16, 0x1FEF),
+                };
+                assert_eq!(pushed, value);
+                assert_eq!(test_cpu.registers.stack_ptr.0, stack_ptr);
+
+                test_cpu.registers.acc = Wrapping(0);
+                test_cpu.registers.index_x = Wrapping(0);
+                test_cpu.registers.index_y = Wrapping(0);
+                run_opcode(&mut test_cpu, &mut test_mem, pull, &[]);
+                let pulled = match pull {
+                    0x68 => test_cpu.registers.acc.0,
+                    0xFA => test_cpu.registers.index_x.0,
+                    _ => test_cpu.registers.index_y.0,
+                };
+                assert_eq!(pulled, value);
+                assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FF0);
+                assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+                assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+            }
+        }
+    }
+
+    #[test]
+    fn test_push_pull_special_registers() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.direct_page = Wrapping(0x1234);
+        test_cpu.registers.data_bank = Wrapping(0x7E);
+
+        // PHD; PHB; PHK; PHP
+        run_opcode(&mut test_cpu, &mut test_mem, 0x0B, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x8B, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x4B, &[]);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x08, &[]);
+        assert_eq!(test_mem.get_word(0x001FEF).unwrap(), 0x1234);
+        assert_eq!(test_mem.get_byte(0x001FEE).unwrap(), 0x7E);
+        assert_eq!(test_mem.get_byte(0x001FED).unwrap(), 0x80);
+        assert_eq!(
+            test_mem.get_byte(0x001FEC).unwrap(),
+            test_cpu.registers.get_flag_vals()
+        );
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FEB);
+
+        // Replace the pushed status with one that sets 8-bit registers and carry.
+        test_mem.put_byte(0x001FEC, 0b0011_0001).unwrap();
+        test_cpu.registers.index_x = Wrapping(0x1234);
+
+        // PLP; PLB (the pushed program bank); PLB; PLD
+        run_opcode(&mut test_cpu, &mut test_mem, 0x28, &[]);
+        assert_eq!(test_cpu.registers.get_flag_vals(), 0b0011_0001);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0034);
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0xAB, &[]);
+        assert_eq!(test_cpu.registers.data_bank.0, 0x80);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0xAB, &[]);
+        assert_eq!(test_cpu.registers.data_bank.0, 0x7E);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Negative));
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0x2B, &[]);
+        assert_eq!(test_cpu.registers.direct_page.0, 0x1234);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FF0);
+    }
+
+    #[test]
+    fn test_push_effective_address() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_mem._put_word(0x000110, 0xBEEF).unwrap();
+
+        // PEA pushes the operand.
+        run_opcode(&mut test_cpu, &mut test_mem, 0xF4, &[0x34, 0x12]);
+        assert_eq!(test_mem.get_word(0x001FEF).unwrap(), 0x1234);
+
+        // PEI pushes the pointer at D + dp. D is $0100, so the low byte of D is 0.
+        run_opcode(&mut test_cpu, &mut test_mem, 0xD4, &[0x10]);
+        assert_eq!(test_mem.get_word(0x001FED).unwrap(), 0xBEEF);
+
+        // PER pushes the address relative to the next instruction.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x62, &[0x00, 0x10]);
+        assert_eq!(test_mem.get_word(0x001FEB).unwrap(), 0x9003);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1FEA);
+    }
+}
@@ -0,0 +1,185 @@
+use super::{
+    addressing,
+    registers::{StatusFlags, REGISTER_MODE_16_BIT},
+    CpuInstructionFnArguments,
+};
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Write a register to the operand address of a store instruction.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `value`:  Value of the register to store.
+///     - `width`:  `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the register.
+/// # Returns:
+///     - The number of cycles the store took.
+fn store(arg: &mut CpuInstructionFnArguments, value: u16, width: bool) -> u8 {
+    let address = arg
+        .effective_address()
+        .expect("Store instruction was mapped to an addressing mode without an address.");
+    arg.write_operand(address, width, value);
+
+    let mut cycles = addressing::write_cycles(arg.cpu, arg.mode);
+    if width == REGISTER_MODE_16_BIT {
+        cycles += 1;
+    }
+
+    cycles
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// STA Store Accumulator to Memory
+/// Syntax: STA dp, STA addr, STA long, and their indexed and indirect forms.
+/// Opcodes: 0x81, 0x83, 0x85, 0x87, 0x8D, 0x8F, 0x91, 0x92, 0x93, 0x95, 0x97, 0x99, 0x9D, 0x9F
+/// Bytes: 2-4 depending on addressing mode.
+/// Flags affected: --------
+pub(super) fn sta(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    let value = arg.cpu.registers.acc.0;
+    Some(store(arg, value, width))
+}
+
+/// STX Store Index Register X to Memory
+/// Syntax: STX dp, STX dp,Y, STX addr
+/// Opcodes: 0x86, 0x8E, 0x96
+/// Bytes: 2-3 depending on addressing mode.
+/// Flags affected: --------
+pub(super) fn stx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let value = arg.cpu.registers.index_x.0;
+    Some(store(arg, value, width))
+}
+
+/// STY Store Index Register Y to Memory
+/// Syntax: STY dp, STY dp,X, STY addr
+/// Opcodes: 0x84, 0x8C, 0x94
+/// Bytes: 2-3 depending on addressing mode.
+/// Flags affected: --------
+pub(super) fn sty(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    let value = arg.cpu.registers.index_y.0;
+    Some(store(arg, value, width))
+}
+
+/// STZ Store Zero to Memory
+/// Syntax: STZ dp, STZ dp,X, STZ addr, STZ addr,X
+/// Opcodes: 0x64, 0x74, 0x9C, 0x9E
+/// Bytes: 2-3 depending on addressing mode.
+/// Flags affected: --------
+pub(super) fn stz(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    // STZ follows the width of the accumulator.
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    Some(store(arg, 0, width))
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::addressing::CpuAddressMode;
+    use super::super::tests::{operand_for, run_opcode, setup_addressing_test};
+    use super::*;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_sta_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0x81, CpuAddressMode::DirectPageIndexedIndirectX),
+            (0x83, CpuAddressMode::StackRelative),
+            (0x85, CpuAddressMode::DirectPage),
+            (0x87, CpuAddressMode::DirectPageIndirectLong),
+            (0x8D, CpuAddressMode::Absolute),
+            (0x8F, CpuAddressMode::AbsoluteLong),
+            (0x91, CpuAddressMode::DirectPageIndirectIndexedY),
+            (0x92, CpuAddressMode::DirectPageIndirect),
+            (0x93, CpuAddressMode::StackRelativeIndirectIndexedY),
+            (0x95, CpuAddressMode::DirectPageIndexedX),
+            (0x97, CpuAddressMode::DirectPageIndirectLongIndexedY),
+            (0x99, CpuAddressMode::AbsoluteIndexedY),
+            (0x9D, CpuAddressMode::AbsoluteIndexedX),
+            (0x9F, CpuAddressMode::AbsoluteLongIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            // 16-bit: the whole word is stored.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.acc = Wrapping(0xBEEF);
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), 0xBEEF);
+            assert_eq!(test_cpu.registers.pc.0, 0x8001 + operand.len() as u16);
+
+            // 8-bit: only the low byte is stored.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+            test_cpu.registers.acc = Wrapping(0x00EF);
+            test_mem.put_byte(address + 1, 0x55).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), 0x55EF);
+        }
+    }
+
+    #[test]
+    fn test_stx_sty_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode, stores Y
+            (0x86, CpuAddressMode::DirectPage, false),
+            (0x8E, CpuAddressMode::Absolute, false),
+            (0x96, CpuAddressMode::DirectPageIndexedY, false),
+            (0x84, CpuAddressMode::DirectPage, true),
+            (0x8C, CpuAddressMode::Absolute, true),
+            (0x94, CpuAddressMode::DirectPageIndexedX, true),
+        ];
+
+        for (opcode, mode, stores_y) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            let expected = match stores_y {
+                true => test_cpu.registers.index_y.0,
+                false => test_cpu.registers.index_x.0,
+            };
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), expected);
+
+            // In 8-bit index mode only the low byte is stored, regardless of the accumulator width.
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::IndexSize);
+            test_mem.put_byte(address + 1, 0x55).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), 0x5500 | expected);
+        }
+    }
+
+    #[test]
+    fn test_stz_addressing_modes() {
+        let test_cases = vec![
+            // opcode, mode
+            (0x64, CpuAddressMode::DirectPage),
+            (0x74, CpuAddressMode::DirectPageIndexedX),
+            (0x9C, CpuAddressMode::Absolute),
+            (0x9E, CpuAddressMode::AbsoluteIndexedX),
+        ];
+
+        for (opcode, mode) in test_cases {
+            let (operand, address) = operand_for(mode);
+            println!("Test Case: {:#04X} {:?}", opcode, mode);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_mem._put_word(address, 0xFFFF).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), 0x0000);
+
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.set_flag(StatusFlags::AccSize);
+            test_mem._put_word(address, 0xFFFF).unwrap();
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &operand);
+            assert_eq!(test_mem.get_word(address).unwrap(), 0xFF00);
+        }
+    }
+}
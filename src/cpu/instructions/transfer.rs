@@ -0,0 +1,345 @@
+use super::{
+    load::merge,
+    registers::{StatusFlags, EMULATION_STACK_PAGE, REGISTER_MODE_16_BIT, REGISTER_MODE_8_BIT},
+    CpuInstructionFnArguments,
+};
+use std::num::Wrapping;
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Copy a value into a register, and set the flags to match it.
+/// # Parameters:
+///     - `arg`:        Arguments of the instruction being executed.
+///     - `register`:   Current value of the destination register.
+///     - `value`:      Value of the source register.
+///     - `width`:      `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the destination register.
+/// # Returns:
+///     - The new value of the destination register.
+fn transfer(
+    arg: &mut CpuInstructionFnArguments, register: Wrapping<u16>, value: u16, width: bool,
+) -> Wrapping<u16> {
+    arg.cpu.registers.set_nz_flags(value, width);
+    merge(register, value, width)
+}
+
+/// Get the value the stack pointer is given by TXS or TCS.
+/// In emulation mode the stack stays in page 1.
+/// # Parameters:
+///     - `arg`:    Arguments of the instruction being executed.
+///     - `value`:  Value of the source register.
+/// # Returns:
+///     - The new value of the stack pointer.
+fn stack_value(arg: &CpuInstructionFnArguments, value: u16) -> Wrapping<u16> {
+    match arg.cpu.registers.emulation {
+        true => Wrapping(EMULATION_STACK_PAGE | (value & 0x00FF)),
+        false => Wrapping(value),
+    }
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// TAX Transfer Accumulator to Index Register X
+/// Syntax: TAX
+/// Opcodes: 0xAA
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tax(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    arg.cpu.registers.index_x = transfer(
+        arg,
+        arg.cpu.registers.index_x,
+        arg.cpu.registers.acc.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TAY Transfer Accumulator to Index Register Y
+/// Syntax: TAY
+/// Opcodes: 0xA8
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tay(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    arg.cpu.registers.index_y = transfer(
+        arg,
+        arg.cpu.registers.index_y,
+        arg.cpu.registers.acc.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TXA Transfer Index Register X to Accumulator
+/// Syntax: TXA
+/// Opcodes: 0x8A
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn txa(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    arg.cpu.registers.acc = transfer(
+        arg,
+        arg.cpu.registers.acc,
+        arg.cpu.registers.index_x.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TYA Transfer Index Register Y to Accumulator
+/// Syntax: TYA
+/// Opcodes: 0x98
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tya(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::AccSize);
+    arg.cpu.registers.acc = transfer(
+        arg,
+        arg.cpu.registers.acc,
+        arg.cpu.registers.index_y.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TXY Transfer Index Register X to Index Register Y
+/// Syntax: TXY
+/// Opcodes: 0x9B
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn txy(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    arg.cpu.registers.index_y = transfer(
+        arg,
+        arg.cpu.registers.index_y,
+        arg.cpu.registers.index_x.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TYX Transfer Index Register Y to Index Register X
+/// Syntax: TYX
+/// Opcodes: 0xBB
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tyx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    arg.cpu.registers.index_x = transfer(
+        arg,
+        arg.cpu.registers.index_x,
+        arg.cpu.registers.index_y.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TSX Transfer Stack Pointer to Index Register X
+/// Syntax: TSX
+/// Opcodes: 0xBA
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tsx(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let width = arg.cpu.registers.get_flag(StatusFlags::IndexSize);
+    arg.cpu.registers.index_x = transfer(
+        arg,
+        arg.cpu.registers.index_x,
+        arg.cpu.registers.stack_ptr.0,
+        width,
+    );
+    Some(2)
+}
+
+/// TXS Transfer Index Register X to Stack Pointer
+/// Syntax: TXS
+/// Opcodes: 0x9A
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn txs(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.stack_ptr = stack_value(arg, arg.cpu.registers.index_x.0);
+    Some(2)
+}
+
+/// TCS Transfer 16-bit Accumulator to Stack Pointer
+/// Syntax: TCS
+/// Opcodes: 0x1B
+/// Bytes: 1
+/// Flags affected: --------
+pub(super) fn tcs(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.stack_ptr = stack_value(arg, arg.cpu.registers.acc.0);
+    Some(2)
+}
+
+/// TSC Transfer Stack Pointer to 16-bit Accumulator
+/// Syntax: TSC
+/// Opcodes: 0x3B
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tsc(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.acc = transfer(
+        arg,
+        arg.cpu.registers.acc,
+        arg.cpu.registers.stack_ptr.0,
+        REGISTER_MODE_16_BIT,
+    );
+    Some(2)
+}
+
+/// TCD Transfer 16-bit Accumulator to Direct Page Register
+/// Syntax: TCD
+/// Opcodes: 0x5B
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tcd(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.direct_page = transfer(
+        arg,
+        arg.cpu.registers.direct_page,
+        arg.cpu.registers.acc.0,
+        REGISTER_MODE_16_BIT,
+    );
+    Some(2)
+}
+
+/// TDC Transfer Direct Page Register to 16-bit Accumulator
+/// Syntax: TDC
+/// Opcodes: 0x7B
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn tdc(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    arg.cpu.registers.acc = transfer(
+        arg,
+        arg.cpu.registers.acc,
+        arg.cpu.registers.direct_page.0,
+        REGISTER_MODE_16_BIT,
+    );
+    Some(2)
+}
+
+/// XBA Exchange the B and A Accumulators
+/// The flags are set from the new low byte, whatever the accumulator width.
+/// Syntax: XBA
+/// Opcodes: 0xEB
+/// Bytes: 1
+/// Flags affected: n-----z-
+pub(super) fn xba(arg: &mut CpuInstructionFnArguments) -> Option<u8> {
+    let value = arg.cpu.registers.acc.0.swap_bytes();
+    arg.cpu.registers.acc = Wrapping(value);
+    arg.cpu.registers.set_nz_flags(value, REGISTER_MODE_8_BIT);
+    Some(3)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{run_opcode, setup_addressing_test};
+    use super::*;
+
+    #[test]
+    fn test_register_transfers() {
+        let test_cases = vec![
+            // opcode, 8-bit accumulator, 8-bit index, expected A, X, Y, negative, zero
+            (0xAA, false, false, 0x8000, 0x8000, 0x00FF, true, false),
+            (0xA8, false, false, 0x8000, 0x0000, 0x8000, true, false),
+            (0x8A, false, false, 0x0000, 0x0000, 0x00FF, false, true),
+            (0x98, false, false, 0x00FF, 0x0000, 0x00FF, false, false),
+            (0x9B, false, false, 0x8000, 0x0000, 0x0000, false, true),
+            (0xBB, false, false, 0x8000, 0x00FF, 0x00FF, false, false),
+            // 8-bit index registers only take the low byte of the accumulator.
+            (0xAA, false, true, 0x8000, 0x0000, 0x00FF, false, true),
+            // An 8-bit accumulator keeps its high byte.
+            (0x98, true, true, 0x80FF, 0x0000, 0x00FF, true, false),
+            // A 16-bit accumulator takes the whole 8-bit index register.
+            (0x98, false, true, 0x00FF, 0x0000, 0x00FF, false, false),
+        ];
+
+        for (opcode, acc_8bit, index_8bit, acc, x, y, negative, zero) in test_cases {
+            println!(
+                "Test Case: {:#04X} 8-bit A: {} 8-bit X/Y: {}",
+                opcode, acc_8bit, index_8bit
+            );
+            let (mut test_cpu, mut test_mem) = setup_addressing_test();
+            test_cpu.registers.acc = Wrapping(0x8000);
+            test_cpu.registers.index_x = Wrapping(0x0000);
+            test_cpu.registers.index_y = Wrapping(0x00FF);
+            if index_8bit {
+                test_cpu.registers.set_flag(StatusFlags::IndexSize);
+            }
+            if acc_8bit {
+                test_cpu.registers.set_flag(StatusFlags::AccSize);
+            }
+
+            run_opcode(&mut test_cpu, &mut test_mem, opcode, &[]);
+            assert_eq!(test_cpu.registers.acc.0, acc);
+            assert_eq!(test_cpu.registers.index_x.0, x);
+            assert_eq!(test_cpu.registers.index_y.0, y);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Negative), negative);
+            assert_eq!(test_cpu.registers.get_flag(StatusFlags::Zero), zero);
+        }
+    }
+
+    #[test]
+    fn test_stack_transfers() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.index_x = Wrapping(0x1234);
+
+        // TXS does not change the flags.
+        run_opcode(&mut test_cpu, &mut test_mem, 0x9A, &[]);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x1234);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+
+        // TSC is always 16-bit.
+        test_cpu.registers.set_flag(StatusFlags::AccSize);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x3B, &[]);
+        assert_eq!(test_cpu.registers.acc.0, 0x1234);
+
+        // TSX with 8-bit index registers only takes the low byte.
+        test_cpu.registers.set_flag(StatusFlags::IndexSize);
+        run_opcode(&mut test_cpu, &mut test_mem, 0xBA, &[]);
+        assert_eq!(test_cpu.registers.index_x.0, 0x0034);
+
+        // TCS keeps the stack in page 1 in emulation mode.
+        test_cpu.registers.set_emulation(true);
+        test_cpu.registers.acc = Wrapping(0x5678);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x1B, &[]);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x0178);
+
+        test_cpu.registers.index_x = Wrapping(0x00F0);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x9A, &[]);
+        assert_eq!(test_cpu.registers.stack_ptr.0, 0x01F0);
+    }
+
+    #[test]
+    fn test_direct_page_transfers() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.acc = Wrapping(0x0000);
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0x5B, &[]);
+        assert_eq!(test_cpu.registers.direct_page.0, 0x0000);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+
+        test_cpu.registers.direct_page = Wrapping(0x8100);
+        test_cpu.registers.set_flag(StatusFlags::AccSize);
+        run_opcode(&mut test_cpu, &mut test_mem, 0x7B, &[]);
+        assert_eq!(test_cpu.registers.acc.0, 0x8100);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_xba() {
+        let (mut test_cpu, mut test_mem) = setup_addressing_test();
+        test_cpu.registers.acc = Wrapping(0x8000);
+
+        // The flags come from the new low byte, even with a 16-bit accumulator.
+        run_opcode(&mut test_cpu, &mut test_mem, 0xEB, &[]);
+        assert_eq!(test_cpu.registers.acc.0, 0x0080);
+        assert!(test_cpu.registers.get_flag(StatusFlags::Negative));
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Zero));
+
+        run_opcode(&mut test_cpu, &mut test_mem, 0xEB, &[]);
+        assert_eq!(test_cpu.registers.acc.0, 0x8000);
+        assert!(!test_cpu.registers.get_flag(StatusFlags::Negative));
+        assert!(test_cpu.registers.get_flag(StatusFlags::Zero));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::romdata::{self, ExceptionVector, ExceptionVectorTable};
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// State of the interrupt lines into the CPU.
+///     exception_vectors:  Copy of the ROM's exception vector table, used to find the handler for each interrupt.
+///     nmi_pending:        An NMI edge has been seen, and has not been serviced yet.
+///     irq_asserted:       The IRQ line is held low. IRQ is level triggered, so it stays pending until it is released.
+///     abort_pending:      An ABORT has been requested, and has not been serviced yet.
+#[derive(Debug)]
+pub(super) struct InterruptController {
+    exception_vectors: ExceptionVectorTable,
+    nmi_pending: bool,
+    irq_asserted: bool,
+    abort_pending: bool,
+}
+
+impl InterruptController {
+    /// Return an interrupt controller with no vectors and nothing pending.
+    pub const fn new() -> Self {
+        Self {
+            exception_vectors: [0; romdata::EV_LEN_BYTES],
+            nmi_pending: false,
+            irq_asserted: false,
+            abort_pending: false,
+        }
+    }
+
+    /// Clear any pending interrupts. The exception vectors are kept.
+    pub fn reset(&mut self) {
+        self.nmi_pending = false;
+        self.irq_asserted = false;
+        self.abort_pending = false;
+    }
+
+    /// Set the exception vector table to vector through.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `table`:  Exception vector table from the ROM.
+    pub fn set_exception_vectors(&mut self, table: ExceptionVectorTable) {
+        self.exception_vectors = table;
+    }
+
+    /// Find the handler for an interrupt.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `vector`:     Interrupt to look up.
+    ///     - `emulation`:  Whether the CPU is in emulation mode.
+    /// # Returns:
+    ///     - The address of the handler in bank $00.
+    pub fn vector(&self, vector: ExceptionVector, emulation: bool) -> u16 {
+        romdata::exception_vector(&self.exception_vectors, vector, emulation)
+    }
+
+    /// Signal an NMI. NMI is edge triggered, so it is serviced once for each request.
+    pub fn request_nmi(&mut self) { self.nmi_pending = true; }
+
+    /// Assert or release the IRQ line.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `asserted`:   `true` while a device is requesting an IRQ.
+    #[cfg(test)]
+    pub fn set_irq(&mut self, asserted: bool) { self.irq_asserted = asserted; }
+
+    /// Signal an ABORT.
+    #[cfg(test)]
+    pub fn request_abort(&mut self) { self.abort_pending = true; }
+
+    /// Check whether anything would wake the CPU from WAI. A masked IRQ still wakes it, without being serviced.
+    pub fn should_wake(&self) -> bool {
+        self.nmi_pending || self.irq_asserted || self.abort_pending
+    }
+
+    /// Take the highest priority interrupt that should be serviced before the next instruction.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `irq_disabled`:   Whether the I flag is set, masking IRQs.
+    /// # Returns:
+    ///     - `Some(vector)`:   The interrupt to service. NMI and ABORT are cleared once taken.
+    ///     - `None`:           If there is nothing to service.
+    pub fn take_pending(&mut self, irq_disabled: bool) -> Option<ExceptionVector> {
+        if self.abort_pending {
+            self.abort_pending = false;
+            Some(ExceptionVector::Abort)
+        }
+        else if self.nmi_pending {
+            self.nmi_pending = false;
+            Some(ExceptionVector::Nmi)
+        }
+        else if self.irq_asserted && !irq_disabled {
+            Some(ExceptionVector::Irq)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_pending() {
+        let mut interrupts = InterruptController::new();
+        assert_eq!(interrupts.take_pending(false), None);
+
+        // NMI is taken once, and is not masked by I.
+        interrupts.request_nmi();
+        assert_eq!(interrupts.take_pending(true), Some(ExceptionVector::Nmi));
+        assert_eq!(interrupts.take_pending(true), None);
+
+        // IRQ is masked by I, and stays pending until the line is released.
+        interrupts.set_irq(true);
+        assert_eq!(interrupts.take_pending(true), None);
+        assert_eq!(interrupts.take_pending(false), Some(ExceptionVector::Irq));
+        assert_eq!(interrupts.take_pending(false), Some(ExceptionVector::Irq));
+        interrupts.set_irq(false);
+        assert_eq!(interrupts.take_pending(false), None);
+
+        // ABORT beats NMI, which beats IRQ.
+        interrupts.set_irq(true);
+        interrupts.request_nmi();
+        interrupts.request_abort();
+        assert_eq!(interrupts.take_pending(false), Some(ExceptionVector::Abort));
+        assert_eq!(interrupts.take_pending(false), Some(ExceptionVector::Nmi));
+        assert_eq!(interrupts.take_pending(false), Some(ExceptionVector::Irq));
+    }
+
+    #[test]
+    fn test_should_wake() {
+        let mut interrupts = InterruptController::new();
+        assert!(!interrupts.should_wake());
+
+        interrupts.set_irq(true);
+        assert!(interrupts.should_wake());
+
+        interrupts.reset();
+        assert!(!interrupts.should_wake());
+        interrupts.request_nmi();
+        assert!(interrupts.should_wake());
+    }
+}
@@ -0,0 +1,304 @@
+use std::ops::RangeInclusive;
+
+use crate::emu::{scheduler::Component, EmulationError};
+use crate::memory::{self, io::IoRegisters};
+
+/**************************************** Constant Values ***************************************************************/
+
+/// WRMPYA-WRDIVB, the operands of the multiplier and divider. Writing WRMPYB or WRDIVB starts an operation.
+pub const WRITE_REGISTERS: RangeInclusive<u16> = WRMPYA_ADDR..=WRDIVB_ADDR;
+
+/// RDDIVL-RDMPYH, the results of the multiplier and divider.
+pub const READ_REGISTERS: RangeInclusive<u16> = RDDIVL_ADDR..=RDMPYH_ADDR;
+
+const WRMPYA_ADDR: u16 = 0x4202;
+const WRMPYB_ADDR: u16 = 0x4203;
+const WRDIVL_ADDR: u16 = 0x4204;
+const WRDIVH_ADDR: u16 = 0x4205;
+const WRDIVB_ADDR: u16 = 0x4206;
+const RDDIVL_ADDR: u16 = 0x4214;
+const RDDIVH_ADDR: u16 = 0x4215;
+const RDMPYL_ADDR: u16 = 0x4216;
+const RDMPYH_ADDR: u16 = 0x4217;
+
+/// CPU cycles a multiplication takes, one for each bit of WRMPYA.
+const MULTIPLY_CYCLES: u8 = 8;
+
+/// CPU cycles a division takes, one for each bit of the quotient.
+const DIVIDE_CYCLES: u8 = 16;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// The CPU's unsigned 8x8 multiplier and 16/8 divider at $4202-$4206 and $4214-$4217.
+/// Both work one bit per CPU cycle through the result registers, so reading them before the operation has finished
+/// sees a partial result, as it does on hardware. The CPU queues the cycles of each step it runs, and they are run as
+/// the unit catches up to the master clock.
+/// https://snes.nesdev.org/wiki/Multiplication
+///     wrmpya:             Multiplicand.
+///     wrdiv:              Dividend.
+///     rddiv:              Quotient, or the multiplier's remaining bits of WRMPYA while it runs.
+///     rdmpy:              Product, or remainder.
+///     shift:              Operand being shifted against the result, WRMPYB or WRDIVB.
+///     multiply_cycles:    Cycles left in the multiplication in progress.
+///     divide_cycles:      Cycles left in the division in progress.
+///     queued_cycles:      CPU cycles run by the CPU which the unit hasn't run yet.
+///     queued_clocks:      Master clocks those cycles took.
+#[derive(Debug)]
+pub struct MathUnit {
+    wrmpya: u8,
+    wrdiv: u16,
+    rddiv: u16,
+    rdmpy: u16,
+    shift: u32,
+    multiply_cycles: u8,
+    divide_cycles: u8,
+    queued_cycles: usize,
+    queued_clocks: usize,
+}
+
+impl MathUnit {
+    /// Return a math unit with every register 0 and nothing in progress.
+    pub fn new() -> Self {
+        Self {
+            wrmpya: 0,
+            wrdiv: 0,
+            rddiv: 0,
+            rdmpy: 0,
+            shift: 0,
+            multiply_cycles: 0,
+            divide_cycles: 0,
+            queued_cycles: 0,
+            queued_clocks: 0,
+        }
+    }
+
+    /// Queue the cycles of a CPU step, to be run as the unit catches up to the master clock.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `cycles`: CPU cycles the step took.
+    ///     - `clocks`: Master clocks the step took.
+    pub fn queue_cycles(&mut self, cycles: usize, clocks: usize) {
+        self.queued_cycles += cycles;
+        self.queued_clocks += clocks;
+    }
+
+    /// Run one cycle of the operation in progress.
+    fn step(&mut self) {
+        if self.multiply_cycles > 0 {
+            self.multiply_cycles -= 1;
+            if self.rddiv & 0x0001 != 0 {
+                self.rdmpy = self.rdmpy.wrapping_add(self.shift as u16);
+            }
+            self.rddiv >>= 1;
+            self.shift <<= 1;
+        }
+
+        if self.divide_cycles > 0 {
+            self.divide_cycles -= 1;
+            self.rddiv <<= 1;
+            self.shift >>= 1;
+            if self.rdmpy as u32 >= self.shift {
+                self.rdmpy -= self.shift as u16;
+                self.rddiv |= 0x0001;
+            }
+        }
+    }
+
+    /// Check whether an operation is in progress.
+    fn is_busy(&self) -> bool { self.multiply_cycles > 0 || self.divide_cycles > 0 }
+}
+
+impl Component for MathUnit {
+    /// Run the next queued CPU cycle of any operation in progress.
+    /// Each cycle takes an even share of the master clocks left queued, so the cycles of a step end with it.
+    /// Returns 0 master clocks if there are no cycles queued.
+    fn tick(&mut self, _memory: &mut memory::Memory) -> Result<usize, EmulationError> {
+        if self.queued_cycles == 0 {
+            return Ok(0);
+        }
+
+        let clocks = self.queued_clocks / self.queued_cycles;
+        self.queued_cycles -= 1;
+        self.queued_clocks -= clocks;
+        self.step();
+        Ok(clocks)
+    }
+
+    /// Run the queued cycles which fall before `target`.
+    /// Time the CPU spends without running cycles, such as while DMA has stopped it, passes without the unit running.
+    fn run_until(
+        &mut self, elapsed: usize, target: usize, memory: &mut memory::Memory,
+    ) -> Result<usize, EmulationError> {
+        let mut elapsed = elapsed;
+        while elapsed < target && self.queued_cycles > 0 {
+            elapsed += self.tick(memory)?;
+        }
+        Ok(elapsed.max(target))
+    }
+}
+
+impl IoRegisters for MathUnit {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        match address {
+            RDDIVL_ADDR..=RDMPYH_ADDR => self.peek(address),
+            // The operands are write only.
+            _ => None,
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let [rddiv_low, rddiv_high] = self.rddiv.to_le_bytes();
+        let [rdmpy_low, rdmpy_high] = self.rdmpy.to_le_bytes();
+        let [wrdiv_low, wrdiv_high] = self.wrdiv.to_le_bytes();
+        match address {
+            WRMPYA_ADDR => Some(self.wrmpya),
+            WRDIVL_ADDR => Some(wrdiv_low),
+            WRDIVH_ADDR => Some(wrdiv_high),
+            RDDIVL_ADDR => Some(rddiv_low),
+            RDDIVH_ADDR => Some(rddiv_high),
+            RDMPYL_ADDR => Some(rdmpy_low),
+            RDMPYH_ADDR => Some(rdmpy_high),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            WRMPYA_ADDR => self.wrmpya = value,
+            WRMPYB_ADDR => {
+                // The product is cleared even if the write is ignored because the unit is busy.
+                self.rdmpy = 0;
+                if !self.is_busy() {
+                    self.rddiv = u16::from_le_bytes([self.wrmpya, value]);
+                    self.shift = value as u32;
+                    self.multiply_cycles = MULTIPLY_CYCLES;
+                }
+            }
+            WRDIVL_ADDR => self.wrdiv = (self.wrdiv & 0xFF00) | value as u16,
+            WRDIVH_ADDR => self.wrdiv = (self.wrdiv & 0x00FF) | (value as u16) << 8,
+            WRDIVB_ADDR => {
+                self.rdmpy = self.wrdiv;
+                if !self.is_busy() {
+                    self.shift = (value as u32) << 16;
+                    self.divide_cycles = DIVIDE_CYCLES;
+                }
+            }
+            // The results are read only.
+            _ => {}
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::timing;
+    use super::*;
+
+    /// Master clocks for a number of fast CPU cycles.
+    fn cycles(count: usize) -> usize { count * timing::FAST_ACCESS_CLOCKS as usize }
+
+    /// Queue a number of fast CPU cycles and run them from the start.
+    fn run(math: &mut MathUnit, count: usize) {
+        math.queue_cycles(count, cycles(count));
+        math.run_until(0, cycles(count), &mut memory::Memory::new()).unwrap();
+    }
+
+    /// Read a 16-bit result register pair.
+    fn read_word(math: &mut MathUnit, address: u16) -> u16 {
+        u16::from_le_bytes([math.read(address).unwrap(), math.read(address + 1).unwrap()])
+    }
+
+    #[test]
+    fn test_multiply() {
+        let test_cases = vec![
+            // a, b, product
+            (0x00, 0x00, 0x0000),
+            (0x03, 0x05, 0x000F),
+            (0xFF, 0xFF, 0xFE01),
+            (0x80, 0x02, 0x0100),
+        ];
+
+        for (a, b, product) in test_cases {
+            println!("Test Case: {:#04X} * {:#04X}", a, b);
+            let mut math = MathUnit::new();
+            math.write(WRMPYA_ADDR, a);
+            math.write(WRMPYB_ADDR, b);
+            run(&mut math, 8);
+            assert_eq!(read_word(&mut math, RDMPYL_ADDR), product);
+            // WRMPYB is left in RDDIV.
+            assert_eq!(read_word(&mut math, RDDIVL_ADDR), b as u16);
+        }
+    }
+
+    #[test]
+    fn test_divide() {
+        let test_cases = vec![
+            // dividend, divisor, quotient, remainder
+            (0x1234, 0x10, 0x0123, 0x0004),
+            (0xFFFF, 0x01, 0xFFFF, 0x0000),
+            (0x0005, 0x07, 0x0000, 0x0005),
+            // Division by zero gives all ones and leaves the dividend as the remainder.
+            (0xABCD, 0x00, 0xFFFF, 0xABCD),
+        ];
+
+        for (dividend, divisor, quotient, remainder) in test_cases {
+            println!("Test Case: {:#06X} / {:#04X}", dividend, divisor);
+            let mut math = MathUnit::new();
+            let [low, high] = u16::to_le_bytes(dividend);
+            math.write(WRDIVL_ADDR, low);
+            math.write(WRDIVH_ADDR, high);
+            math.write(WRDIVB_ADDR, divisor);
+            run(&mut math, 16);
+            assert_eq!(read_word(&mut math, RDDIVL_ADDR), quotient);
+            assert_eq!(read_word(&mut math, RDMPYL_ADDR), remainder);
+        }
+    }
+
+    #[test]
+    fn test_latency() {
+        let mut math = MathUnit::new();
+        math.write(WRMPYA_ADDR, 0xFF);
+        math.write(WRMPYB_ADDR, 0xFF);
+
+        // Nothing runs until the CPU has run some cycles, however many master clocks pass.
+        let mut memory = memory::Memory::new();
+        let elapsed = math.run_until(0, cycles(8), &mut memory).unwrap();
+        assert_eq!(elapsed, cycles(8));
+        assert_eq!(read_word(&mut math, RDMPYL_ADDR), 0x0000);
+
+        // Part way through, only the low bits of WRMPYA have been multiplied in. Each cycle runs in its share of the
+        // master clocks of the step that queued it, whether it was fast or slow.
+        math.queue_cycles(4, 4 * 8);
+        let elapsed = math.run_until(elapsed, elapsed + 4 * 8, &mut memory).unwrap();
+        assert_eq!(read_word(&mut math, RDMPYL_ADDR), 0x0F * 0xFF);
+        math.queue_cycles(4, 3 * 6 + 8);
+        let elapsed = math.run_until(elapsed, elapsed + 3 * 6, &mut memory).unwrap();
+        assert_eq!(read_word(&mut math, RDMPYL_ADDR), 0x7F * 0xFF);
+
+        // A cycle is never split, so running part of one runs all of it.
+        math.run_until(elapsed, elapsed + 1, &mut memory).unwrap();
+        assert_eq!(read_word(&mut math, RDMPYL_ADDR), 0xFE01);
+        assert!(!math.is_busy());
+
+        // A new operation can't start until the last has finished, but it still overwrites RDMPY.
+        math.write(WRMPYB_ADDR, 0x02);
+        math.write(WRDIVL_ADDR, 0x34);
+        math.write(WRDIVB_ADDR, 0x01);
+        assert_eq!(math.multiply_cycles, 8);
+        assert_eq!(math.divide_cycles, 0);
+        assert_eq!(read_word(&mut math, RDMPYL_ADDR), 0x0034);
+    }
+
+    #[test]
+    fn test_write_only() {
+        let mut math = MathUnit::new();
+        math.write(WRMPYA_ADDR, 0xAA);
+        math.write(RDMPYL_ADDR, 0xBB);
+
+        assert_eq!(math.read(WRMPYA_ADDR), None);
+        assert_eq!(math.peek(WRMPYA_ADDR), Some(0xAA));
+        assert_eq!(math.read(RDMPYL_ADDR), Some(0x00));
+    }
+}
@@ -0,0 +1,180 @@
+use std::ops::RangeInclusive;
+
+use crate::memory::io::IoRegisters;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// NMITIMEN, which enables the NMI at the start of vertical blanking.
+pub const WRITE_REGISTERS: RangeInclusive<u16> = NMITIMEN_ADDR..=NMITIMEN_ADDR;
+
+/// RDNMI, which flags that vertical blanking has started.
+pub const READ_REGISTERS: RangeInclusive<u16> = RDNMI_ADDR..=RDNMI_ADDR;
+
+const NMITIMEN_ADDR: u16 = 0x4200;
+const RDNMI_ADDR: u16 = 0x4210;
+
+/// Bit of NMITIMEN set to raise an NMI at the start of vertical blanking.
+const NMI_ENABLE_BIT: u8 = 0x80;
+
+/// Bit of RDNMI set at the start of vertical blanking, until RDNMI is read or vertical blanking ends.
+const NMI_FLAG_BIT: u8 = 0x80;
+
+/// Version of the CPU, in the low bits of RDNMI.
+const CPU_VERSION: u8 = 0x02;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// The CPU's NMI registers, NMITIMEN at $4200 and RDNMI at $4210.
+/// Only the NMI enable bit of NMITIMEN is acted on, since the timer IRQs and joypad auto-read don't exist yet. Bits 4-6
+/// of RDNMI are open bus on hardware, and read as 0 here.
+/// https://snes.nesdev.org/wiki/MMIO_registers
+///     nmitimen:       Last value written to NMITIMEN.
+///     nmi_flag:       Whether vertical blanking has started since RDNMI was last read.
+///     nmi_pending:    Whether an NMI has been raised which hasn't been passed on to the CPU yet.
+#[derive(Debug)]
+pub struct NmiRegisters {
+    nmitimen: u8,
+    nmi_flag: bool,
+    nmi_pending: bool,
+}
+
+impl NmiRegisters {
+    /// Return the registers with the NMI disabled and its flag clear.
+    pub fn new() -> Self {
+        Self {
+            nmitimen: 0,
+            nmi_flag: false,
+            nmi_pending: false,
+        }
+    }
+
+    /// Set the NMI flag at the start of vertical blanking, and raise an NMI if NMITIMEN enables it.
+    pub fn start_vblank(&mut self) {
+        self.nmi_flag = true;
+        if self.nmitimen & NMI_ENABLE_BIT != 0 {
+            self.nmi_pending = true;
+        }
+    }
+
+    /// Clear the NMI flag at the end of vertical blanking, if it hasn't been read already.
+    pub fn end_vblank(&mut self) { self.nmi_flag = false; }
+
+    /// Take the NMI raised since this was last called, if there was one.
+    /// # Returns:
+    ///     - `true` if the CPU should be sent an NMI.
+    pub fn take_nmi(&mut self) -> bool { std::mem::take(&mut self.nmi_pending) }
+}
+
+impl IoRegisters for NmiRegisters {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        match address {
+            // Reading RDNMI clears the flag.
+            RDNMI_ADDR => {
+                let value = self.peek(address);
+                self.nmi_flag = false;
+                value
+            }
+            // NMITIMEN is write only.
+            _ => None,
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            NMITIMEN_ADDR => Some(self.nmitimen),
+            RDNMI_ADDR => match self.nmi_flag {
+                true => Some(NMI_FLAG_BIT | CPU_VERSION),
+                false => Some(CPU_VERSION),
+            },
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        // RDNMI is read only.
+        if address == NMITIMEN_ADDR {
+            // Enabling the NMI while the flag is still set raises it straight away.
+            let enabling = self.nmitimen & NMI_ENABLE_BIT == 0 && value & NMI_ENABLE_BIT != 0;
+            if enabling && self.nmi_flag {
+                self.nmi_pending = true;
+            }
+            self.nmitimen = value;
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_vblank() {
+        let test_cases = vec![
+            // NMITIMEN, whether an NMI is raised
+            (0x00, false),
+            (0x01, false),
+            (0x80, true),
+            (0xB1, true),
+        ];
+
+        for (nmitimen, raised) in test_cases {
+            println!("Test Case: {:#04X}", nmitimen);
+            let mut nmi = NmiRegisters::new();
+            nmi.write(NMITIMEN_ADDR, nmitimen);
+            nmi.start_vblank();
+            assert_eq!(nmi.take_nmi(), raised);
+            assert!(!nmi.take_nmi());
+
+            // The flag is set whether or not the NMI is enabled.
+            assert_eq!(nmi.peek(RDNMI_ADDR), Some(NMI_FLAG_BIT | CPU_VERSION));
+        }
+    }
+
+    #[test]
+    fn test_enable_during_vblank() {
+        let mut nmi = NmiRegisters::new();
+        nmi.start_vblank();
+        assert!(!nmi.take_nmi());
+
+        // Enabling the NMI while the flag is set raises it, but writing the enable bit again doesn't.
+        nmi.write(NMITIMEN_ADDR, 0x80);
+        assert!(nmi.take_nmi());
+        nmi.write(NMITIMEN_ADDR, 0x81);
+        assert!(!nmi.take_nmi());
+
+        // Once the flag has been read, enabling the NMI waits for the next vertical blanking.
+        nmi.write(NMITIMEN_ADDR, 0x00);
+        nmi.read(RDNMI_ADDR);
+        nmi.write(NMITIMEN_ADDR, 0x80);
+        assert!(!nmi.take_nmi());
+    }
+
+    #[test]
+    fn test_rdnmi() {
+        let mut nmi = NmiRegisters::new();
+        assert_eq!(nmi.read(RDNMI_ADDR), Some(CPU_VERSION));
+
+        // Reading the flag clears it.
+        nmi.start_vblank();
+        assert_eq!(nmi.read(RDNMI_ADDR), Some(NMI_FLAG_BIT | CPU_VERSION));
+        assert_eq!(nmi.read(RDNMI_ADDR), Some(CPU_VERSION));
+
+        // So does the end of vertical blanking.
+        nmi.start_vblank();
+        nmi.end_vblank();
+        assert_eq!(nmi.read(RDNMI_ADDR), Some(CPU_VERSION));
+    }
+
+    #[test]
+    fn test_write_only() {
+        let mut nmi = NmiRegisters::new();
+        nmi.write(NMITIMEN_ADDR, 0x81);
+        nmi.write(RDNMI_ADDR, 0xFF);
+
+        assert_eq!(nmi.read(NMITIMEN_ADDR), None);
+        assert_eq!(nmi.peek(NMITIMEN_ADDR), Some(0x81));
+        assert_eq!(nmi.peek(RDNMI_ADDR), Some(CPU_VERSION));
+    }
+}
@@ -10,6 +10,9 @@ pub(super) const _ALU_16BIT_CARRY_BIT: usize = 16;
 pub(super) const REGISTER_MODE_16_BIT: bool = false;
 pub(super) const REGISTER_MODE_8_BIT: bool = true;
 
+/// In emulation mode the high byte of the stack pointer is always $01.
+pub(super) const EMULATION_STACK_PAGE: u16 = 0x0100;
+
 /**************************************** Struct and Type definitions ***************************************************/
 /// CPU Register fields.
 ///     acc:                Accumulator
@@ -20,39 +23,62 @@ pub(super) const REGISTER_MODE_8_BIT: bool = true;
 ///     program_bank:       Holds bank address of all instruction fetches
 ///     processor_status:   Holds flags & test results. broken out in StatusRegister explanation.
 ///     pc:                 Program Counter
+///     emulation:          6502 emulation mode (the hidden E flag).
 #[derive(Debug, Clone, Copy)]
 pub(super) struct CpuRegisters {
     pub(super) acc: Wrapping<u16>,
-    pub(super) _index_x: Wrapping<u16>,
-    pub(super) _index_y: Wrapping<u16>,
+    pub(super) index_x: Wrapping<u16>,
+    pub(super) index_y: Wrapping<u16>,
     pub(super) stack_ptr: Wrapping<u16>,
     pub(super) data_bank: Wrapping<u8>,
     pub(super) direct_page: Wrapping<u16>,
     pub(super) program_bank: Wrapping<u8>,
     pub(super) status: StatusRegister,
     pub(super) pc: Wrapping<u16>,
+    pub(super) emulation: bool,
 }
 
 impl CpuRegisters {
+    /// Return the registers as they are at power on.
+    /// The CPU always starts in emulation mode, with 8-bit registers and the stack in page 1.
+    /// The PC is only a placeholder until `reset()` loads it from the RESET vector.
     pub const fn new() -> Self {
         CpuRegisters {
             acc: Wrapping(0),
-            _index_x: Wrapping(0),
-            _index_y: Wrapping(0),
-            stack_ptr: Wrapping(0),
+            index_x: Wrapping(0),
+            index_y: Wrapping(0),
+            stack_ptr: Wrapping(EMULATION_STACK_PAGE | 0x00FF),
             data_bank: Wrapping(0),
             direct_page: Wrapping(0),
             program_bank: Wrapping(0x80),
             status: StatusRegister::new(),
             pc: Wrapping(0x8000),
+            emulation: true,
         }
     }
 
+    /// Put the registers into the state the CPU leaves them in after a reset.
+    /// The accumulator and the low bytes of the index registers and stack pointer are left as they were.
+    /// Parameters:
+    ///     - `self`
+    ///     - `bank`:   Bank to start executing from.
+    ///     - `pc`:     Address to start executing from, usually the RESET vector.
+    pub fn reset(&mut self, bank: u8, pc: u16) {
+        self.set_emulation(true);
+        self.set_flag(StatusFlags::IRQDisable);
+        self.clear_flag(StatusFlags::Decimal);
+        self.direct_page = Wrapping(0);
+        self.data_bank = Wrapping(0);
+        self.program_bank = Wrapping(bank);
+        self.pc = Wrapping(pc);
+    }
+
     /// Print the current state of the CPU.
     pub fn print_state(&self) {
         println!(
-            "\nPC: {:#06X} ACC: {:#06X} SP: {:#06X}\nData Bank: {:#04X} Prog Bank: {:#04X} Direct Page: {:#06X}"
-             ,self.pc, self.acc, self.stack_ptr, self.data_bank, self.program_bank, self.direct_page,
+            "\nPC: {:#06X} ACC: {:#06X} X: {:#06X} Y: {:#06X} SP: {:#06X}\nData Bank: {:#04X} Prog Bank: {:#04X} Direct Page: {:#06X}\nStatus: {:#010b} Emulation: {}"
+             ,self.pc, self.acc, self.index_x, self.index_y, self.stack_ptr, self.data_bank, self.program_bank, self.direct_page,
+             self.status.value, self.emulation as u8,
         );
     }
 
@@ -67,9 +93,11 @@ impl CpuRegisters {
         self.status.flags[flag as usize] = true;
         self.status.value |= Wrapping(1 << flag as u8);
 
-        // If the user flips from 16-bit mode to 8-bit mode, clear the top byte in the ACC.
-        if flag == StatusFlags::AccSize {
-            self.acc = Wrapping(self.acc.0 & 0x00FF);
+        // Flipping the index registers to 8-bit mode clears the top byte of X and Y.
+        // The accumulator keeps its top byte (B) when it flips to 8-bit mode.
+        if flag == StatusFlags::IndexSize {
+            self.index_x = Wrapping(self.index_x.0 & 0x00FF);
+            self.index_y = Wrapping(self.index_y.0 & 0x00FF);
         }
     }
 
@@ -78,10 +106,31 @@ impl CpuRegisters {
     ///     - `self`
     ///     - `flag`: Target flag to clear.
     pub fn clear_flag(&mut self, flag: StatusFlags) {
+        // M and X are always set in emulation mode.
+        if self.emulation && (flag == StatusFlags::AccSize || flag == StatusFlags::IndexSize) {
+            return;
+        }
+
         self.status.flags[flag as usize] = false;
         self.status.value &= Wrapping(!(1 << flag as u8));
     }
 
+    /// Enter or leave 6502 emulation mode.
+    /// Entering emulation mode forces 8-bit registers and moves the stack to page 1. Leaving it keeps the register
+    /// widths as they are, so the CPU comes out of emulation mode with 8-bit registers.
+    /// Parameters:
+    ///     - `self`
+    ///     - `enabled`: `true` to enter emulation mode, `false` to enter native mode.
+    pub fn set_emulation(&mut self, enabled: bool) {
+        self.emulation = enabled;
+
+        if enabled {
+            self.set_flag(StatusFlags::AccSize);
+            self.set_flag(StatusFlags::IndexSize);
+            self.stack_ptr = Wrapping(EMULATION_STACK_PAGE | (self.stack_ptr.0 & 0x00FF));
+        }
+    }
+
     /// Get an individual flag register value.
     /// Parameters:
     ///     - `self`
@@ -91,22 +140,79 @@ impl CpuRegisters {
     ///     - `true`: if flag is currently set
     pub fn get_flag(&self, flag: StatusFlags) -> bool { self.status.flags[flag as usize] }
 
+    /// Set or clear the Negative and Zero flags to match the result of an operation.
+    /// Parameters:
+    ///     - `self`
+    ///     - `value`: Result of the operation.
+    ///     - `width`: `REGISTER_MODE_8_BIT` or `REGISTER_MODE_16_BIT`, for the width of the result.
+    pub fn set_nz_flags(&mut self, value: u16, width: bool) {
+        let (negative, zero) = match width {
+            REGISTER_MODE_8_BIT => (value & 0x0080 != 0, value & 0x00FF == 0),
+            REGISTER_MODE_16_BIT => (value & 0x8000 != 0, value == 0),
+        };
+
+        match negative {
+            true => self.set_flag(StatusFlags::Negative),
+            false => self.clear_flag(StatusFlags::Negative),
+        }
+
+        match zero {
+            true => self.set_flag(StatusFlags::Zero),
+            false => self.clear_flag(StatusFlags::Zero),
+        }
+    }
+
     /// Get the stored register value of all of the flags.
-    pub fn _get_flag_vals(&self) -> u8 { self.status.value.0 }
+    pub fn get_flag_vals(&self) -> u8 { self.status.value.0 }
+
+    /// Set all of the flags from a stored register value, as PLP does.
+    /// M and X stay set in emulation mode, and setting X truncates the index registers.
+    /// Parameters:
+    ///     - `self`
+    ///     - `value`: New value of the status register.
+    pub fn set_flag_vals(&mut self, value: u8) {
+        for bit in 0..8 {
+            let flag = StatusFlags::from_bit(bit);
+            match value & (1 << bit) != 0 {
+                true => self.set_flag(flag),
+                false => self.clear_flag(flag),
+            }
+        }
+    }
 }
 
 /// Status Flags.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StatusFlags {
-    Carry       = 0,
-    Zero        = 1,
-    _IRQDisable = 2,
-    _Decimal    = 3,
-    _IndexSize  = 4,
-    AccSize     = 5,
-    Overflow    = 6,
-    Negative    = 7,
+    Carry      = 0,
+    Zero       = 1,
+    IRQDisable = 2,
+    Decimal    = 3,
+    IndexSize  = 4,
+    AccSize    = 5,
+    Overflow   = 6,
+    Negative   = 7,
+}
+
+impl StatusFlags {
+    /// Get the flag stored at a bit of the status register.
+    /// Parameters:
+    ///     - `bit`: Bit number, 0 to 7.
+    /// Returns:
+    ///     - The flag stored at that bit.
+    const fn from_bit(bit: u8) -> Self {
+        match bit & 0x07 {
+            0 => StatusFlags::Carry,
+            1 => StatusFlags::Zero,
+            2 => StatusFlags::IRQDisable,
+            3 => StatusFlags::Decimal,
+            4 => StatusFlags::IndexSize,
+            5 => StatusFlags::AccSize,
+            6 => StatusFlags::Overflow,
+            _ => StatusFlags::Negative,
+        }
+    }
 }
 
 ///
@@ -131,10 +237,128 @@ pub(super) struct StatusRegister {
 }
 
 impl StatusRegister {
+    /// Return the status register as it is at power on: M, X and I set, all others clear.
     pub const fn new() -> Self {
+        let mut flags = [false; 8];
+        flags[StatusFlags::IRQDisable as usize] = true;
+        flags[StatusFlags::IndexSize as usize] = true;
+        flags[StatusFlags::AccSize as usize] = true;
+
         StatusRegister {
-            flags: [false; 8],
-            value: Wrapping(0),
+            flags,
+            value: Wrapping(
+                (1 << StatusFlags::IRQDisable as u8)
+                    | (1 << StatusFlags::IndexSize as u8)
+                    | (1 << StatusFlags::AccSize as u8),
+            ),
         }
     }
 }
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a set of registers in native mode with 16-bit registers.
+    fn native_registers() -> CpuRegisters {
+        let mut registers = CpuRegisters::new();
+        registers.set_emulation(false);
+        registers.clear_flag(StatusFlags::AccSize);
+        registers.clear_flag(StatusFlags::IndexSize);
+        registers
+    }
+
+    #[test]
+    fn test_power_on_state() {
+        let registers = CpuRegisters::new();
+        assert!(registers.emulation);
+        assert!(registers.get_flag(StatusFlags::AccSize));
+        assert!(registers.get_flag(StatusFlags::IndexSize));
+        assert!(registers.get_flag(StatusFlags::IRQDisable));
+        assert_eq!(registers.get_flag_vals(), 0b0011_0100);
+        assert_eq!(registers.stack_ptr.0 & 0xFF00, EMULATION_STACK_PAGE);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut registers = native_registers();
+        registers.acc = Wrapping(0x1234);
+        registers.index_x = Wrapping(0xABCD);
+        registers.stack_ptr = Wrapping(0x1FF0);
+        registers.direct_page = Wrapping(0x4300);
+        registers.data_bank = Wrapping(0x7E);
+        registers.set_flag(StatusFlags::Decimal);
+
+        registers.reset(0x00, 0x8123);
+        assert!(registers.emulation);
+        assert!(registers.get_flag(StatusFlags::AccSize));
+        assert!(registers.get_flag(StatusFlags::IndexSize));
+        assert!(registers.get_flag(StatusFlags::IRQDisable));
+        assert!(!registers.get_flag(StatusFlags::Decimal));
+        assert_eq!(registers.acc.0, 0x1234);
+        assert_eq!(registers.index_x.0, 0x00CD);
+        assert_eq!(registers.stack_ptr.0, 0x01F0);
+        assert_eq!(registers.direct_page.0, 0x0000);
+        assert_eq!(registers.data_bank.0, 0x00);
+        assert_eq!(registers.program_bank.0, 0x00);
+        assert_eq!(registers.pc.0, 0x8123);
+    }
+
+    #[test]
+    fn test_index_size_truncates_index_registers() {
+        let mut registers = native_registers();
+        registers.acc = Wrapping(0x1234);
+        registers.index_x = Wrapping(0xABCD);
+        registers.index_y = Wrapping(0x8001);
+
+        registers.set_flag(StatusFlags::IndexSize);
+        assert_eq!(registers.index_x.0, 0x00CD);
+        assert_eq!(registers.index_y.0, 0x0001);
+        // The accumulator is sized separately.
+        assert_eq!(registers.acc.0, 0x1234);
+
+        // Returning to 16-bit mode does not restore the high byte.
+        registers.clear_flag(StatusFlags::IndexSize);
+        assert_eq!(registers.index_x.0, 0x00CD);
+        assert_eq!(registers.index_y.0, 0x0001);
+    }
+
+    #[test]
+    fn test_acc_size_leaves_index_registers() {
+        let mut registers = native_registers();
+        registers.acc = Wrapping(0x1234);
+        registers.index_x = Wrapping(0xABCD);
+        registers.index_y = Wrapping(0x8001);
+
+        registers.set_flag(StatusFlags::AccSize);
+        // The high byte of the accumulator (B) survives.
+        assert_eq!(registers.acc.0, 0x1234);
+        assert_eq!(registers.index_x.0, 0xABCD);
+        assert_eq!(registers.index_y.0, 0x8001);
+    }
+
+    #[test]
+    fn test_set_flag_vals() {
+        let mut registers = native_registers();
+        registers.index_x = Wrapping(0xABCD);
+
+        registers.set_flag_vals(0b1101_0011);
+        assert_eq!(registers.get_flag_vals(), 0b1101_0011);
+        assert!(registers.get_flag(StatusFlags::Negative));
+        assert!(registers.get_flag(StatusFlags::Overflow));
+        assert!(!registers.get_flag(StatusFlags::AccSize));
+        assert!(registers.get_flag(StatusFlags::IndexSize));
+        assert!(!registers.get_flag(StatusFlags::Decimal));
+        assert!(!registers.get_flag(StatusFlags::IRQDisable));
+        assert!(registers.get_flag(StatusFlags::Zero));
+        assert!(registers.get_flag(StatusFlags::Carry));
+        assert_eq!(registers.index_x.0, 0x00CD);
+
+        // M and X cannot be cleared in emulation mode.
+        registers.set_emulation(true);
+        registers.set_flag_vals(0x00);
+        assert_eq!(registers.get_flag_vals(), 0b0011_0000);
+    }
+}
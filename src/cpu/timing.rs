@@ -0,0 +1,117 @@
+use crate::memory;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Master clocks taken by an access to fast memory, and by internal operation cycles.
+pub(super) const FAST_ACCESS_CLOCKS: u16 = 6;
+
+/// Master clocks taken by an access to slow memory, such as WRAM and SlowROM.
+pub(super) const SLOW_ACCESS_CLOCKS: u16 = 8;
+
+/// Master clocks taken by an access to the old style joypad registers at $4000-$41FF.
+pub(super) const XSLOW_ACCESS_CLOCKS: u16 = 12;
+
+/// MEMSEL register. Bit 0 enables FastROM access in banks $80-$FF.
+pub(super) const MEMSEL_ADDR: usize = 0x00420D;
+
+/// Bit of MEMSEL which enables FastROM access.
+const MEMSEL_FAST_ROM_BIT: u8 = 0x01;
+
+/**************************************** Public Functions **************************************************************/
+
+/// Check whether FastROM access is enabled by MEMSEL.
+/// # Parameters:
+///     - `memory`: Memory to read MEMSEL from.
+/// # Returns:
+///     - `true` if ROM in banks $80-$FF is accessed at fast speed.
+pub(super) fn fast_rom_enabled(memory: &memory::Memory) -> bool {
+    memory
+        .get_byte(MEMSEL_ADDR)
+        .is_ok_and(|memsel| memsel & MEMSEL_FAST_ROM_BIT != 0)
+}
+
+/// Find the number of master clocks an access to an address takes.
+/// https://snes.nesdev.org/wiki/Timing
+///
+/// # Parameters:
+///     - `address`:    Composed 24-bit address being accessed.
+///     - `fast_rom`:   Whether FastROM access is enabled by MEMSEL.
+/// # Returns:
+///     - Number of master clocks the access takes, 6, 8 or 12.
+pub(super) fn access_clocks(address: usize, fast_rom: bool) -> u16 {
+    let bank = (address >> 16) as u8;
+    let offset = address as u16;
+
+    match bank {
+        0x00..=0x3F | 0x80..=0xBF => match offset {
+            0x0000..=0x1FFF => SLOW_ACCESS_CLOCKS,
+            0x2000..=0x3FFF => FAST_ACCESS_CLOCKS,
+            0x4000..=0x41FF => XSLOW_ACCESS_CLOCKS,
+            0x4200..=0x5FFF => FAST_ACCESS_CLOCKS,
+            0x6000..=0x7FFF => SLOW_ACCESS_CLOCKS,
+            _ => rom_access_clocks(bank, fast_rom),
+        },
+        0x40..=0x7F => SLOW_ACCESS_CLOCKS,
+        0xC0..=0xFF => rom_access_clocks(bank, fast_rom),
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Find the number of master clocks an access to ROM takes. Only banks $80-$FF can be FastROM.
+/// # Parameters:
+///     - `bank`:       Bank being accessed.
+///     - `fast_rom`:   Whether FastROM access is enabled by MEMSEL.
+/// # Returns:
+///     - Number of master clocks the access takes.
+fn rom_access_clocks(bank: u8, fast_rom: bool) -> u16 {
+    match fast_rom && bank >= 0x80 {
+        true => FAST_ACCESS_CLOCKS,
+        false => SLOW_ACCESS_CLOCKS,
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_clocks() {
+        let test_cases = vec![
+            // address, slow rom clocks, fast rom clocks
+            (0x000000, 8, 8),
+            (0x001FFF, 8, 8),
+            (0x002100, 6, 6),
+            (0x004016, 12, 12),
+            (0x004200, 6, 6),
+            (0x006000, 8, 8),
+            (0x008000, 8, 8),
+            (0x3FFFFF, 8, 8),
+            (0x7E0000, 8, 8),
+            (0x7FFFFF, 8, 8),
+            (0x800000, 8, 8),
+            (0x808000, 8, 6),
+            (0x80420D, 6, 6),
+            (0xBFFFFF, 8, 6),
+            (0xC00000, 8, 6),
+            (0xFFFFFF, 8, 6),
+        ];
+
+        for (address, slow, fast) in test_cases {
+            println!("Test Case: {:#08X}", address);
+            assert_eq!(access_clocks(address, false), slow);
+            assert_eq!(access_clocks(address, true), fast);
+        }
+    }
+
+    #[test]
+    fn test_fast_rom_enabled() {
+        let mut memory = memory::Memory::new();
+        assert!(!fast_rom_enabled(&memory));
+
+        memory.put_byte(MEMSEL_ADDR, 0x01).unwrap();
+        assert!(fast_rom_enabled(&memory));
+    }
+}
@@ -2,6 +2,7 @@ mod breakpoints;
 mod misc;
 mod parser;
 mod parser_data;
+mod speed;
 mod step;
 mod utils;
 
@@ -74,6 +75,12 @@ enum DebugCommandTypes {
     Step,
     Dump,
     Print,
+    Reset,
+    Speed,
+    Strict,
+    Poke,
+    Ppu,
+    Frame,
     _Watch,
     Exit,
     Invalid,
@@ -104,6 +111,18 @@ impl From<&str> for DebugCommandTypes {
             "s" => Self::Step,
             "step" => Self::Step,
 
+            "reset" => Self::Reset,
+
+            "speed" => Self::Speed,
+
+            "strict" => Self::Strict,
+
+            "poke" => Self::Poke,
+
+            "ppu" => Self::Ppu,
+
+            "frame" => Self::Frame,
+
             //            "w" => Self::Watch,
             //            "watch" => Self::Watch,
             _ => Self::Invalid,
@@ -118,6 +137,12 @@ struct ExitCommand;
 struct InvalidCommand;
 struct BreakCommand;
 struct StepCommand;
+struct ResetCommand;
+struct SpeedCommand;
+struct StrictCommand;
+struct PokeCommand;
+struct PpuCommand;
+struct FrameCommand;
 struct _DumpCommand;
 struct _WatchCommand;
 
@@ -132,6 +157,12 @@ impl DebugFn for DebugCommandTypes {
             DebugCommandTypes::Step => StepCommand.debug_op(args, debug, vm),
             DebugCommandTypes::Dump => todo!(),
             DebugCommandTypes::Print => PrintCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Reset => ResetCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Speed => SpeedCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Strict => StrictCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Poke => PokeCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Ppu => PpuCommand.debug_op(args, debug, vm),
+            DebugCommandTypes::Frame => FrameCommand.debug_op(args, debug, vm),
             DebugCommandTypes::_Watch => todo!(),
             DebugCommandTypes::Exit => ExitCommand.debug_op(args, debug, vm),
             DebugCommandTypes::Invalid => InvalidCommand.debug_op(args, debug, vm),
@@ -178,17 +209,23 @@ pub fn run(mut vm: VirtualMachine) {
                 vm.is_running = false;
                 println!("BREAK: Halted at {:#08X}", value);
             }
-            else {
-                vm.is_running = emu::step_cpu(&mut vm);
+            else if let Err(error) = emu::step_cpu(&mut vm) {
+                vm.is_running = false;
+                println!("BREAK: {}", error);
             }
         }
         // If the debugger is running the VM by stepping for N steps, check for how many steps are remaining.
         else if debugger.step_state.is_stepping {
-            vm.is_running = emu::step_cpu(&mut vm);
+            let result = emu::step_cpu(&mut vm);
             debugger.step_state.steps_to_run -= 1;
 
-            // Stop when we are finished running
-            if debugger.step_state.steps_to_run == 0 {
+            // Stop when we are finished running, or the CPU can't go any further.
+            if let Err(error) = result {
+                println!("BREAK: {}", error);
+                debugger.step_state.is_stepping = false;
+                vm.is_running = false;
+            }
+            else if debugger.step_state.steps_to_run == 0 {
                 debugger.step_state.is_stepping = false;
                 vm.is_running = false;
             }
@@ -199,6 +236,9 @@ pub fn run(mut vm: VirtualMachine) {
             print!(">> ");
             io::stdout().flush().unwrap();
             check_dbg_input(&mut debugger, &mut vm);
+
+            // Don't count the time spent at the prompt against the throttle.
+            vm.throttle.resume();
         }
     }
 }
@@ -1,9 +1,10 @@
 use super::{
-    ContinueCommand, DebugFn, ExitCommand, HelpCommand, InvalidCommand, PrintCommand,
-    VirtualMachine,
+    utils::HexOperators, ContinueCommand, DebugFn, ExitCommand, FrameCommand, HelpCommand, InvalidCommand,
+    PokeCommand, PpuCommand, PrintCommand, ResetCommand, StrictCommand, VirtualMachine,
 };
 use crate::debugger::InvalidDbgArgError;
-use std::process::exit;
+use crate::ppu::PpuMemory;
+use std::{fs::File, io::BufWriter, process::exit};
 
 /**************************************** Constant Values ***************************************************************/
 
@@ -28,6 +29,14 @@ impl DebugFn for HelpCommand {
         println!("exit, quit, q\n\tTerminate the program");
         println!("b $XXXXXX\n\tSets a breakpoint for address $XXXXXX");
         println!("c, r\n\tRun the program until a halt is reached, or a breakpoint is hit");
+        println!("reset\n\tReset the system and start again from the RESET vector");
+        println!("speed\n\tShow the throttle mode and the measured emulation speed");
+        println!("speed realtime, speed turbo, speed N\n\tRun at real hardware speed, unthrottled, or N times real speed");
+        println!("speed slice N\n\tSynchronise to the wall clock every N master clocks");
+        println!("strict [on|off]\n\tShow or set whether accesses to unmapped addresses break into the debugger");
+        println!("poke $XXXXXX $XX\n\tWrite byte $XX to address $XXXXXX, even if it is in ROM");
+        println!("ppu vram|cgram|oam [$XXXX]\n\tPrint 128 bytes of a PPU memory from byte $XXXX");
+        println!("frame FILE\n\tSave the last frame drawn as a PPM image");
         Ok(())
     }
 }
@@ -81,6 +90,107 @@ impl DebugFn for PrintCommand {
     }
 }
 
+impl DebugFn for ResetCommand {
+    fn debug_op(
+        &self, _args: &[&str], debug: &mut super::DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        // Stop any steps that were in progress, so the VM halts at the start of the reset.
+        debug.step_state.is_stepping = false;
+        debug.step_state.steps_to_run = 0;
+
+        vm.reset();
+        println!("Reset to {:#08X}", vm.cpu.get_pc());
+        Ok(())
+    }
+}
+
+impl DebugFn for StrictCommand {
+    fn debug_op(
+        &self, args: &[&str], _debug: &mut super::DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        match args.first() {
+            None => {}
+            Some(&"on") => vm.memory.set_strict(true),
+            Some(&"off") => vm.memory.set_strict(false),
+            Some(arg) => {
+                return Err(InvalidDbgArgError::from(format!(
+                    "Invalid strict mode {}, expected on or off.",
+                    arg
+                )))
+            }
+        }
+
+        let mode = match vm.memory.is_strict() {
+            true => "on",
+            false => "off",
+        };
+        println!("Strict mode is {}", mode);
+        Ok(())
+    }
+}
+
+impl DebugFn for PokeCommand {
+    fn debug_op(
+        &self, args: &[&str], _debug: &mut super::DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        let (address, value) = match (args.first(), args.get(1)) {
+            (Some(address), Some(value)) => (address.to_hex()?, value.to_hex()?),
+            _ => return Err(InvalidDbgArgError::from("Expected an address and a byte to write to it.")),
+        };
+        let byte = u8::try_from(value)
+            .map_err(|_| InvalidDbgArgError::from(format!("{:#X} doesn't fit in a byte.", value)))?;
+
+        match vm.memory.put_byte(address, byte) {
+            Ok(()) => {
+                println!("{:#08X} Byte Value: {:#04X}", address, byte);
+                Ok(())
+            }
+            Err(_) => Err(InvalidDbgArgError::from(format!(
+                "{:#08X} is out of range of memory.",
+                address
+            ))),
+        }
+    }
+}
+
+impl DebugFn for PpuCommand {
+    fn debug_op(
+        &self, args: &[&str], _debug: &mut super::DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        let memory = match args.first() {
+            Some(&"vram") => PpuMemory::Vram,
+            Some(&"cgram") => PpuMemory::Cgram,
+            Some(&"oam") => PpuMemory::Oam,
+            _ => return Err(InvalidDbgArgError::from("Expected a PPU memory, vram, cgram or oam.")),
+        };
+        let start = match args.get(1) {
+            Some(address) => address.to_hex()?,
+            None => 0,
+        };
+
+        vm.ppu.borrow().print_memory(memory, start);
+        Ok(())
+    }
+}
+
+impl DebugFn for FrameCommand {
+    fn debug_op(
+        &self, args: &[&str], _debug: &mut super::DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        let path = args
+            .first()
+            .ok_or(InvalidDbgArgError::from("Expected a file to save the frame to."))?;
+        let result = File::create(path).and_then(|file| vm.frame().write_ppm(&mut BufWriter::new(file)));
+        match result {
+            Ok(()) => {
+                println!("Saved frame to {}", path);
+                Ok(())
+            }
+            Err(error) => Err(InvalidDbgArgError::from(format!("Could not save frame: {}", error))),
+        }
+    }
+}
+
 /**************************************** Tests *************************************************************************/
 
 //TODO:
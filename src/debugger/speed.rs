@@ -0,0 +1,191 @@
+use super::*;
+use crate::emu::throttle::ThrottleMode;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+trait SpeedFn {
+    fn speed_op(
+        &self, args: &[&str], debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError>;
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+enum SpeedSubCommandTypes {
+    Show,
+    RealTime,
+    Turbo,
+    Slice,
+    Multiplier,
+}
+
+impl From<&str> for SpeedSubCommandTypes {
+    fn from(value: &str) -> Self {
+        match value {
+            "show" => Self::Show,
+
+            "realtime" => Self::RealTime,
+            "rt" => Self::RealTime,
+
+            "turbo" => Self::Turbo,
+
+            "slice" => Self::Slice,
+
+            _ => Self::Multiplier,
+        }
+    }
+}
+
+impl SpeedFn for SpeedSubCommandTypes {
+    fn speed_op(
+        &self, args: &[&str], debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        match self {
+            SpeedSubCommandTypes::Show => ShowOp.speed_op(args, debug, vm),
+            SpeedSubCommandTypes::RealTime => RealTimeOp.speed_op(args, debug, vm),
+            SpeedSubCommandTypes::Turbo => TurboOp.speed_op(args, debug, vm),
+            SpeedSubCommandTypes::Slice => SliceOp.speed_op(args, debug, vm),
+            SpeedSubCommandTypes::Multiplier => MultiplierOp.speed_op(args, debug, vm),
+        }
+    }
+}
+
+struct ShowOp;
+struct RealTimeOp;
+struct TurboOp;
+struct SliceOp;
+struct MultiplierOp;
+
+/**************************************** Subcommand implementations **********************************************************/
+
+/// Map the speed function to the subcommand received from the user.
+impl DebugFn for SpeedCommand {
+    fn debug_op(
+        &self, args: &[&str], debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        // If the user asked for the speed with no argument, just show it.
+        if args.is_empty() {
+            SpeedSubCommandTypes::Show.speed_op(args, debug, vm)
+        }
+        else {
+            let sub = SpeedSubCommandTypes::from(args[0]);
+            match sub {
+                SpeedSubCommandTypes::Multiplier => sub.speed_op(args, debug, vm),
+                _ => sub.speed_op(&args[1..], debug, vm),
+            }
+        }
+    }
+}
+
+/// Show the throttle mode and the measured speed.
+impl SpeedFn for ShowOp {
+    fn speed_op(
+        &self, _args: &[&str], _debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        vm.throttle.print_state();
+        println!("Synchronising every {} master clocks", vm.throttle.get_slice());
+        Ok(())
+    }
+}
+
+/// Run at the speed of real hardware.
+impl SpeedFn for RealTimeOp {
+    fn speed_op(
+        &self, _args: &[&str], _debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        vm.throttle.mode = ThrottleMode::RealTime;
+        println!("Speed set to {}", vm.throttle.mode);
+        Ok(())
+    }
+}
+
+/// Run as fast as possible.
+impl SpeedFn for TurboOp {
+    fn speed_op(
+        &self, _args: &[&str], _debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        vm.throttle.mode = ThrottleMode::Turbo;
+        println!("Speed set to {}", vm.throttle.mode);
+        Ok(())
+    }
+}
+
+/// Set how many master clocks run between each synchronisation to the wall clock.
+impl SpeedFn for SliceOp {
+    fn speed_op(
+        &self, args: &[&str], _debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        match args.first().map(|arg| arg.parse::<usize>()) {
+            Some(Ok(clocks)) if clocks > 0 => {
+                vm.throttle.set_slice(clocks);
+                println!("Synchronising every {} master clocks", clocks);
+                Ok(())
+            }
+            _ => Err(InvalidDbgArgError::from(
+                "Slice must be a whole number of master clocks greater than 0.",
+            )),
+        }
+    }
+}
+
+/// Run at a multiple of the speed of real hardware.
+impl SpeedFn for MultiplierOp {
+    fn speed_op(
+        &self, args: &[&str], _debug: &mut DebuggerState, vm: &mut VirtualMachine,
+    ) -> Result<(), InvalidDbgArgError> {
+        let value = args[0].strip_suffix('x').unwrap_or(args[0]);
+        match value.parse::<f64>() {
+            Ok(speed) if speed.is_finite() && speed > 0.0 => {
+                vm.throttle.mode = ThrottleMode::Multiplier(speed);
+                println!("Speed set to {}", vm.throttle.mode);
+                Ok(())
+            }
+            _ => Err(InvalidDbgArgError::from(format!(
+                "Invalid speed {}. Expected realtime, turbo, slice, or a multiplier greater than 0.",
+                args[0]
+            ))),
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_modes() {
+        let mut debug = DebuggerState::new();
+        let mut vm = VirtualMachine::new();
+
+        SpeedCommand.debug_op(&["turbo"], &mut debug, &mut vm).unwrap();
+        assert_eq!(vm.throttle.mode, ThrottleMode::Turbo);
+
+        SpeedCommand.debug_op(&["2.5"], &mut debug, &mut vm).unwrap();
+        assert_eq!(vm.throttle.mode, ThrottleMode::Multiplier(2.5));
+
+        SpeedCommand.debug_op(&["0.5x"], &mut debug, &mut vm).unwrap();
+        assert_eq!(vm.throttle.mode, ThrottleMode::Multiplier(0.5));
+
+        SpeedCommand.debug_op(&["rt"], &mut debug, &mut vm).unwrap();
+        assert_eq!(vm.throttle.mode, ThrottleMode::RealTime);
+
+        // Bad multipliers leave the mode alone.
+        assert!(SpeedCommand.debug_op(&["0"], &mut debug, &mut vm).is_err());
+        assert!(SpeedCommand.debug_op(&["fast"], &mut debug, &mut vm).is_err());
+        assert_eq!(vm.throttle.mode, ThrottleMode::RealTime);
+    }
+
+    #[test]
+    fn test_speed_slice() {
+        let mut debug = DebuggerState::new();
+        let mut vm = VirtualMachine::new();
+
+        SpeedCommand.debug_op(&["slice", "1364"], &mut debug, &mut vm).unwrap();
+        assert_eq!(vm.throttle.get_slice(), 1364);
+
+        assert!(SpeedCommand.debug_op(&["slice"], &mut debug, &mut vm).is_err());
+        assert!(SpeedCommand.debug_op(&["slice", "0"], &mut debug, &mut vm).is_err());
+        assert_eq!(vm.throttle.get_slice(), 1364);
+    }
+}
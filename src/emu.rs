@@ -1,64 +1,141 @@
-use std::time;
+use std::{
+    cell::{Ref, RefCell},
+    fmt,
+    rc::Rc,
+};
 
 use crate::cpu;
 use crate::cpu::instructions::INSTRUCTION_MAP;
 use crate::debugger;
 use crate::memory;
+use crate::ppu;
 use crate::romdata;
 
+pub mod scheduler;
+pub mod throttle;
+pub mod timeline;
+
+use scheduler::Component;
+use timeline::TimelineEvent;
+
 /**************************************** Constant Values ***************************************************************/
 /// The SNES master clock runs at about 21.477MHz NTSC (theoretically 1.89e9/88 Hz).
-const _MASTER_CLOCK_CYCLE_TICK_SEC: f64 = 1.0 / (21.477 * 1000.0 * 1000.0);
+/// The CPU takes 6, 8 or 12 master clocks per cycle depending on the memory it accesses, so SlowROMs run it at about
+/// 2.68MHz and FastROMs at about 3.58MHz.
+const MASTER_CLOCK_CYCLE_TICK_SEC: f64 = 1.0 / (21.477 * 1000.0 * 1000.0);
 
-/// SlowROMs run the SNES CPU at 2.68MHz.
-const SLOWROM_CLOCK_CYCLE_TICK_SEC: f64 = 1.0 / (2.68 * 1000.0 * 1000.0);
+/**************************************** Struct and Type definitions ***************************************************/
 
-/// FastROMs run the SNES CPU at 3.58MHz.
-const FASTROM_CLOCK_CYCLE_TICK_SEC: f64 = 1.0 / (3.58 * 1000.0 * 1000.0);
+/// Error which stops the system from running any further.
+///     UnimplementedOpcode:    The CPU reached an opcode that has not been implemented.
+///     UnmappedAccess:         The CPU accessed an address nothing is connected to, with strict mode on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmulationError {
+    UnimplementedOpcode(cpu::UnimplementedOpcodeError),
+    UnmappedAccess(memory::UnmappedAccessError),
+}
 
-/**************************************** Struct and Type definitions ***************************************************/
+impl fmt::Display for EmulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmulationError::UnimplementedOpcode(error) => write!(f, "{}", error),
+            EmulationError::UnmappedAccess(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<cpu::UnimplementedOpcodeError> for EmulationError {
+    fn from(value: cpu::UnimplementedOpcodeError) -> Self { Self::UnimplementedOpcode(value) }
+}
+
+impl From<memory::UnmappedAccessError> for EmulationError {
+    fn from(value: memory::UnmappedAccessError) -> Self { Self::UnmappedAccess(value) }
+}
 
 /// Struct to manage count of clocks.
+/// The master clock is the timeline every other component is measured against.
 struct ClockState {
-    clock_speed: f64,
-    _master_clock_cycles_elapsed: usize,
-    cpu_clock_cycles_elapsed: usize,
-    _ppu_clock_cycles_elapsed: usize,
+    master_clock_cycles_elapsed: usize,
 }
 
 impl ClockState {
     pub fn new() -> Self {
         Self {
-            clock_speed: 0.0,
-            _master_clock_cycles_elapsed: 0,
-            cpu_clock_cycles_elapsed: 0,
-            _ppu_clock_cycles_elapsed: 0,
+            master_clock_cycles_elapsed: 0,
         }
     }
 }
 
 /// VM Struct which contains the individual pieces of the system.
+/// Registers that belong to a component are attached to memory, and shared with the VM when it needs to run them.
 pub struct VirtualMachine {
     pub cpu: cpu::CpuState,
+    math: Rc<RefCell<cpu::math::MathUnit>>,
+    nmi: Rc<RefCell<cpu::nmi::NmiRegisters>>,
+    dma: Rc<RefCell<memory::dma::Dma>>,
+    pub ppu: Rc<RefCell<ppu::Ppu>>,
     pub memory: memory::Memory,
     pub romdata: romdata::RomData,
     clocks: ClockState,
+    timeline: timeline::Timeline,
+    scheduler: scheduler::Scheduler,
+    pub throttle: throttle::Throttle,
     pub is_running: bool,
 }
 
 impl VirtualMachine {
     pub fn new() -> Self {
+        let math = Rc::new(RefCell::new(cpu::math::MathUnit::new()));
+        let nmi = Rc::new(RefCell::new(cpu::nmi::NmiRegisters::new()));
+        let dma = Rc::new(RefCell::new(memory::dma::Dma::new()));
+        let ppu = Rc::new(RefCell::new(ppu::Ppu::new()));
+
+        let mut memory = memory::Memory::new();
+        let registers: [(_, memory::io::IoHandler); 7] = [
+            (cpu::math::WRITE_REGISTERS, math.clone()),
+            (cpu::math::READ_REGISTERS, math.clone()),
+            (cpu::nmi::WRITE_REGISTERS, nmi.clone()),
+            (cpu::nmi::READ_REGISTERS, nmi.clone()),
+            (ppu::REGISTERS, ppu.clone()),
+            (memory::dma::ENABLE_REGISTERS, dma.clone()),
+            (memory::dma::CHANNEL_REGISTERS, dma.clone()),
+        ];
+        for (range, handler) in registers {
+            memory
+                .register_io(range, handler)
+                .expect("System registers are in the I/O area, and don't overlap");
+        }
+
+        // The math unit and the PPU run behind the CPU. DMA stops the CPU instead, so it is run on its own.
+        let mut scheduler = scheduler::Scheduler::new();
+        scheduler.add(math.clone());
+        scheduler.add(ppu.clone());
+
         Self {
             cpu: cpu::CpuState::new(),
-            memory: memory::Memory::new(),
+            math,
+            nmi,
+            dma,
+            ppu,
+            memory,
             romdata: romdata::RomData::new(),
             clocks: ClockState::new(),
+            timeline: timeline::Timeline::new(),
+            scheduler,
+            throttle: throttle::Throttle::new(),
             is_running: false,
         }
     }
 
     pub fn print_state(&self) {
         self.cpu.print_state();
+        println!(
+            "Master clocks elapsed: {}",
+            self.clocks.master_clock_cycles_elapsed
+        );
+        self.timeline.print_state();
+        self.ppu.borrow().print_state();
+        self.throttle.print_state();
 
         let pc_val = self
             .memory
@@ -70,6 +147,23 @@ impl VirtualMachine {
             pc_val, INSTRUCTION_MAP[pc_val as usize].opcode
         );
     }
+
+    /// Get the last frame the PPU finished drawing.
+    pub fn frame(&self) -> Ref<'_, ppu::render::Frame> { Ref::map(self.ppu.borrow(), |ppu| ppu.frame()) }
+
+    /// Reset the system, as if the reset button was pressed. The loaded ROM is kept.
+    /// Retail ROMs start from their RESET vector. Headerless test ROMs have no vectors, so they start from the first
+    /// byte of the ROM at $80:8000.
+    pub fn reset(&mut self) {
+        let (bank, pc) = match self.romdata.is_headerless {
+            true => (romdata::LO_ROM_BANK_ADDR, romdata::ROM_BASE_ADDR),
+            false => (0x00, self.romdata.reset_vector()),
+        };
+        self.cpu.reset(bank, pc);
+        self.cpu
+            .set_exception_vectors(self.romdata.exception_vectors);
+        self.is_running = false;
+    }
 }
 
 /**************************************** File Scope Functions **********************************************************/
@@ -98,10 +192,7 @@ pub fn run(path: std::path::PathBuf, args: Vec<String>) {
     }
     println!("Success.");
 
-    vm.clocks.clock_speed = match vm.romdata.mode.speed {
-        romdata::RomClkSpeed::SlowRom => SLOWROM_CLOCK_CYCLE_TICK_SEC,
-        romdata::RomClkSpeed::FastRom => FASTROM_CLOCK_CYCLE_TICK_SEC,
-    };
+    vm.reset();
 
     // If the user wants to use the debugger, let it delegate the run loop.
     let debugger_enabled = true;
@@ -110,36 +201,400 @@ pub fn run(path: std::path::PathBuf, args: Vec<String>) {
     }
     else {
         vm.is_running = true;
+        vm.throttle.resume();
         while vm.is_running {
-            // TODO: Should CPU be threaded or should this file be the king?
-            // TODO: Spin off thread for SPC700(?)
-            // TODO: Spin off thread for PPU(?)
+            if let Err(error) = step_cpu(&mut vm) {
+                println!("Halted: {}", error);
+                vm.is_running = false;
+            }
+        }
+    }
+}
 
-            vm.is_running = step_cpu(&mut vm);
+/// Request the CPU to step one operation, and then run the rest of the system until it has caught up.
+/// The CPU leads the master clock. Every other component is run up to the point the CPU has reached, and the master
+/// clocks taken are accounted against the throttle.
+/// The CPU being stopped or waiting for an interrupt does not stop the VM, since the rest of the system keeps running.
+/// # Parameters:
+///     - `vm`:         Pointer to VM containing state for the emulator.
+/// # Returns:
+///     - `Ok(())`:     If the VM is still running.
+///     - `Err(error)`: If a component could not continue, such as the CPU reaching an unimplemented opcode, or
+///                     accessing an unmapped address in strict mode.
+pub fn step_cpu(vm: &mut VirtualMachine) -> Result<(), EmulationError> {
+    let step = vm.cpu.step(&mut vm.memory)?;
+    let cpu_clocks = step.clocks as usize;
+
+    // The math unit runs one step for each cycle the CPU ran.
+    vm.math
+        .borrow_mut()
+        .queue_cycles(step.cycles as usize, cpu_clocks);
+    let mut master_clocks = run_system(vm, cpu_clocks)?;
+
+    // A DMA started by the instruction stops the CPU until it has finished. It runs up to each event on the timeline
+    // in turn, so HDMA can interrupt it.
+    loop {
+        let start = vm.clocks.master_clock_cycles_elapsed;
+        let target = start + vm.timeline.clocks_to_next_event();
+        let dma_clocks = vm.dma.borrow_mut().run_until(start, target, &mut vm.memory)? - start;
+        if dma_clocks == 0 {
+            break;
         }
+        master_clocks += run_system(vm, dma_clocks)?;
+    }
+
+    vm.throttle.advance(master_clocks);
+
+    // An NMI raised by vertical blanking, or by the instruction enabling it, is taken before the next instruction.
+    if vm.nmi.borrow_mut().take_nmi() {
+        vm.cpu.request_nmi();
+    }
+
+    // The instruction which made the access has finished, so the PC is already past it.
+    match vm.memory.take_fault() {
+        Some(fault) => Err(fault.into()),
+        None => Ok(()),
     }
 }
 
-/// Request the CPU to step one operation, and then pend for the number of cycles it (should) take for those operations to run.
+/// Run the rest of the system alongside the CPU, handling each event on the timeline as it is reached.
+/// Events such as HDMA can stop the CPU, which lengthens the time run.
 /// # Parameters:
 ///     - `vm`:         Pointer to VM containing state for the emulator.
+///     - `clocks`:     Master clocks the CPU has run for.
 /// # Returns:
-///     - `vm_running`: Whether the VM is running or has stopped.
-pub fn step_cpu(vm: &mut VirtualMachine) -> bool {
-    let mut vm_running = true;
-    // If there is no need to pend on another cycle, then go ahead and run an operation.
-    if vm.cpu.cycles_to_pend == 0 {
-        vm_running = vm.cpu.step(&mut vm.memory);
-        println!(
-            "Next instruction stalled by {} cycles",
-            vm.cpu.cycles_to_pend
+///     - `Ok(clocks)`: The number of master clocks run, including any time the CPU was stopped for.
+///     - `Err(error)`: If a component could not continue.
+fn run_system(vm: &mut VirtualMachine, clocks: usize) -> Result<usize, EmulationError> {
+    let mut remaining = clocks;
+    let mut total = 0;
+    while remaining > 0 {
+        // HDMA runs on the visible lines, which the PPU's overscan setting can extend.
+        vm.timeline.set_vblank_line(vm.ppu.borrow().vblank_line());
+        let step = remaining.min(vm.timeline.clocks_to_next_event());
+        remaining -= step;
+        total += step;
+        vm.clocks.master_clock_cycles_elapsed += step;
+
+        // Components which run alongside the CPU catch up to the master clock here.
+        vm.scheduler
+            .catch_up(vm.clocks.master_clock_cycles_elapsed, &mut vm.memory)?;
+
+        remaining += match vm.timeline.advance(step) {
+            Some(TimelineEvent::VBlankEnd) => {
+                vm.nmi.borrow_mut().end_vblank();
+                0
+            }
+            Some(TimelineEvent::HdmaInit) => memory::dma::init_hdma(&vm.dma, &mut vm.memory),
+            Some(TimelineEvent::HdmaLine) => memory::dma::run_hdma(&vm.dma, &mut vm.memory),
+            Some(TimelineEvent::VBlankStart) => {
+                vm.nmi.borrow_mut().start_vblank();
+                0
+            }
+            None => 0,
+        };
+    }
+    Ok(total)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_headerless() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.is_running = true;
+
+        vm.reset();
+        assert_eq!(vm.cpu.get_pc(), 0x808000);
+        assert!(!vm.is_running);
+    }
+
+    #[test]
+    fn test_reset_retail() {
+        let mut vm = VirtualMachine::new();
+        // Emulation mode RESET vector at $00:FFFC.
+        vm.romdata.exception_vectors[0x1C] = 0x00;
+        vm.romdata.exception_vectors[0x1D] = 0x90;
+
+        vm.reset();
+        assert_eq!(vm.cpu.get_pc(), 0x009000);
+
+        // Resetting again after running somewhere else starts back at the vector.
+        vm.cpu.reset(0x12, 0x3456);
+        vm.reset();
+        assert_eq!(vm.cpu.get_pc(), 0x009000);
+    }
+
+    #[test]
+    fn test_reset_loads_exception_vectors() {
+        let mut vm = VirtualMachine::new();
+        // Emulation mode NMI vector at $00:FFFA.
+        vm.romdata.exception_vectors[0x1A] = 0x00;
+        vm.romdata.exception_vectors[0x1B] = 0xA0;
+
+        vm.reset();
+        vm.cpu.request_nmi();
+        vm.cpu.step(&mut vm.memory).unwrap();
+        assert_eq!(vm.cpu.get_pc(), 0x00A000);
+    }
+
+    #[test]
+    fn test_step_cpu_stopped() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // A stopped CPU leaves the VM running.
+        vm.memory.put_byte(0x808000, 0xDB).unwrap();
+        assert!(step_cpu(&mut vm).is_ok());
+        assert!(step_cpu(&mut vm).is_ok());
+        assert_eq!(vm.cpu.run_state, cpu::CpuRunState::Stopped);
+    }
+
+    #[test]
+    fn test_step_cpu_master_clocks() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // NOP from SlowROM: the opcode fetch takes 8 master clocks, and the internal cycle takes 6.
+        vm.memory.put_byte(0x808000, 0xEA).unwrap();
+        step_cpu(&mut vm).unwrap();
+        assert_eq!(vm.clocks.master_clock_cycles_elapsed, 14);
+
+        // With FastROM enabled in MEMSEL, the fetch from bank $80 takes 6.
+        vm.memory.put_byte(0x00420D, 0x01).unwrap();
+        vm.memory.put_byte(0x808001, 0xEA).unwrap();
+        step_cpu(&mut vm).unwrap();
+        assert_eq!(vm.clocks.master_clock_cycles_elapsed, 26);
+    }
+
+    #[test]
+    fn test_step_cpu_strict_mode() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // LDA $2000 reads open bus, which is only reported in strict mode.
+        for (offset, byte) in [0xAD, 0x00, 0x20, 0xAD, 0x00, 0x20].into_iter().enumerate() {
+            vm.memory.put_byte(0x808000 + offset, byte).unwrap();
+        }
+        assert!(step_cpu(&mut vm).is_ok());
+
+        vm.memory.set_strict(true);
+        let error = step_cpu(&mut vm).unwrap_err();
+        assert_eq!(error.to_string(), "unmapped read from $002000");
+        assert_eq!(vm.cpu.get_pc(), 0x808006);
+    }
+
+    #[test]
+    fn test_step_cpu_runs_math_unit() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // Bit 6 of WRMPYA is multiplied in on the 7th cycle.
+        vm.memory.write(0x004202, 0x40);
+        vm.memory.write(0x004203, 0x02);
+        assert_eq!(vm.memory.read(0x004216), 0x00);
+
+        // The unit counts CPU cycles, not master clocks. Three NOPs from SlowROM are 6 cycles, even though their 42
+        // master clocks would be 7 fast cycles.
+        for offset in 0..3 {
+            vm.memory.put_byte(0x808000 + offset, 0xEA).unwrap();
+            step_cpu(&mut vm).unwrap();
+        }
+        assert_eq!(vm.memory.read(0x004216), 0x00);
+        vm.memory.put_byte(0x808003, 0xEA).unwrap();
+        step_cpu(&mut vm).unwrap();
+        assert_eq!(vm.memory.read(0x004216), 0x80);
+
+        // The PPU's multiplier is attached too.
+        vm.memory.write(0x00211B, 0x00);
+        vm.memory.write(0x00211B, 0x01);
+        vm.memory.write(0x00211C, 0xFF);
+        assert_eq!(vm.memory.read(0x002135), 0xFF);
+        assert_eq!(vm.memory.read(0x002136), 0xFF);
+    }
+
+    #[test]
+    fn test_step_cpu_runs_ppu() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // VRAM is reached through the PPU's port.
+        vm.memory.write(0x002116, 0x34);
+        vm.memory.write(0x002117, 0x12);
+        vm.memory.write(0x002118, 0xAB);
+        assert_eq!(
+            vm.ppu.borrow().get_memory_byte(ppu::PpuMemory::Vram, 0x2468),
+            0xAB
+        );
+
+        // The PPU counts a dot for every 4 master clocks run, which SLHV latches into OPHCT.
+        for offset in 0..4 {
+            vm.memory.put_byte(0x808000 + offset, 0xEA).unwrap();
+            step_cpu(&mut vm).unwrap();
+        }
+        vm.memory.read(0x002137);
+        assert_eq!(
+            vm.memory.read(0x00213C) as usize,
+            vm.clocks.master_clock_cycles_elapsed / 4
         );
     }
-    // Otherwise, punt on operating for however long we need to.
-    else if vm.cpu.cycles_to_pend > 0 {
-        std::thread::sleep(time::Duration::from_secs_f64(vm.clocks.clock_speed));
-        vm.clocks.cpu_clock_cycles_elapsed += 1;
-        vm.cpu.cycles_to_pend -= 1;
+
+    #[test]
+    fn test_vblank_nmi() {
+        let test_cases = vec![
+            // NMITIMEN before VBlank, NMITIMEN written during VBlank, whether RDNMI is read first, where the CPU is
+            // left waiting
+            (0x00, 0x00, false, 0x808001),
+            (0x80, 0x80, false, 0x00A001),
+            // Enabling the NMI part way through VBlank raises it straight away, unless RDNMI has been read.
+            (0x00, 0x80, false, 0x00A001),
+            (0x00, 0x80, true, 0x808001),
+        ];
+
+        for (nmitimen, nmitimen_vblank, read_first, pc) in test_cases {
+            println!(
+                "Test Case: NMITIMEN {:#04X} then {:#04X}, RDNMI read first: {}",
+                nmitimen, nmitimen_vblank, read_first
+            );
+            let mut vm = VirtualMachine::new();
+            vm.romdata.is_headerless = true;
+            // Emulation mode NMI vector at $00:FFFA.
+            vm.romdata.exception_vectors[0x1A] = 0x00;
+            vm.romdata.exception_vectors[0x1B] = 0xA0;
+            vm.reset();
+
+            // Wait for an interrupt, and wait again in the NMI handler.
+            vm.memory.put_byte(0x808000, 0xCB).unwrap();
+            vm.memory.put_byte(0x00A000, 0xCB).unwrap();
+            vm.memory.write(0x004200, nmitimen);
+            while vm.clocks.master_clock_cycles_elapsed < 226 * timeline::CLOCKS_PER_LINE {
+                step_cpu(&mut vm).unwrap();
+            }
+
+            // RDNMI is flagged at the start of vertical blanking either way, until it is read.
+            if read_first {
+                assert_eq!(vm.memory.read(0x004210), 0x82);
+            }
+            vm.memory.write(0x004200, nmitimen_vblank);
+            while vm.clocks.master_clock_cycles_elapsed < 227 * timeline::CLOCKS_PER_LINE {
+                step_cpu(&mut vm).unwrap();
+            }
+            assert_eq!(vm.cpu.get_pc(), pc);
+
+            if !read_first {
+                assert_eq!(vm.memory.read(0x004210), 0x82);
+            }
+            assert_eq!(vm.memory.read(0x004210), 0x02);
+        }
+    }
+
+    #[test]
+    fn test_frame() {
+        let mut vm = VirtualMachine::new();
+        vm.memory.write(0x002100, 0x0F);
+        vm.memory.write(0x002122, 0x1F);
+        vm.memory.write(0x002122, 0x00);
+
+        // The frame is finished at the start of vertical blanking.
+        run_system(&mut vm, 200 * timeline::CLOCKS_PER_LINE).unwrap();
+        assert_eq!(vm.frame().pixel(0, 0), 0x0000);
+        run_system(&mut vm, 25 * timeline::CLOCKS_PER_LINE).unwrap();
+        assert_eq!(vm.frame().height(), 224);
+        assert_eq!(vm.frame().pixel(0, 0), 0x001F);
+        assert_eq!(vm.frame().pixel(255, 223), 0x001F);
+    }
+
+    #[test]
+    fn test_step_cpu_runs_dma() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+
+        // Copy 4 bytes of ROM into WRAM through the WMDATA port.
+        for (offset, byte) in [0x01, 0x02, 0x03, 0x04].into_iter().enumerate() {
+            vm.memory.put_byte(0x809000 + offset, byte).unwrap();
+        }
+        for (offset, byte) in [0x00, 0x80, 0x00, 0x90, 0x80, 0x04, 0x00].into_iter().enumerate() {
+            vm.memory.write(0x004300 + offset, byte);
+        }
+
+        // LDA #$01, STA $420B from SlowROM. The store takes 3 slow fetches and a fast write, then the DMA runs.
+        for (offset, byte) in [0xA9, 0x01, 0x8D, 0x0B, 0x42].into_iter().enumerate() {
+            vm.memory.put_byte(0x808000 + offset, byte).unwrap();
+        }
+        step_cpu(&mut vm).unwrap();
+        assert_eq!(vm.clocks.master_clock_cycles_elapsed, 16);
+        step_cpu(&mut vm).unwrap();
+
+        for offset in 0..4 {
+            assert_eq!(vm.memory.get_byte(0x7E0000 + offset).unwrap(), offset as u8 + 1);
+        }
+        // 30 clocks for the store, 2 to reach a multiple of 8, then 8 + 8 + 4 * 8 for the DMA.
+        assert_eq!(vm.clocks.master_clock_cycles_elapsed, 16 + 30 + 2 + 8 + 8 + 32);
+    }
+
+    #[test]
+    fn test_step_cpu_runs_hdma() {
+        let mut vm = VirtualMachine::new();
+        vm.romdata.is_headerless = true;
+        vm.reset();
+        vm.memory.put_byte(0x808000, 0xDB).unwrap();
+
+        // Write one byte to WMDATA on each of the first two lines, from a table at $7E:2000.
+        for (offset, byte) in [0x01, 0xAA, 0x01, 0xBB, 0x00].into_iter().enumerate() {
+            vm.memory.write(0x7E2000 + offset, byte);
+        }
+        for (offset, byte) in [0x00, 0x80, 0x00, 0x20, 0x7E].into_iter().enumerate() {
+            vm.memory.write(0x004300 + offset, byte);
+        }
+        vm.memory.write(0x00420C, 0x01);
+
+        // The CPU is stopped, but the rest of the system keeps running.
+        while vm.clocks.master_clock_cycles_elapsed < 3 * timeline::CLOCKS_PER_LINE {
+            step_cpu(&mut vm).unwrap();
+        }
+        assert_eq!(vm.memory.get_byte(0x7E0000).unwrap(), 0xAA);
+        assert_eq!(vm.memory.get_byte(0x7E0001).unwrap(), 0xBB);
+        assert_eq!(vm.memory.get_byte(0x7E0002).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_hdma_overscan() {
+        let test_cases = vec![
+            // SETINI, byte written on line 239
+            (0x00, 0x00),
+            (0x04, 0xCC),
+        ];
+
+        for (setini, expected) in test_cases {
+            println!("Test Case: SETINI {:#04X}", setini);
+            let mut vm = VirtualMachine::new();
+            vm.memory.write(0x002133, setini);
+
+            // Write one byte to WMDATA on lines 0, 127 and 239, from a table at $7E:2000.
+            let table = [0x7F, 0xAA, 0x70, 0xBB, 0x01, 0xCC, 0x00];
+            for (offset, byte) in table.into_iter().enumerate() {
+                vm.memory.write(0x7E2000 + offset, byte);
+            }
+            for (offset, byte) in [0x00, 0x80, 0x00, 0x20, 0x7E].into_iter().enumerate() {
+                vm.memory.write(0x004300 + offset, byte);
+            }
+            vm.memory.write(0x00420C, 0x01);
+
+            // HDMA runs on every visible line, which overscan extends to line 239.
+            run_system(&mut vm, 241 * timeline::CLOCKS_PER_LINE).unwrap();
+            assert_eq!(vm.memory.get_byte(0x7E0001).unwrap(), 0xBB);
+            assert_eq!(vm.memory.get_byte(0x7E0002).unwrap(), expected);
+        }
     }
-    vm_running
 }
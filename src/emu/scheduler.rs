@@ -0,0 +1,188 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::EmulationError;
+use crate::memory;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// A piece of the system which runs against the master clock, such as the CPU, PPU or APU.
+/// Everything runs on one thread. The CPU leads, and after each of its steps every other component is run until it
+/// has caught up to the master clock, so the order things happen in is the same on every run.
+pub trait Component {
+    /// Run the next unit of work, such as one instruction or one dot.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory shared by every component.
+    /// # Returns:
+    ///     - `Ok(clocks)`: The number of master clocks the work took.
+    ///     - `Err(error)`: If the component could not continue.
+    fn tick(&mut self, memory: &mut memory::Memory) -> Result<usize, EmulationError>;
+
+    /// Run until the component has reached a point on the master clock.
+    /// A tick is never split, so the component may finish past `target`.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `elapsed`:    Master clock the component has been run up to so far.
+    ///     - `target`:     Master clock to run up to.
+    ///     - `memory`:     Memory shared by every component.
+    /// # Returns:
+    ///     - `Ok(elapsed)`:    The master clock the component has now been run up to.
+    ///     - `Err(error)`:     If the component could not continue.
+    fn run_until(
+        &mut self, elapsed: usize, target: usize, memory: &mut memory::Memory,
+    ) -> Result<usize, EmulationError> {
+        let mut elapsed = elapsed;
+        while elapsed < target {
+            elapsed += self.tick(memory)?;
+        }
+        Ok(elapsed)
+    }
+}
+
+/// A component which runs behind the CPU, and the master clock it has been run up to.
+///     component:  Component to run.
+///     elapsed:    Master clock the component has been run up to so far.
+struct Follower {
+    component: Rc<RefCell<dyn Component>>,
+    elapsed: usize,
+}
+
+/// Every component which runs behind the CPU, in the order they are caught up.
+pub struct Scheduler {
+    followers: Vec<Follower>,
+}
+
+impl Scheduler {
+    /// Return a scheduler with no components.
+    pub fn new() -> Self { Self { followers: vec![] } }
+
+    /// Add a component to run behind the CPU, from the start of the master clock.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `component`:  Component to run. Its registers can stay attached to memory, as long as it doesn't access
+    ///                     them itself through memory while it runs.
+    pub fn add(&mut self, component: Rc<RefCell<dyn Component>>) {
+        self.followers.push(Follower {
+            component,
+            elapsed: 0,
+        });
+    }
+
+    /// Bring every component up to a point on the master clock, in the order they were added.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `target`: Master clock to run up to.
+    ///     - `memory`: Memory shared by every component.
+    /// # Returns:
+    ///     - `Ok(())`:     If every component has caught up.
+    ///     - `Err(error)`: If a component could not continue.
+    pub fn catch_up(&mut self, target: usize, memory: &mut memory::Memory) -> Result<(), EmulationError> {
+        for follower in self.followers.iter_mut() {
+            catch_up(
+                &mut *follower.component.borrow_mut(),
+                &mut follower.elapsed,
+                target,
+                memory,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// Bring a component up to a point on the master clock, and record how far it got.
+/// # Parameters:
+///     - `component`:  Component to run.
+///     - `elapsed`:    Master clock the component has been run up to, which is updated once it has run.
+///     - `target`:     Master clock to run up to.
+///     - `memory`:     Memory shared by every component.
+/// # Returns:
+///     - `Ok(())`:     If the component has caught up.
+///     - `Err(error)`: If the component could not continue.
+pub fn catch_up(
+    component: &mut (impl Component + ?Sized), elapsed: &mut usize, target: usize,
+    memory: &mut memory::Memory,
+) -> Result<(), EmulationError> {
+    *elapsed = component.run_until(*elapsed, target, memory)?;
+    Ok(())
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Component which takes a fixed number of master clocks per tick, and counts its ticks.
+    struct FixedComponent {
+        clocks_per_tick: usize,
+        ticks: usize,
+    }
+
+    impl Component for FixedComponent {
+        fn tick(&mut self, _memory: &mut memory::Memory) -> Result<usize, EmulationError> {
+            self.ticks += 1;
+            Ok(self.clocks_per_tick)
+        }
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut memory = memory::Memory::new();
+        let mut component = FixedComponent {
+            clocks_per_tick: 4,
+            ticks: 0,
+        };
+
+        // Already caught up, so nothing runs.
+        assert_eq!(component.run_until(8, 8, &mut memory).unwrap(), 8);
+        assert_eq!(component.ticks, 0);
+
+        // Ticks are not split, so it runs past the target.
+        assert_eq!(component.run_until(8, 18, &mut memory).unwrap(), 20);
+        assert_eq!(component.ticks, 3);
+    }
+
+    #[test]
+    fn test_catch_up() {
+        let mut memory = memory::Memory::new();
+        let mut component = FixedComponent {
+            clocks_per_tick: 5,
+            ticks: 0,
+        };
+        let mut elapsed = 0;
+
+        catch_up(&mut component, &mut elapsed, 14, &mut memory).unwrap();
+        assert_eq!(elapsed, 15);
+
+        // The overshoot is carried into the next catch up.
+        catch_up(&mut component, &mut elapsed, 28, &mut memory).unwrap();
+        assert_eq!(elapsed, 30);
+        assert_eq!(component.ticks, 6);
+    }
+
+    #[test]
+    fn test_scheduler() {
+        let mut memory = memory::Memory::new();
+        let fast = Rc::new(RefCell::new(FixedComponent {
+            clocks_per_tick: 2,
+            ticks: 0,
+        }));
+        let slow = Rc::new(RefCell::new(FixedComponent {
+            clocks_per_tick: 7,
+            ticks: 0,
+        }));
+        let mut scheduler = Scheduler::new();
+        scheduler.add(fast.clone());
+        scheduler.add(slow.clone());
+
+        // Each component keeps its own place on the master clock.
+        scheduler.catch_up(10, &mut memory).unwrap();
+        assert_eq!(fast.borrow().ticks, 5);
+        assert_eq!(slow.borrow().ticks, 2);
+        scheduler.catch_up(14, &mut memory).unwrap();
+        assert_eq!(fast.borrow().ticks, 7);
+        assert_eq!(slow.borrow().ticks, 2);
+    }
+}
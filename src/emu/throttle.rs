@@ -0,0 +1,206 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::timeline::{CLOCKS_PER_LINE, LINES_PER_FRAME};
+use super::MASTER_CLOCK_CYCLE_TICK_SEC;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Master clocks in one NTSC frame.
+pub const MASTER_CLOCKS_PER_FRAME: usize = LINES_PER_FRAME * CLOCKS_PER_LINE;
+
+/// Master clock rate of real hardware, in MHz.
+const MASTER_CLOCK_MHZ: f64 = 21.477;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// How fast emulation runs against the wall clock.
+///     RealTime:       Run at the speed of real hardware.
+///     Turbo:          Run as fast as the host allows.
+///     Multiplier(n):  Run at `n` times the speed of real hardware.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThrottleMode {
+    RealTime,
+    Turbo,
+    Multiplier(f64),
+}
+
+impl fmt::Display for ThrottleMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThrottleMode::RealTime => write!(f, "real time"),
+            ThrottleMode::Turbo => write!(f, "turbo"),
+            ThrottleMode::Multiplier(speed) => write!(f, "{}x", speed),
+        }
+    }
+}
+
+/// Paces emulation against the wall clock.
+/// Emulation runs unthrottled for a slice of master clocks, and then sleeps off whatever is left of the time that
+/// slice should have taken.
+///     mode:               How fast to run.
+///     slice_clocks:       Master clocks to run between each synchronisation, one frame by default.
+///     clocks_in_slice:    Master clocks run so far in the current slice.
+///     slice_start:        Wall clock time the current slice started at.
+///     measured_mhz:       Master clock rate measured over the last full slice, if one has finished.
+#[derive(Debug)]
+pub struct Throttle {
+    pub mode: ThrottleMode,
+    slice_clocks: usize,
+    clocks_in_slice: usize,
+    slice_start: Instant,
+    measured_mhz: Option<f64>,
+}
+
+impl Throttle {
+    /// Return a real time throttle which synchronises once per frame.
+    pub fn new() -> Self {
+        Self {
+            mode: ThrottleMode::RealTime,
+            slice_clocks: MASTER_CLOCKS_PER_FRAME,
+            clocks_in_slice: 0,
+            slice_start: Instant::now(),
+            measured_mhz: None,
+        }
+    }
+
+    /// Set the number of master clocks to run between each synchronisation.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `clocks`: Master clocks in a slice. Must not be 0.
+    pub fn set_slice(&mut self, clocks: usize) {
+        self.slice_clocks = clocks;
+        self.resume();
+    }
+
+    /// Get the number of master clocks run between each synchronisation.
+    pub fn get_slice(&self) -> usize { self.slice_clocks }
+
+    /// Start a new slice from now, discarding the current one.
+    /// Call this when emulation resumes after being paused, so the pause is not counted against the slice.
+    pub fn resume(&mut self) {
+        self.clocks_in_slice = 0;
+        self.slice_start = Instant::now();
+    }
+
+    /// Account for master clocks that have been emulated, and synchronise to the wall clock if the slice is over.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `master_clocks`:  Master clocks which have just been emulated.
+    pub fn advance(&mut self, master_clocks: usize) {
+        self.clocks_in_slice += master_clocks;
+        if self.clocks_in_slice < self.slice_clocks {
+            return;
+        }
+
+        if let Some(target) = self.slice_duration(self.clocks_in_slice) {
+            if let Some(remaining) = target.checked_sub(self.slice_start.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+        self.end_slice(self.slice_start.elapsed());
+    }
+
+    /// Find how long a slice should take on the wall clock.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `master_clocks`:  Master clocks emulated in the slice.
+    /// # Returns:
+    ///     - `Some(duration)`: The wall clock time the slice should take.
+    ///     - `None`:           If the slice should not be throttled.
+    fn slice_duration(&self, master_clocks: usize) -> Option<Duration> {
+        let real_time = master_clocks as f64 * MASTER_CLOCK_CYCLE_TICK_SEC;
+        match self.mode {
+            ThrottleMode::RealTime => Some(Duration::from_secs_f64(real_time)),
+            ThrottleMode::Turbo => None,
+            ThrottleMode::Multiplier(speed) => Some(Duration::from_secs_f64(real_time / speed)),
+        }
+    }
+
+    /// Record the speed of a finished slice, and start the next one.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `elapsed`:    Wall clock time the slice took, including any time spent sleeping.
+    fn end_slice(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.measured_mhz = Some(self.clocks_in_slice as f64 / seconds / 1_000_000.0);
+        }
+        self.resume();
+    }
+
+    /// Print the throttle mode and measured speed.
+    pub fn print_state(&self) {
+        match self.measured_mhz {
+            Some(mhz) => println!(
+                "Speed: {} ({:.3}MHz, {:.1}% of real hardware)",
+                self.mode,
+                mhz,
+                mhz / MASTER_CLOCK_MHZ * 100.0
+            ),
+            None => println!("Speed: {} (not measured yet)", self.mode),
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_duration() {
+        let mut throttle = Throttle::new();
+        let one_second = (1.0 / MASTER_CLOCK_CYCLE_TICK_SEC) as usize;
+
+        let real_time = throttle.slice_duration(one_second).unwrap();
+        assert!((real_time.as_secs_f64() - 1.0).abs() < 1e-6);
+
+        throttle.mode = ThrottleMode::Multiplier(2.0);
+        let double = throttle.slice_duration(one_second).unwrap();
+        assert!((double.as_secs_f64() - 0.5).abs() < 1e-6);
+
+        throttle.mode = ThrottleMode::Turbo;
+        assert_eq!(throttle.slice_duration(one_second), None);
+    }
+
+    #[test]
+    fn test_advance_slices() {
+        let mut throttle = Throttle::new();
+        throttle.mode = ThrottleMode::Turbo;
+        throttle.set_slice(100);
+
+        // Nothing is measured until a full slice has run.
+        throttle.advance(99);
+        assert_eq!(throttle.clocks_in_slice, 99);
+        assert_eq!(throttle.measured_mhz, None);
+
+        throttle.advance(14);
+        assert_eq!(throttle.clocks_in_slice, 0);
+    }
+
+    #[test]
+    fn test_end_slice_measures_speed() {
+        let mut throttle = Throttle::new();
+        throttle.clocks_in_slice = MASTER_CLOCKS_PER_FRAME;
+
+        // A frame which took a sixtieth of a second ran at about 21.44MHz.
+        throttle.end_slice(Duration::from_secs_f64(1.0 / 60.0));
+        let mhz = throttle.measured_mhz.unwrap();
+        assert!((mhz - 21.442).abs() < 0.001);
+        assert_eq!(throttle.clocks_in_slice, 0);
+    }
+
+    #[test]
+    fn test_realtime_sleeps() {
+        let mut throttle = Throttle::new();
+        let start = Instant::now();
+
+        // A whole frame in real time takes about 16.6ms.
+        throttle.advance(MASTER_CLOCKS_PER_FRAME);
+        assert!(start.elapsed() >= Duration::from_millis(16));
+        let mhz = throttle.measured_mhz.unwrap();
+        assert!(mhz <= MASTER_CLOCK_MHZ + 0.1);
+    }
+}
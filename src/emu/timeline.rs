@@ -0,0 +1,203 @@
+use crate::ppu;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Master clocks in one scanline. Every NTSC scanline is treated as this long, ignoring the short and long lines.
+pub const CLOCKS_PER_LINE: usize = 1364;
+
+/// Scanlines in one NTSC frame.
+pub const LINES_PER_FRAME: usize = 262;
+
+/// Master clock within line 0 that HDMA is initialised at, approximately.
+const HDMA_INIT_CLOCK: usize = 20;
+
+/// Master clock within each line that HDMA runs at, at the start of horizontal blanking.
+const HDMA_LINE_CLOCK: usize = 1104;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Something which happens at a fixed point in each frame.
+///     VBlankEnd:      Vertical blanking ends, at the start of the frame.
+///     HdmaInit:       HDMA channels reload their tables, at the start of the frame.
+///     HdmaLine:       HDMA channels transfer, during horizontal blanking of each visible line, up to line 224, or 239
+///                     with overscan.
+///     VBlankStart:    Vertical blanking starts, after the last visible line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineEvent {
+    VBlankEnd,
+    HdmaInit,
+    HdmaLine,
+    VBlankStart,
+}
+
+/// Position of the master clock within the frame, by scanline.
+///     line:           Scanline, from 0 at the start of the frame.
+///     line_clock:     Master clocks into the scanline.
+///     frames:         Frames completed.
+///     vblank_line:    First line of vertical blanking, which HDMA stops before.
+#[derive(Debug)]
+pub struct Timeline {
+    line: usize,
+    line_clock: usize,
+    frames: usize,
+    vblank_line: usize,
+}
+
+impl Timeline {
+    /// Return a timeline at the start of the first frame.
+    pub fn new() -> Self {
+        Self {
+            line: 0,
+            line_clock: 0,
+            frames: 0,
+            vblank_line: ppu::VBLANK_LINE,
+        }
+    }
+
+    /// Set the first line of vertical blanking, which moves with the PPU's overscan setting.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `line`:   First line of vertical blanking, 225 or 240.
+    pub fn set_vblank_line(&mut self, line: usize) { self.vblank_line = line; }
+
+    /// Find the master clocks until the next event, or until the end of the line if there are none left in it.
+    /// # Returns:
+    ///     - Master clocks to run before stopping again, at least 1.
+    pub fn clocks_to_next_event(&self) -> usize {
+        let next_stop = events(self.line, self.vblank_line)
+            .iter()
+            .map(|(clock, _)| *clock)
+            .find(|clock| *clock > self.line_clock)
+            .unwrap_or(CLOCKS_PER_LINE);
+        next_stop - self.line_clock
+    }
+
+    /// Move the timeline forward.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `clocks`: Master clocks to move forward, no more than `clocks_to_next_event`.
+    /// # Returns:
+    ///     - `Some(event)`:    The event reached, which should happen before the timeline moves on again.
+    ///     - `None`:           If no event was reached.
+    pub fn advance(&mut self, clocks: usize) -> Option<TimelineEvent> {
+        debug_assert!(clocks <= self.clocks_to_next_event());
+        self.line_clock += clocks;
+        if self.line_clock == CLOCKS_PER_LINE {
+            self.line_clock = 0;
+            self.line += 1;
+            if self.line == LINES_PER_FRAME {
+                self.line = 0;
+                self.frames += 1;
+            }
+        }
+
+        events(self.line, self.vblank_line)
+            .iter()
+            .find(|(clock, _)| *clock == self.line_clock)
+            .map(|(_, event)| *event)
+    }
+
+    /// Print the position of the timeline.
+    pub fn print_state(&self) {
+        println!(
+            "Frame: {}, Scanline: {}, Clock in line: {}",
+            self.frames, self.line, self.line_clock
+        );
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Find the events in a scanline.
+/// # Parameters:
+///     - `line`:           Scanline to look in.
+///     - `vblank_line`:    First line of vertical blanking.
+/// # Returns:
+///     - The master clock within the line of each event, in order.
+fn events(line: usize, vblank_line: usize) -> &'static [(usize, TimelineEvent)] {
+    match line {
+        0 => &[
+            (0, TimelineEvent::VBlankEnd),
+            (HDMA_INIT_CLOCK, TimelineEvent::HdmaInit),
+            (HDMA_LINE_CLOCK, TimelineEvent::HdmaLine),
+        ],
+        _ if line < vblank_line => &[(HDMA_LINE_CLOCK, TimelineEvent::HdmaLine)],
+        _ if line == vblank_line => &[(0, TimelineEvent::VBlankStart)],
+        _ => &[],
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the timeline until the next event, returning the event and the master clocks run.
+    fn run_to_event(timeline: &mut Timeline) -> (TimelineEvent, usize) {
+        let mut clocks = 0;
+        loop {
+            let step = timeline.clocks_to_next_event();
+            clocks += step;
+            if let Some(event) = timeline.advance(step) {
+                return (event, clocks);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frame_events() {
+        let test_cases = vec![
+            // first line of vertical blanking, last line HDMA runs on
+            (225, 224),
+            (240, 239),
+        ];
+
+        for (vblank_line, last_hdma_line) in test_cases {
+            println!("Test Case: VBlank on line {}", vblank_line);
+            let mut timeline = Timeline::new();
+            timeline.set_vblank_line(vblank_line);
+            assert_eq!(run_to_event(&mut timeline), (TimelineEvent::HdmaInit, HDMA_INIT_CLOCK));
+            assert_eq!(
+                run_to_event(&mut timeline),
+                (TimelineEvent::HdmaLine, HDMA_LINE_CLOCK - HDMA_INIT_CLOCK)
+            );
+
+            // HDMA runs once each line up to the last visible one.
+            for line in 1..=last_hdma_line {
+                assert_eq!(
+                    run_to_event(&mut timeline),
+                    (TimelineEvent::HdmaLine, CLOCKS_PER_LINE)
+                );
+                assert_eq!(timeline.line, line);
+            }
+
+            // Vertical blanking starts on the next line, and lasts until the next frame starts.
+            assert_eq!(
+                run_to_event(&mut timeline),
+                (TimelineEvent::VBlankStart, CLOCKS_PER_LINE - HDMA_LINE_CLOCK)
+            );
+            assert_eq!(timeline.line, vblank_line);
+            assert_eq!(
+                run_to_event(&mut timeline),
+                (TimelineEvent::VBlankEnd, (LINES_PER_FRAME - vblank_line) * CLOCKS_PER_LINE)
+            );
+            assert_eq!(timeline.frames, 1);
+            assert_eq!(run_to_event(&mut timeline), (TimelineEvent::HdmaInit, HDMA_INIT_CLOCK));
+        }
+    }
+
+    #[test]
+    fn test_advance_part_way() {
+        let mut timeline = Timeline::new();
+        assert_eq!(timeline.advance(10), None);
+        assert_eq!(timeline.clocks_to_next_event(), HDMA_INIT_CLOCK - 10);
+        assert_eq!(timeline.advance(HDMA_INIT_CLOCK - 10), Some(TimelineEvent::HdmaInit));
+
+        // Once an event has been reached, the timeline moves on to the next.
+        assert_eq!(
+            timeline.clocks_to_next_event(),
+            HDMA_LINE_CLOCK - HDMA_INIT_CLOCK
+        );
+    }
+}
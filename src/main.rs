@@ -5,6 +5,7 @@ mod cpu;
 mod debugger;
 mod emu;
 mod memory;
+mod ppu;
 mod romdata;
 
 /// Main function, initializes and runs core.
@@ -1,19 +1,29 @@
-use crate::romdata;
+use crate::romdata::RomSize;
+use bus::{Bus, BusTarget};
 use core::fmt;
+use std::{cell::RefCell, ops::RangeInclusive, rc::Rc};
+
+mod bus;
+pub mod dma;
+pub mod io;
+mod wram;
 
 /**************************************** Constant Values ***************************************************************/
 pub const MEMORY_SIZE: usize = (0xFFFFFF) + 1;
-pub const MEMORY_START: usize = 0x000000;
+pub const _MEMORY_START: usize = 0x000000;
 pub const MEMORY_END: usize = 0xFFFFFF;
 pub const _MEMORY_BANK_COUNT: usize = 0xFF; // Number of addressable memory banks.
 pub const _MEMORY_BANK_START: usize = 0x0000;
 pub const _MEMORY_BANK_SIZE: usize = 0xFFFF; // Size of one memory bank.
 pub const MEMORY_BANK_INDEX: u8 = 16; // Bit index to shift a u8 by to obtain a bank address.
 
-/**************************************** Struct and Type definitions ***************************************************/
-/// Wrapper type for a u8 array which represents memory.
-type MemoryData = Box<[u8; MEMORY_SIZE]>;
+/// Size of the blank ROM that memory starts with before a ROM is loaded, which is as large as a LoROM can be.
+const BLANK_ROM_SIZE: usize = 4 * 1024 * 1024;
 
+/// MEMSEL register, which is write only, but is looked at by the CPU to time accesses to ROM.
+const MEMSEL_ADDR: u16 = 0x420D;
+
+/**************************************** Struct and Type definitions ***************************************************/
 /// Struct for an Invalid Address or an index out of bounds.
 #[derive(Debug, Clone)]
 pub struct InvalidAddressError {
@@ -30,19 +40,120 @@ impl fmt::Display for InvalidAddressError {
     }
 }
 
-/// Structure to represent memory.
-/// Really just a wrapper for an array.
+/// Kind of access made to the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusAccess {
+    Read,
+    Write,
+}
+
+/// Fault raised in strict mode when the CPU accesses an address nothing is connected to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmappedAccessError {
+    address: usize,
+    access: BusAccess,
+}
+
+impl fmt::Display for UnmappedAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let access = match self.access {
+            BusAccess::Read => "read from",
+            BusAccess::Write => "write to",
+        };
+        write!(f, "unmapped {} ${:06X}", access, self.address)
+    }
+}
+
+/// Structure to represent memory, as the CPU sees it.
+/// Each address is decoded by the bus to whatever it is connected to, so mirrors share the same bytes.
+///     bus:            Address decoder for the loaded cartridge.
+///     wram:           128KiB of work RAM, shared with the WRAM port registers it is attached to.
+///     rom:            ROM image of the loaded cartridge.
+///     sram:           Cartridge SRAM, empty if the cartridge has none.
+///     io:             Registers attached to the I/O area.
+///     open_bus:       Last value on the data bus, which reads from unmapped addresses see.
+///     strict:         Whether accesses to unmapped addresses raise a fault.
+///     fault:          Fault raised by the last access to an unmapped address in strict mode, until it is taken.
 pub struct Memory {
-    memory: MemoryData,
+    bus: Bus,
+    wram: Rc<RefCell<wram::Wram>>,
+    rom: Vec<u8>,
+    sram: Vec<u8>,
+    io: io::IoMap,
+    open_bus: u8,
+    strict: bool,
+    fault: Option<UnmappedAccessError>,
 }
 
 impl Memory {
-    /// Creates a new instance of a Memory object, with all addresses initialized to 0.
+    /// Creates a new instance of a Memory object, with a blank LoROM loaded and all addresses initialized to 0.
     pub fn new() -> Self {
-        Memory {
-            // https://github.com/rust-lang/rust/issues/53827
-            memory: vec![0; MEMORY_SIZE].into_boxed_slice().try_into().unwrap(),
-        }
+        let mut memory = Memory {
+            bus: Bus::new(RomSize::LoRom, BLANK_ROM_SIZE, 0),
+            wram: Rc::new(RefCell::new(wram::Wram::new())),
+            rom: vec![0; BLANK_ROM_SIZE],
+            sram: vec![],
+            io: io::IoMap::new(),
+            open_bus: 0,
+            strict: false,
+            fault: None,
+        };
+
+        let memsel = io::LatchRegisters::new(MEMSEL_ADDR..=MEMSEL_ADDR);
+        memory
+            .register_io(MEMSEL_ADDR..=MEMSEL_ADDR, Rc::new(RefCell::new(memsel)))
+            .expect("MEMSEL is in the I/O area");
+        memory
+            .register_io(wram::WMDATA_ADDR..=wram::WMADDH_ADDR, memory.wram.clone())
+            .expect("The WRAM port is in the I/O area");
+        memory
+    }
+
+    /// Attach registers to a range of addresses in the I/O area, so that they handle every access to it.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `range`:      Addresses to attach to, from $2000-$5FFF. These are mirrored into every system bank.
+    ///     - `handler`:    Registers to attach.
+    /// # Returns:
+    ///     - `Ok(())`:     If the registers were attached.
+    ///     - `Err(error)`: If the range is outside the I/O area, or overlaps registers already attached.
+    pub fn register_io(
+        &mut self, range: RangeInclusive<u16>, handler: io::IoHandler,
+    ) -> Result<(), io::IoRangeError> {
+        self.io.register(range, handler)
+    }
+
+    /// Set whether accesses to unmapped addresses raise a fault, to catch wild pointers.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `strict`: True to raise faults.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+        self.fault = None;
+    }
+
+    /// Check whether accesses to unmapped addresses raise a fault.
+    pub fn is_strict(&self) -> bool { self.strict }
+
+    /// Take the fault raised by an access to an unmapped address, if there was one.
+    /// # Returns:
+    ///     - `Some(fault)`:    The first fault raised since the last call.
+    ///     - `None`:           If there was no fault, or strict mode is off.
+    pub fn take_fault(&mut self) -> Option<UnmappedAccessError> { self.fault.take() }
+
+    /// Get the last value on the data bus, which is seen by accesses nothing responds to.
+    pub fn open_bus(&self) -> u8 { self.open_bus }
+
+    /// Connect a cartridge to the bus.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `rom`:        ROM image to map. Must not be empty.
+    ///     - `mapping`:    Memory map of the cartridge.
+    ///     - `sram_size`:  Size of the cartridge SRAM in bytes, or 0 if there is none.
+    pub fn load_rom(&mut self, rom: Vec<u8>, mapping: RomSize, sram_size: usize) {
+        self.bus = Bus::new(mapping, rom.len(), sram_size);
+        self.rom = rom;
+        self.sram = vec![0; sram_size];
     }
 
     /// Print out a few rows of memory.
@@ -61,13 +172,13 @@ impl Memory {
         for i in 0..8 {
             print!("{:02x}|", i);
             for j in 0..16 {
-                print!("{:02x} ", self.memory[start_addr + (16 * i) + j]);
+                print!("{:02x} ", self.peek(start_addr + (16 * i) + j));
             }
             println!();
         }
     }
 
-    /// Return one byte from memory.
+    /// Return one byte from memory, without it being seen as an access by the system.
     /// # Parameters:
     ///     - `self`: Pointer to memory object which contains memory to read from.
     ///     - `address`: Address of byte to fetch, as a fully assembled absolute address.
@@ -76,12 +187,14 @@ impl Memory {
     ///     - `InvalidAddressError(e)`      If an invalid address was passed.
     pub fn get_byte(&self, address: usize) -> Result<u8, InvalidAddressError> {
         match address_is_valid(address) {
-            Ok(_t) => Ok(self.memory[address]),
+            Ok(_t) => Ok(self.peek(address)),
             Err(e) => Err(e),
         }
     }
 
-    /// Put a byte into memory.
+    /// Put a byte into memory for the debugger, without it being timed or checked in strict mode as a CPU write is.
+    /// Unlike a write from the CPU, this can change ROM. Writes to I/O registers are passed on to them, so they have
+    /// the same side effects as a CPU write, such as moving on the PPU's VRAM address.
     /// # Parameters:
     ///     - `self`:       Pointer to mutable memory object to write byte into.
     ///     - `address`:    Location in memory to write to, as a fully assembled absolute address.
@@ -92,33 +205,56 @@ impl Memory {
     pub fn put_byte(&mut self, address: usize, byte: u8) -> Result<(), InvalidAddressError> {
         match address_is_valid(address) {
             Ok(_t) => {
-                self.memory[address] = byte;
+                self.poke(self.bus.decode(address), byte);
                 Ok(())
             }
             Err(e) => Err(e),
         }
     }
 
-    /// Write an entire bank into memory.
+    /// Read a byte from the bus, as the CPU does. Reads from I/O registers may have side effects.
+    /// Reads which nothing responds to see the last value on the data bus.
     /// # Parameters:
     ///     - `self`
-    ///     - `banktype`:       The size of a rom, used to determine the bank length.
-    ///     - `address`:        Start address to write this bank from, as an absolute address.
-    ///     - `bankdata`:       Data to write to target bank.
-    /// # Returns
-    ///     - `Ok(())`:                     If written OK.
-    ///     - `InvalidAddressError(e)`:     If an invalid address was passed.
-    pub fn put_bank(
-        &mut self, banktype: romdata::BankSize, address: usize, bankdata: &[u8],
-    ) -> Result<(), InvalidAddressError> {
-        match address_is_valid(address + banktype as usize - 1) {
-            Ok(_t) => {
-                for offset in 0..banktype as usize - 1 {
-                    self.memory[address + offset] = bankdata[offset];
+    ///     - `address`:    Address to read, which wraps within the 24-bit address space.
+    /// # Returns:
+    ///     - The byte read.
+    pub fn read(&mut self, address: usize) -> u8 {
+        let address = address & MEMORY_END;
+        let value = match self.bus.decode(address) {
+            BusTarget::Io(register) => match self.io.get(register) {
+                Some(handler) => handler.borrow_mut().read(register),
+                None => {
+                    self.unmapped(address, BusAccess::Read);
+                    None
                 }
-                Ok(())
+            },
+            BusTarget::OpenBus => {
+                self.unmapped(address, BusAccess::Read);
+                None
             }
-            Err(e) => Err(e),
+            _ => Some(self.peek(address)),
+        };
+
+        self.open_bus = value.unwrap_or(self.open_bus);
+        self.open_bus
+    }
+
+    /// Write a byte to the bus, as the CPU does. Writes to ROM are ignored.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address to write, which wraps within the 24-bit address space.
+    ///     - `byte`:       Byte to write.
+    pub fn write(&mut self, address: usize, byte: u8) {
+        let address = address & MEMORY_END;
+        self.open_bus = byte;
+        match self.bus.decode(address) {
+            BusTarget::Rom(_) => {}
+            BusTarget::Io(register) if self.io.get(register).is_none() => {
+                self.unmapped(address, BusAccess::Write)
+            }
+            BusTarget::OpenBus => self.unmapped(address, BusAccess::Write),
+            target => self.poke(target, byte),
         }
     }
 
@@ -130,12 +266,12 @@ impl Memory {
     /// # Returns:
     ///     - `Ok(())`:                     If OK.
     ///     - `InvalidAddressError`:        If an invalid address was passed.
+    #[cfg(test)]
     pub fn _put_word(&mut self, address: usize, word: u16) -> Result<(), InvalidAddressError> {
         match address_is_valid(address + 1) {
             Ok(_t) => {
-                self.memory[address] = word.to_le_bytes()[0];
-                self.memory[address + 1] = word.to_le_bytes()[1];
-                Ok(())
+                self.put_byte(address, word.to_le_bytes()[0])?;
+                self.put_byte(address + 1, word.to_le_bytes()[1])
             }
             Err(e) => Err(e),
         }
@@ -148,16 +284,68 @@ impl Memory {
     /// # Returns:
     ///     - `Ok(value)`   If OK, the word as a wrapped u16.
     ///     - `Err(e)`      If an invalid address was passed.
+    #[cfg(test)]
     pub fn get_word(&self, address: usize) -> Result<u16, InvalidAddressError> {
         match address_is_valid(address + 1) {
             Ok(_t) => {
                 let word_val: u16 =
-                    u16::from_le_bytes([self.memory[address], self.memory[address + 1]]);
+                    u16::from_le_bytes([self.peek(address), self.peek(address + 1)]);
                 Ok(word_val)
             }
             Err(e) => Err(e),
         }
     }
+
+    /// Look up the byte an address is connected to, without any side effects.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Valid 24-bit address to read.
+    /// # Returns:
+    ///     - The byte at the address, or the last value on the data bus if nothing responds to it.
+    fn peek(&self, address: usize) -> u8 {
+        match self.bus.decode(address) {
+            BusTarget::Wram(offset) => self.wram.borrow().get(offset),
+            BusTarget::Rom(offset) => self.rom[offset],
+            BusTarget::Sram(offset) => self.sram[offset],
+            BusTarget::Io(register) => self
+                .io
+                .get(register)
+                .and_then(|handler| handler.borrow().peek(register))
+                .unwrap_or(self.open_bus),
+            BusTarget::OpenBus => self.open_bus,
+        }
+    }
+
+    /// Store a byte to whatever an address is connected to.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `target`: Decoded target to store to.
+    ///     - `byte`:   Byte to store.
+    fn poke(&mut self, target: BusTarget, byte: u8) {
+        match target {
+            BusTarget::Wram(offset) => self.wram.borrow_mut().set(offset, byte),
+            BusTarget::Rom(offset) => self.rom[offset] = byte,
+            BusTarget::Sram(offset) => self.sram[offset] = byte,
+            BusTarget::Io(register) => {
+                if let Some(handler) = self.io.get(register) {
+                    handler.borrow_mut().write(register, byte);
+                }
+            }
+            BusTarget::OpenBus => {}
+        }
+    }
+
+    /// Record an access to an unmapped address, raising a fault if strict mode is on.
+    /// Only the first fault is kept until it is taken, since that is where things went wrong.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address accessed.
+    ///     - `access`:     Kind of access made.
+    fn unmapped(&mut self, address: usize, access: BusAccess) {
+        if self.strict && self.fault.is_none() {
+            self.fault = Some(UnmappedAccessError { address, access });
+        }
+    }
 }
 
 /**************************************** File Scope Functions **********************************************************/
@@ -183,7 +371,6 @@ pub fn address_is_valid(address: usize) -> Result<(), InvalidAddressError> {
         Ok(())
     }
     else {
-        println!("Error");
         Err(InvalidAddressError::new(address))
     }
 }
@@ -194,42 +381,61 @@ mod tests {
     use super::*;
     use rand::{Rng, RngCore};
 
-    const MEMORY_END: usize = 0xFFFFFF;
+    /// Work RAM, which is only reachable through one address in banks $7E-$7F.
+    const WRAM_START: usize = 0x7E0000;
 
     /**************************************** Test Helpers **************************************************************/
-    /// Given a memory ptr fill it with random test data, and return the random test data it was filled from.
+    /// Given a memory ptr fill WRAM with random test data, and return the random test data it was filled from.
     /// # Parameters:
     ///     - `memory_under_test`:      Memory to fill.
     /// # Returns:
     ///     - `random_data`:            Randomly generated list of u8s.
-    fn fill_random(memory_under_test: &mut Memory) -> MemoryData {
-        let mut random_data: MemoryData =
-            vec![0; MEMORY_SIZE].into_boxed_slice().try_into().unwrap();
+    fn fill_random(memory_under_test: &mut Memory) -> wram::WramData {
+        let mut random_data: wram::WramData =
+            vec![0; bus::WRAM_SIZE].into_boxed_slice().try_into().unwrap();
         rand::thread_rng().fill_bytes(&mut *random_data);
 
-        for addr in 0..MEMORY_SIZE {
-            memory_under_test.memory[addr] = random_data[addr];
+        for offset in 0..bus::WRAM_SIZE {
+            memory_under_test.wram.borrow_mut().set(offset, random_data[offset]);
         }
 
         random_data
     }
 
+    /// Register which counts how many times it has been read.
+    struct CountingRegister {
+        reads: u8,
+    }
+
+    impl io::IoRegisters for CountingRegister {
+        fn read(&mut self, _address: u16) -> Option<u8> {
+            self.reads += 1;
+            Some(self.reads)
+        }
+
+        fn peek(&self, _address: u16) -> Option<u8> { Some(self.reads) }
+
+        fn write(&mut self, _address: u16, value: u8) { self.reads = value; }
+    }
+
     /**************************************** Unit Test Implementations *************************************************/
     /***** Byte Tests *****/
 
     #[test]
     fn test_put_byte() {
         let mut memory_under_test = Memory::new();
-        let mut random_data: Box<[u8; MEMORY_SIZE]> =
-            vec![0; MEMORY_SIZE].into_boxed_slice().try_into().unwrap();
+        let mut random_data: wram::WramData =
+            vec![0; bus::WRAM_SIZE].into_boxed_slice().try_into().unwrap();
         rand::thread_rng().fill_bytes(&mut *random_data);
 
-        for addr in 0..MEMORY_END {
-            memory_under_test.put_byte(addr, random_data[addr]).unwrap();
+        for offset in 0..bus::WRAM_SIZE {
+            memory_under_test
+                .put_byte(WRAM_START + offset, random_data[offset])
+                .unwrap();
         }
 
-        for addr in 0..MEMORY_END {
-            assert_eq!(random_data[addr], memory_under_test.memory[addr]);
+        for offset in 0..bus::WRAM_SIZE {
+            assert_eq!(random_data[offset], memory_under_test.wram.borrow().get(offset));
         }
     }
 
@@ -243,10 +449,13 @@ mod tests {
     #[test]
     fn test_get_byte() {
         let mut memory_under_test = Memory::new();
-        let random_data: MemoryData = fill_random(&mut memory_under_test);
+        let random_data: wram::WramData = fill_random(&mut memory_under_test);
 
-        for addr in 0..MEMORY_END {
-            assert_eq!(random_data[addr], memory_under_test.get_byte(addr).unwrap());
+        for offset in 0..bus::WRAM_SIZE {
+            assert_eq!(
+                random_data[offset],
+                memory_under_test.get_byte(WRAM_START + offset).unwrap()
+            );
         }
     }
 
@@ -257,35 +466,190 @@ mod tests {
         let _ = &memory_under_test.get_byte(MEMORY_SIZE).unwrap();
     }
 
+    #[test]
+    fn test_mirrors_share_bytes() {
+        let mut memory_under_test = Memory::new();
+
+        // Low RAM in the system banks is the first 8KiB of WRAM.
+        memory_under_test.put_byte(0x7E0010, 0xAA).unwrap();
+        assert_eq!(memory_under_test.get_byte(0x000010).unwrap(), 0xAA);
+        assert_eq!(memory_under_test.get_byte(0x800010).unwrap(), 0xAA);
+
+        // LoROM is mirrored into banks $00-$7D from banks $80-$FF.
+        memory_under_test.put_byte(0x808000, 0xBB).unwrap();
+        assert_eq!(memory_under_test.get_byte(0x008000).unwrap(), 0xBB);
+    }
+
+    #[test]
+    fn test_write_rom() {
+        let mut memory_under_test = Memory::new();
+        memory_under_test.put_byte(0x808000, 0xAA).unwrap();
+
+        // The CPU can't write to ROM.
+        memory_under_test.write(0x808000, 0xBB);
+        assert_eq!(memory_under_test.read(0x808000), 0xAA);
+
+        // But it can write to RAM.
+        memory_under_test.write(0x7E8000, 0xBB);
+        assert_eq!(memory_under_test.read(0x7E8000), 0xBB);
+    }
+
+    #[test]
+    fn test_load_rom() {
+        let mut memory_under_test = Memory::new();
+        let mut rom = vec![0; 0x20000];
+        rom[0x00FFC0] = 0xAA;
+        rom[0x010000] = 0xBB;
+
+        memory_under_test.load_rom(rom, RomSize::HiRom, 0x2000);
+        assert_eq!(memory_under_test.get_byte(0x00FFC0).unwrap(), 0xAA);
+        assert_eq!(memory_under_test.get_byte(0xC10000).unwrap(), 0xBB);
+
+        // The cartridge SRAM is mapped in.
+        memory_under_test.write(0x206000, 0xCC);
+        assert_eq!(memory_under_test.read(0xA06000), 0xCC);
+        assert_eq!(memory_under_test.sram[0], 0xCC);
+    }
+
+    #[test]
+    fn test_open_bus() {
+        let mut memory_under_test = Memory::new();
+
+        // Nothing is connected, so writes are lost, and reads see the last value on the data bus.
+        memory_under_test.write(0x002000, 0xAA);
+        assert_eq!(memory_under_test.read(0x002000), 0xAA);
+
+        memory_under_test.write(0x7E0000, 0xBB);
+        memory_under_test.read(0x7E0001);
+        assert_eq!(memory_under_test.read(0x002000), 0x00);
+        assert_eq!(memory_under_test.get_byte(0x002000).unwrap(), 0x00);
+
+        // Write only registers don't drive the bus either.
+        memory_under_test.write(0x002181, 0x01);
+        memory_under_test.write(0x7E0010, 0xCC);
+        assert_eq!(memory_under_test.read(0x002181), 0xCC);
+
+        // Without strict mode, nothing is raised.
+        assert!(memory_under_test.take_fault().is_none());
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut memory_under_test = Memory::new();
+        memory_under_test.set_strict(true);
+
+        memory_under_test.write(0x7E0000, 0xAA);
+        memory_under_test.read(0x808000);
+        assert!(memory_under_test.take_fault().is_none());
+
+        // Only the first fault is kept.
+        memory_under_test.read(0x002000);
+        memory_under_test.write(0x006000, 0x00);
+        let fault = memory_under_test.take_fault().unwrap();
+        assert_eq!(fault.address, 0x002000);
+        assert_eq!(fault.access, BusAccess::Read);
+        assert!(memory_under_test.take_fault().is_none());
+
+        // I/O addresses with no registers attached are unmapped, but looking at them from the debugger isn't an access.
+        memory_under_test.write(0x804211, 0x00);
+        memory_under_test.get_byte(0x002000).unwrap();
+        let fault = memory_under_test.take_fault().unwrap();
+        assert_eq!(fault.address, 0x804211);
+        assert_eq!(fault.access, BusAccess::Write);
+    }
+
+    #[test]
+    fn test_io_registers() {
+        let mut memory_under_test = Memory::new();
+        let register = Rc::new(RefCell::new(CountingRegister { reads: 0 }));
+        memory_under_test
+            .register_io(0x4210..=0x4210, register.clone())
+            .unwrap();
+
+        // Reads from the CPU have side effects, and are seen through every system bank.
+        assert_eq!(memory_under_test.read(0x004210), 1);
+        assert_eq!(memory_under_test.read(0x804210), 2);
+
+        // Looking at the register from the debugger doesn't.
+        assert_eq!(memory_under_test.get_byte(0x004210).unwrap(), 2);
+        memory_under_test.print_bytes(Some(0x004200));
+        assert_eq!(register.borrow().reads, 2);
+
+        memory_under_test.write(0x3F4210, 0x10);
+        assert_eq!(register.borrow().reads, 0x10);
+
+        // Registers can't be attached over each other.
+        assert!(memory_under_test
+            .register_io(0x4200..=0x421F, register.clone())
+            .is_err());
+
+        // Nothing is attached to the neighbouring register, so the read sees open bus.
+        memory_under_test.write(0x004211, 0xAA);
+        assert_eq!(memory_under_test.read(0x004211), 0xAA);
+    }
+
+    #[test]
+    fn test_wram_port() {
+        let mut memory_under_test = Memory::new();
+
+        // Point the port at $7F:0000 and write through it. The address increments after each access.
+        memory_under_test.write(0x002181, 0x00);
+        memory_under_test.write(0x002182, 0x00);
+        memory_under_test.write(0x002183, 0x01);
+        memory_under_test.write(0x802180, 0xAA);
+        memory_under_test.write(0x002180, 0xBB);
+        assert_eq!(memory_under_test.read(0x7F0000), 0xAA);
+        assert_eq!(memory_under_test.read(0x7F0001), 0xBB);
+
+        // Reading back through low RAM goes to the same bytes.
+        memory_under_test.write(0x7E0005, 0xCC);
+        memory_under_test.write(0x002183, 0x00);
+        memory_under_test.write(0x002181, 0x05);
+        assert_eq!(memory_under_test.get_byte(0x002180).unwrap(), 0xCC);
+        assert_eq!(memory_under_test.read(0x002180), 0xCC);
+        assert_eq!(memory_under_test.get_byte(0x002181).unwrap(), 0x06);
+    }
+
+    #[test]
+    fn test_memsel_write_only() {
+        let mut memory_under_test = Memory::new();
+        memory_under_test.write(0x00420D, 0x01);
+
+        // Reads see open bus, but the debugger can still see the value written.
+        memory_under_test.write(0x7E0000, 0xAA);
+        assert_eq!(memory_under_test.read(0x80420D), 0xAA);
+        assert_eq!(memory_under_test.get_byte(0x00420D).unwrap(), 0x01);
+    }
+
     /***** Word Tests *****/
     #[test]
     fn test_put_word() {
         let mut memory_under_test: Memory = Memory::new();
-        let mut random_data: Box<[u16; MEMORY_SIZE / 2]> = vec![0; MEMORY_SIZE / 2]
+        let mut random_data: Box<[u16; bus::WRAM_SIZE / 2]> = vec![0; bus::WRAM_SIZE / 2]
             .into_boxed_slice()
             .try_into()
             .unwrap();
 
-        memory_under_test._put_word(0x000000, 0xAABB).unwrap();
-        assert_eq!(memory_under_test.memory[0], 0xBB);
-        assert_eq!(memory_under_test.memory[1], 0xAA);
+        memory_under_test._put_word(WRAM_START, 0xAABB).unwrap();
+        assert_eq!(memory_under_test.wram.borrow().get(0), 0xBB);
+        assert_eq!(memory_under_test.wram.borrow().get(1), 0xAA);
 
         let mut rand_word: u16;
-        for addr in 0..MEMORY_SIZE {
-            if addr % 2 == 0 {
+        for offset in 0..bus::WRAM_SIZE {
+            if offset % 2 == 0 {
                 rand_word = rand::thread_rng().gen();
-                random_data[addr / 2] = rand_word;
-                memory_under_test._put_word(addr, rand_word).unwrap();
+                random_data[offset / 2] = rand_word;
+                memory_under_test
+                    ._put_word(WRAM_START + offset, rand_word)
+                    .unwrap();
             }
         }
 
-        for addr in 0..MEMORY_SIZE {
-            if addr % 2 == 0 {
-                let test_word: u16 = u16::from_le_bytes([
-                    memory_under_test.memory[addr],
-                    memory_under_test.memory[addr + 1],
-                ]);
-                assert_eq!(random_data[addr / 2], test_word);
+        for offset in 0..bus::WRAM_SIZE {
+            if offset % 2 == 0 {
+                let wram = memory_under_test.wram.borrow();
+                let test_word: u16 = u16::from_le_bytes([wram.get(offset), wram.get(offset + 1)]);
+                assert_eq!(random_data[offset / 2], test_word);
             }
         }
     }
@@ -299,33 +663,34 @@ mod tests {
 
     #[test]
     fn test_get_word() {
-        let mut memory_under_test: Memory = Memory::new();
-        let mut random_data: Box<[u16; MEMORY_SIZE / 2]> = vec![0; MEMORY_SIZE / 2]
+        let memory_under_test: Memory = Memory::new();
+        let mut random_data: Box<[u16; bus::WRAM_SIZE / 2]> = vec![0; bus::WRAM_SIZE / 2]
             .into_boxed_slice()
             .try_into()
             .unwrap();
 
         // Quick sanity check to make sure our get_word() gives back a BE value.
-        memory_under_test.memory[0] = 0xBB;
-        memory_under_test.memory[1] = 0xAA;
-        assert_eq!(memory_under_test.get_word(0x000000).unwrap(), 0xAABB);
+        memory_under_test.wram.borrow_mut().set(0, 0xBB);
+        memory_under_test.wram.borrow_mut().set(1, 0xAA);
+        assert_eq!(memory_under_test.get_word(WRAM_START).unwrap(), 0xAABB);
 
         let mut rand_word: u16;
-        for addr in 0..MEMORY_SIZE {
-            if addr % 2 == 0 {
+        for offset in 0..bus::WRAM_SIZE {
+            if offset % 2 == 0 {
                 rand_word = rand::thread_rng().gen();
-                random_data[addr / 2] = rand_word;
-                memory_under_test._put_word(addr, rand_word).unwrap();
+                random_data[offset / 2] = rand_word;
+                let [low, high] = rand_word.to_le_bytes();
+                memory_under_test.wram.borrow_mut().set(offset, low);
+                memory_under_test.wram.borrow_mut().set(offset + 1, high);
             }
         }
 
-        for addr in 0..MEMORY_SIZE {
-            if addr % 2 == 0 {
-                let test_word: u16 = u16::from_le_bytes([
-                    memory_under_test.memory[addr],
-                    memory_under_test.memory[addr + 1],
-                ]);
-                assert_eq!(random_data[addr / 2], test_word);
+        for offset in 0..bus::WRAM_SIZE {
+            if offset % 2 == 0 {
+                assert_eq!(
+                    random_data[offset / 2],
+                    memory_under_test.get_word(WRAM_START + offset).unwrap()
+                );
             }
         }
     }
@@ -0,0 +1,277 @@
+use crate::romdata::RomSize;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Size of work RAM, which sits in banks $7E-$7F.
+pub const WRAM_SIZE: usize = 128 * 1024;
+
+/// Size of the system area in banks $00-$3F and $80-$BF that WRAM is mirrored into, at $0000-$1FFF.
+const LOW_RAM_SIZE: u16 = 8 * 1024;
+
+/// Start of the I/O area in banks $00-$3F and $80-$BF.
+pub const IO_START: u16 = 0x2000;
+
+/// Size of the I/O area, $2000-$5FFF. Only some of it is decoded to registers.
+pub const IO_SIZE: usize = 0x4000;
+
+/// Start of ROM in banks $00-$3F and $80-$BF.
+const ROM_START: u16 = 0x8000;
+
+/// Size of a LoROM bank, and of a LoROM SRAM bank.
+const LO_ROM_BANK_SIZE: usize = 0x8000;
+
+/// Size of a HiROM SRAM bank, at $6000-$7FFF.
+const HI_ROM_SRAM_BANK_SIZE: usize = 0x2000;
+
+/// Offset into an ExHiROM of the banks mapped to $40-$7D and $00-$3F.
+const EX_HI_ROM_UPPER_OFFSET: usize = 4 * 1024 * 1024;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// What an address on the bus is connected to.
+///     Wram(offset):   Work RAM, at an offset into the 128KiB of WRAM.
+///     Rom(offset):    Cartridge ROM, at an offset into the ROM image.
+///     Sram(offset):   Cartridge SRAM, at an offset into the SRAM.
+///     Io(address):    An I/O register, at its 16-bit address within the system banks.
+///     OpenBus:        Nothing, so nothing responds to the access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusTarget {
+    Wram(usize),
+    Rom(usize),
+    Sram(usize),
+    Io(u16),
+    OpenBus,
+}
+
+/// Address decoder for the CPU's 24-bit A-bus.
+/// https://snes.nesdev.org/wiki/Memory_map
+///     mapping:    Memory map of the cartridge.
+///     rom_size:   Size of the ROM image, which is mirrored to fill the space it is mapped into.
+///     sram_size:  Size of the cartridge SRAM, which is mirrored the same way. 0 if there is none.
+#[derive(Debug, Clone, Copy)]
+pub struct Bus {
+    mapping: RomSize,
+    rom_size: usize,
+    sram_size: usize,
+}
+
+impl Bus {
+    /// Return a decoder for a cartridge.
+    /// # Parameters:
+    ///     - `mapping`:    Memory map of the cartridge.
+    ///     - `rom_size`:   Size of the ROM image. Must not be 0.
+    ///     - `sram_size`:  Size of the cartridge SRAM, or 0 if there is none.
+    pub fn new(mapping: RomSize, rom_size: usize, sram_size: usize) -> Self {
+        Self {
+            mapping,
+            rom_size,
+            sram_size,
+        }
+    }
+
+    /// Find what an address is connected to.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Composed 24-bit address.
+    /// # Returns:
+    ///     - The target of the access.
+    pub fn decode(&self, address: usize) -> BusTarget {
+        let bank = (address >> 16) as u8;
+        let offset = address as u16;
+
+        match bank {
+            0x7E..=0x7F => BusTarget::Wram(address & (WRAM_SIZE - 1)),
+            0x00..=0x3F | 0x80..=0xBF => match offset {
+                0x0000..=0x1FFF => BusTarget::Wram((offset % LOW_RAM_SIZE) as usize),
+                0x2100..=0x21FF | 0x4000..=0x43FF => BusTarget::Io(offset),
+                0x2000..=0x5FFF => BusTarget::OpenBus,
+                0x6000..=0x7FFF => self.expansion_target(bank, offset),
+                ROM_START..=0xFFFF => self.rom_target(bank, offset),
+            },
+            0x40..=0x7D | 0xC0..=0xFF => match self.mapping {
+                RomSize::LoRom if (bank & 0x7F) >= 0x70 && offset < ROM_START => {
+                    self.sram_target((bank & 0x0F) as usize * LO_ROM_BANK_SIZE + offset as usize)
+                }
+                _ => self.rom_target(bank, offset),
+            },
+        }
+    }
+
+    /// Decode an address in ROM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `bank`:       Bank being accessed.
+    ///     - `offset`:     Address within the bank.
+    /// # Returns:
+    ///     - The ROM target, mirrored to fit within the ROM.
+    fn rom_target(&self, bank: u8, offset: u16) -> BusTarget {
+        let rom_offset = match self.mapping {
+            RomSize::LoRom => {
+                (bank & 0x7F) as usize * LO_ROM_BANK_SIZE + (offset & 0x7FFF) as usize
+            }
+            RomSize::HiRom => ((bank & 0x3F) as usize) << 16 | offset as usize,
+            RomSize::ExHiRom => {
+                let upper = match bank & 0x80 {
+                    0 => EX_HI_ROM_UPPER_OFFSET,
+                    _ => 0,
+                };
+                upper + (((bank & 0x3F) as usize) << 16 | offset as usize)
+            }
+        };
+        BusTarget::Rom(rom_offset % self.rom_size)
+    }
+
+    /// Decode an address in the expansion area at $6000-$7FFF of the system banks.
+    /// HiROM cartridges put SRAM here in banks $20-$3F and $A0-$BF, and ExHiROM cartridges in banks $80-$BF.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `bank`:       Bank being accessed.
+    ///     - `offset`:     Address within the bank, from $6000-$7FFF.
+    /// # Returns:
+    ///     - The SRAM target, or open bus if there is no SRAM here.
+    fn expansion_target(&self, bank: u8, offset: u16) -> BusTarget {
+        let has_sram = match self.mapping {
+            RomSize::LoRom => false,
+            RomSize::HiRom => bank & 0x3F >= 0x20,
+            RomSize::ExHiRom => bank >= 0x80,
+        };
+
+        match has_sram {
+            true => self.sram_target(
+                (bank & 0x1F) as usize * HI_ROM_SRAM_BANK_SIZE + (offset & 0x1FFF) as usize,
+            ),
+            false => BusTarget::OpenBus,
+        }
+    }
+
+    /// Decode an address in SRAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `sram_offset`:    Offset into SRAM before mirroring.
+    /// # Returns:
+    ///     - The SRAM target, mirrored to fit within the SRAM, or open bus if there is no SRAM.
+    fn sram_target(&self, sram_offset: usize) -> BusTarget {
+        match self.sram_size {
+            0 => BusTarget::OpenBus,
+            size => BusTarget::Sram(sram_offset % size),
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROM_SIZE: usize = 8 * 1024 * 1024;
+    const SRAM_SIZE: usize = 32 * 1024;
+
+    #[test]
+    fn test_decode_system_area() {
+        let bus = Bus::new(RomSize::LoRom, ROM_SIZE, SRAM_SIZE);
+        let test_cases = vec![
+            (0x000000, BusTarget::Wram(0x0000)),
+            (0x001FFF, BusTarget::Wram(0x1FFF)),
+            (0x801234, BusTarget::Wram(0x1234)),
+            (0x3F0100, BusTarget::Wram(0x0100)),
+            (0x7E0000, BusTarget::Wram(0x00000)),
+            (0x7F1234, BusTarget::Wram(0x11234)),
+            (0x002000, BusTarget::OpenBus),
+            (0x002100, BusTarget::Io(0x2100)),
+            (0x8021FF, BusTarget::Io(0x21FF)),
+            (0x002200, BusTarget::OpenBus),
+            (0x004016, BusTarget::Io(0x4016)),
+            (0x00420D, BusTarget::Io(0x420D)),
+            (0x804370, BusTarget::Io(0x4370)),
+            (0x004400, BusTarget::OpenBus),
+            (0x006000, BusTarget::OpenBus),
+        ];
+
+        for (address, target) in test_cases {
+            println!("Test Case: {:#08X}", address);
+            assert_eq!(bus.decode(address), target);
+        }
+    }
+
+    #[test]
+    fn test_decode_lo_rom() {
+        let bus = Bus::new(RomSize::LoRom, ROM_SIZE, SRAM_SIZE);
+        let test_cases = vec![
+            (0x008000, BusTarget::Rom(0x000000)),
+            (0x808000, BusTarget::Rom(0x000000)),
+            (0x80FFFF, BusTarget::Rom(0x007FFF)),
+            (0x818000, BusTarget::Rom(0x008000)),
+            (0x408000, BusTarget::Rom(0x200000)),
+            (0x400000, BusTarget::Rom(0x200000)),
+            (0xC09000, BusTarget::Rom(0x201000)),
+            (0xFFFFFF, BusTarget::Rom(0x3FFFFF)),
+            (0x700000, BusTarget::Sram(0x0000)),
+            (0x710010, BusTarget::Sram(0x0010)),
+            (0xF07FFF, BusTarget::Sram(0x7FFF)),
+            (0x708000, BusTarget::Rom(0x380000)),
+        ];
+
+        for (address, target) in test_cases {
+            println!("Test Case: {:#08X}", address);
+            assert_eq!(bus.decode(address), target);
+        }
+    }
+
+    #[test]
+    fn test_decode_hi_rom() {
+        let bus = Bus::new(RomSize::HiRom, ROM_SIZE, SRAM_SIZE);
+        let test_cases = vec![
+            (0xC00000, BusTarget::Rom(0x000000)),
+            (0xC0FFFF, BusTarget::Rom(0x00FFFF)),
+            (0xFFFFFF, BusTarget::Rom(0x3FFFFF)),
+            (0x400000, BusTarget::Rom(0x000000)),
+            (0x008000, BusTarget::Rom(0x008000)),
+            (0x80FFC0, BusTarget::Rom(0x00FFC0)),
+            (0x3F8000, BusTarget::Rom(0x3F8000)),
+            (0x206000, BusTarget::Sram(0x0000)),
+            (0xA17FFF, BusTarget::Sram(0x3FFF)),
+            (0x1F6000, BusTarget::OpenBus),
+            (0x700000, BusTarget::Rom(0x300000)),
+        ];
+
+        for (address, target) in test_cases {
+            println!("Test Case: {:#08X}", address);
+            assert_eq!(bus.decode(address), target);
+        }
+    }
+
+    #[test]
+    fn test_decode_ex_hi_rom() {
+        let bus = Bus::new(RomSize::ExHiRom, ROM_SIZE, SRAM_SIZE);
+        let test_cases = vec![
+            (0xC00000, BusTarget::Rom(0x000000)),
+            (0x808000, BusTarget::Rom(0x008000)),
+            (0x400000, BusTarget::Rom(0x400000)),
+            (0x00FFC0, BusTarget::Rom(0x40FFC0)),
+            (0x3FFFFF, BusTarget::Rom(0x7FFFFF)),
+            (0x806000, BusTarget::Sram(0x0000)),
+            (0x006000, BusTarget::OpenBus),
+        ];
+
+        for (address, target) in test_cases {
+            println!("Test Case: {:#08X}", address);
+            assert_eq!(bus.decode(address), target);
+        }
+    }
+
+    #[test]
+    fn test_decode_mirrors() {
+        // A 1MiB LoROM is mirrored through the rest of the ROM area.
+        let bus = Bus::new(RomSize::LoRom, 1024 * 1024, 0);
+        assert_eq!(bus.decode(0xA08000), BusTarget::Rom(0x000000));
+        assert_eq!(bus.decode(0x9F8000), BusTarget::Rom(0x0F8000));
+
+        // There is no SRAM to decode to.
+        assert_eq!(bus.decode(0x700000), BusTarget::OpenBus);
+
+        // 2KiB of SRAM is mirrored through its banks.
+        let bus = Bus::new(RomSize::LoRom, 1024 * 1024, 2 * 1024);
+        assert_eq!(bus.decode(0x700800), BusTarget::Sram(0x0000));
+    }
+}
@@ -0,0 +1,734 @@
+use std::{cell::RefCell, ops::RangeInclusive};
+
+use super::{compose_address, io::IoRegisters, Memory};
+use crate::emu::{scheduler::Component, EmulationError};
+
+/**************************************** Constant Values ***************************************************************/
+
+/// MDMAEN, which starts a general purpose DMA on each channel whose bit is written as 1.
+pub const MDMAEN_ADDR: u16 = 0x420B;
+
+/// HDMAEN, which enables HDMA on each channel whose bit is 1.
+pub const HDMAEN_ADDR: u16 = 0x420C;
+
+/// MDMAEN and HDMAEN.
+pub const ENABLE_REGISTERS: RangeInclusive<u16> = MDMAEN_ADDR..=HDMAEN_ADDR;
+
+/// Registers of the eight channels, 16 addresses each at $43x0-$43xF.
+pub const CHANNEL_REGISTERS: RangeInclusive<u16> = CHANNEL_START..=0x437F;
+
+/// Address of channel 0's first register.
+const CHANNEL_START: u16 = 0x4300;
+
+/// Number of DMA channels.
+const CHANNEL_COUNT: usize = 8;
+
+/// Start of the B-bus, which DMA reaches through the bottom byte of its address.
+const B_BUS_START: usize = 0x002100;
+
+/// Master clocks taken to move one byte.
+const BYTE_CLOCKS: usize = 8;
+
+/// Master clocks of overhead for each channel which runs.
+const CHANNEL_CLOCKS: usize = 8;
+
+/// Master clocks of overhead to start a DMA, once the CPU has stopped on a multiple of 8 master clocks.
+const START_CLOCKS: usize = 8;
+
+/// Master clocks of overhead each time HDMA runs on any channel.
+const HDMA_START_CLOCKS: usize = 18;
+
+/// Master clocks taken to load the line counter of an HDMA table entry.
+const HDMA_ENTRY_CLOCKS: usize = 8;
+
+/// Master clocks taken to load the indirect address of an HDMA table entry.
+const HDMA_INDIRECT_CLOCKS: usize = 16;
+
+/// Offsets from the B-bus address of the registers written for each byte, for each transfer mode.
+/// Modes 6 and 7 are the same as modes 2 and 3.
+const TRANSFER_PATTERNS: [&[u8]; 8] = [
+    &[0],
+    &[0, 1],
+    &[0, 0],
+    &[0, 0, 1, 1],
+    &[0, 1, 2, 3],
+    &[0, 1, 0, 1],
+    &[0, 0],
+    &[0, 0, 1, 1],
+];
+
+/// Bit of DMAPx set when the transfer reads from the B-bus and writes to the A-bus.
+const DIRECTION_BIT: u8 = 0x80;
+
+/// Bit of DMAPx set when HDMA table entries hold the address of their data, rather than the data itself.
+const INDIRECT_BIT: u8 = 0x40;
+
+/// Bit of DMAPx which fixes the A-bus address.
+const FIXED_BIT: u8 = 0x08;
+
+/// Bit of DMAPx which decrements the A-bus address, unless it is fixed.
+const DECREMENT_BIT: u8 = 0x10;
+
+/// Bits of DMAPx which select the transfer mode.
+const MODE_MASK: u8 = 0x07;
+
+/// Bit of an HDMA line counter set to transfer on every line of the entry, rather than just the first.
+const REPEAT_BIT: u8 = 0x80;
+
+/// Bits of an HDMA line counter which count the lines left in the entry.
+const LINE_COUNT_MASK: u8 = 0x7F;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Registers of one DMA channel, at $43x0-$43xF, and its HDMA state.
+/// https://snes.nesdev.org/wiki/DMA_registers
+///     control:            DMAPx, the direction, HDMA addressing, A-bus step and transfer mode.
+///     b_address:          BBADx, the B-bus register, $21xx.
+///     a_address:          A1TxL/H, the A-bus address, which steps as bytes are moved. HDMA tables start here.
+///     a_bank:             A1Bx, the A-bus bank, which never steps. HDMA tables are in this bank.
+///     count:              DASxL/H, the bytes left to move. 0 means 65536. HDMA keeps its indirect address here.
+///     indirect_bank:      DASBx, the bank of HDMA indirect data.
+///     table_address:      A2AxL/H, the address of the next HDMA table entry, or of its data.
+///     line_counter:       NTRLx, the HDMA line counter and repeat flag.
+///     unused:             Byte at $43xB and $43xF, which holds whatever is written to it.
+///     hdma_terminated:    Whether HDMA has reached the end of the table for this frame.
+///     hdma_do_transfer:   Whether HDMA transfers on the next line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Channel {
+    control: u8,
+    b_address: u8,
+    a_address: u16,
+    a_bank: u8,
+    count: u16,
+    indirect_bank: u8,
+    table_address: u16,
+    line_counter: u8,
+    unused: u8,
+    hdma_terminated: bool,
+    hdma_do_transfer: bool,
+}
+
+impl Channel {
+    /// Find the B-bus register offsets the transfer mode writes in turn.
+    fn pattern(&self) -> &'static [u8] { TRANSFER_PATTERNS[(self.control & MODE_MASK) as usize] }
+
+    /// Check whether the channel moves bytes from the B-bus to the A-bus.
+    fn b_to_a(&self) -> bool { self.control & DIRECTION_BIT != 0 }
+
+    /// Find the full B-bus address of a byte of the transfer.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `index`:  Index of the byte into the transfer.
+    fn b_bus_address(&self, index: usize) -> usize {
+        let pattern = self.pattern();
+        B_BUS_START | self.b_address.wrapping_add(pattern[index % pattern.len()]) as usize
+    }
+
+    /// Step the A-bus address after a byte has been moved. It wraps within its bank.
+    fn step_a_address(&mut self) {
+        if self.control & FIXED_BIT == 0 {
+            self.a_address = match self.control & DECREMENT_BIT {
+                0 => self.a_address.wrapping_add(1),
+                _ => self.a_address.wrapping_sub(1),
+            };
+        }
+    }
+
+    /// Move one byte of a general purpose DMA.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory to move the byte through.
+    ///     - `index`:  Index of the byte into the transfer.
+    /// # Returns:
+    ///     - `true` if that was the last byte.
+    fn transfer_byte(&mut self, memory: &mut Memory, index: usize) -> bool {
+        let a_address = compose_address(self.a_bank, self.a_address);
+        move_byte(memory, a_address, self.b_bus_address(index), self.b_to_a());
+
+        self.step_a_address();
+        self.count = self.count.wrapping_sub(1);
+        self.count == 0
+    }
+
+    /// Read the next byte of the HDMA table.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory the table is in.
+    fn read_table(&mut self, memory: &mut Memory) -> u8 {
+        let value = read_a_bus(memory, compose_address(self.a_bank, self.table_address));
+        self.table_address = self.table_address.wrapping_add(1);
+        value
+    }
+
+    /// Load the next HDMA table entry. A line count of 0 ends the table.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory the table is in.
+    /// # Returns:
+    ///     - The number of master clocks taken.
+    fn load_hdma_entry(&mut self, memory: &mut Memory) -> usize {
+        self.line_counter = self.read_table(memory);
+        self.hdma_terminated = self.line_counter == 0;
+        self.hdma_do_transfer = true;
+
+        let mut clocks = HDMA_ENTRY_CLOCKS;
+        if self.control & INDIRECT_BIT != 0 {
+            let low = self.read_table(memory);
+            let high = self.read_table(memory);
+            self.count = u16::from_le_bytes([low, high]);
+            clocks += HDMA_INDIRECT_CLOCKS;
+        }
+        clocks
+    }
+
+    /// Start HDMA from the top of the table, at the start of the frame.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory the table is in.
+    /// # Returns:
+    ///     - The number of master clocks taken.
+    fn init_hdma(&mut self, memory: &mut Memory) -> usize {
+        self.table_address = self.a_address;
+        CHANNEL_CLOCKS + self.load_hdma_entry(memory)
+    }
+
+    /// Run HDMA for one scanline, transferring if the entry calls for it, and moving on to the next entry once the
+    /// current one has run for all of its lines.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory to move bytes through.
+    /// # Returns:
+    ///     - The number of master clocks taken.
+    fn run_hdma_line(&mut self, memory: &mut Memory) -> usize {
+        let mut clocks = CHANNEL_CLOCKS;
+        if self.hdma_do_transfer {
+            let indirect = self.control & INDIRECT_BIT != 0;
+            for index in 0..self.pattern().len() {
+                let a_address = match indirect {
+                    true => compose_address(self.indirect_bank, self.count),
+                    false => compose_address(self.a_bank, self.table_address),
+                };
+                move_byte(memory, a_address, self.b_bus_address(index), self.b_to_a());
+                match indirect {
+                    true => self.count = self.count.wrapping_add(1),
+                    false => self.table_address = self.table_address.wrapping_add(1),
+                }
+                clocks += BYTE_CLOCKS;
+            }
+        }
+
+        self.line_counter = self.line_counter.wrapping_sub(1);
+        self.hdma_do_transfer = self.line_counter & REPEAT_BIT != 0;
+        if self.line_counter & LINE_COUNT_MASK == 0 {
+            clocks += self.load_hdma_entry(memory);
+        }
+        clocks
+    }
+
+    /// Read one of the channel's registers.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `register`:   Index of the register, $0-$F.
+    /// # Returns:
+    ///     - `Some(value)`:    The value of the register.
+    ///     - `None`:           If nothing is at the index, so the read sees open bus.
+    fn read_register(&self, register: u16) -> Option<u8> {
+        let [a_low, a_high] = self.a_address.to_le_bytes();
+        let [count_low, count_high] = self.count.to_le_bytes();
+        let [table_low, table_high] = self.table_address.to_le_bytes();
+        match register {
+            0x0 => Some(self.control),
+            0x1 => Some(self.b_address),
+            0x2 => Some(a_low),
+            0x3 => Some(a_high),
+            0x4 => Some(self.a_bank),
+            0x5 => Some(count_low),
+            0x6 => Some(count_high),
+            0x7 => Some(self.indirect_bank),
+            0x8 => Some(table_low),
+            0x9 => Some(table_high),
+            0xA => Some(self.line_counter),
+            0xB | 0xF => Some(self.unused),
+            _ => None,
+        }
+    }
+
+    /// Write one of the channel's registers.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `register`:   Index of the register, $0-$F.
+    ///     - `value`:      Value written.
+    fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            0x0 => self.control = value,
+            0x1 => self.b_address = value,
+            0x2 => self.a_address = (self.a_address & 0xFF00) | value as u16,
+            0x3 => self.a_address = (self.a_address & 0x00FF) | (value as u16) << 8,
+            0x4 => self.a_bank = value,
+            0x5 => self.count = (self.count & 0xFF00) | value as u16,
+            0x6 => self.count = (self.count & 0x00FF) | (value as u16) << 8,
+            0x7 => self.indirect_bank = value,
+            0x8 => self.table_address = (self.table_address & 0xFF00) | value as u16,
+            0x9 => self.table_address = (self.table_address & 0x00FF) | (value as u16) << 8,
+            0xA => self.line_counter = value,
+            0xB | 0xF => self.unused = value,
+            _ => {}
+        }
+    }
+}
+
+/// The DMA controller's registers: MDMAEN and HDMAEN at $420B-$420C, and the eight channels at $4300-$437F.
+///     channels:       Registers of each channel.
+///     pending:        Channels written to MDMAEN whose general purpose DMA hasn't finished yet.
+///     hdma_enabled:   Channels HDMA is enabled on.
+///     running:        Whether a general purpose DMA has started, and so has stopped the CPU.
+///     byte_index:     Bytes moved so far by the channel the general purpose DMA is running on.
+#[derive(Debug)]
+pub struct Dma {
+    channels: [Channel; CHANNEL_COUNT],
+    pending: u8,
+    hdma_enabled: u8,
+    running: bool,
+    byte_index: usize,
+}
+
+impl Dma {
+    /// Return a DMA controller with every register 0 and nothing pending.
+    pub fn new() -> Self {
+        Self {
+            channels: [Channel::default(); CHANNEL_COUNT],
+            pending: 0,
+            hdma_enabled: 0,
+            running: false,
+            byte_index: 0,
+        }
+    }
+
+    /// Stop the general purpose DMA on channels HDMA has taken over.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `channels`:   Channels HDMA ran on.
+    fn stop_gpdma(&mut self, channels: u8) {
+        let current = self.pending & self.pending.wrapping_neg();
+        if current & channels != 0 {
+            self.byte_index = 0;
+        }
+        self.pending &= !channels;
+    }
+}
+
+impl Component for Dma {
+    /// Move the next byte of the general purpose DMA, on the lowest channel which hasn't finished.
+    /// Returns 0 master clocks if there is nothing left to move.
+    fn tick(&mut self, memory: &mut Memory) -> Result<usize, EmulationError> {
+        if self.pending == 0 {
+            return Ok(0);
+        }
+
+        let index = self.pending.trailing_zeros() as usize;
+        let mut clocks = BYTE_CLOCKS;
+        if self.byte_index == 0 {
+            clocks += CHANNEL_CLOCKS;
+        }
+
+        // DMA can't reach its own registers, so moving the byte never borrows the controller again.
+        let finished = self.channels[index].transfer_byte(memory, self.byte_index);
+        self.byte_index += 1;
+        if finished {
+            self.pending &= !(1 << index);
+            self.byte_index = 0;
+        }
+        Ok(clocks)
+    }
+
+    /// Run the general purpose DMA on the channels written to MDMAEN, in order from channel 0.
+    /// The CPU is stopped until it finishes. It runs in parts, so that HDMA can interrupt it on each scanline.
+    /// Returns `elapsed` unchanged once there is no DMA left to run.
+    /// https://snes.nesdev.org/wiki/DMA_registers#Timing
+    fn run_until(
+        &mut self, elapsed: usize, target: usize, memory: &mut Memory,
+    ) -> Result<usize, EmulationError> {
+        if self.pending == 0 {
+            self.running = false;
+            return Ok(elapsed);
+        }
+
+        let mut elapsed = elapsed;
+        if !self.running {
+            // The CPU stops on the next multiple of 8 master clocks.
+            self.running = true;
+            self.byte_index = 0;
+            elapsed += (BYTE_CLOCKS - elapsed % BYTE_CLOCKS) % BYTE_CLOCKS + START_CLOCKS;
+        }
+        while elapsed < target && self.pending != 0 {
+            elapsed += self.tick(memory)?;
+        }
+        Ok(elapsed)
+    }
+}
+
+impl IoRegisters for Dma {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        match address {
+            // MDMAEN and HDMAEN are write only.
+            MDMAEN_ADDR | HDMAEN_ADDR => None,
+            _ => self.peek(address),
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            MDMAEN_ADDR => Some(self.pending),
+            HDMAEN_ADDR => Some(self.hdma_enabled),
+            _ => {
+                let offset = address - CHANNEL_START;
+                self.channels[(offset >> 4) as usize].read_register(offset & 0x0F)
+            }
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            MDMAEN_ADDR => self.pending = value,
+            HDMAEN_ADDR => self.hdma_enabled = value,
+            _ => {
+                let offset = address - CHANNEL_START;
+                self.channels[(offset >> 4) as usize].write_register(offset & 0x0F, value);
+            }
+        }
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Check whether DMA can reach an A-bus address. It can't reach the B-bus or the DMA registers through the A-bus.
+/// # Parameters:
+///     - `address`:    Composed 24-bit A-bus address.
+fn a_bus_reachable(address: usize) -> bool {
+    let system_bank = (address >> 16) & 0x40 == 0;
+    let offset = address as u16;
+    let dma_register = matches!(offset, MDMAEN_ADDR | HDMAEN_ADDR | 0x4300..=0x437F);
+    !(system_bank && (dma_register || (0x2100..=0x21FF).contains(&offset)))
+}
+
+/// Read a byte from the A-bus, which sees open bus where DMA can't reach.
+/// # Parameters:
+///     - `memory`:     Memory to read.
+///     - `address`:    Composed 24-bit A-bus address.
+fn read_a_bus(memory: &mut Memory, address: usize) -> u8 {
+    match a_bus_reachable(address) {
+        true => memory.read(address),
+        false => memory.open_bus(),
+    }
+}
+
+/// Move one byte between the A-bus and the B-bus.
+/// # Parameters:
+///     - `memory`:     Memory to move the byte through.
+///     - `a_address`:  Composed 24-bit A-bus address.
+///     - `b_address`:  Full address of the B-bus register.
+///     - `b_to_a`:     True to read from the B-bus and write to the A-bus, false for the other way.
+fn move_byte(memory: &mut Memory, a_address: usize, b_address: usize, b_to_a: bool) {
+    match b_to_a {
+        true => {
+            let value = memory.read(b_address);
+            if a_bus_reachable(a_address) {
+                memory.write(a_address, value);
+            }
+        }
+        false => {
+            let value = read_a_bus(memory, a_address);
+            memory.write(b_address, value);
+        }
+    }
+}
+
+/// Run HDMA on a set of channels.
+/// # Parameters:
+///     - `dma`:        DMA controller, which is also attached to memory.
+///     - `memory`:     Memory to move bytes through.
+///     - `channels`:   Channels to run on.
+///     - `run`:        What to run on each channel, returning the master clocks it took.
+/// # Returns:
+///     - The number of master clocks the CPU is stopped for, or 0 if there were no channels to run.
+fn run_hdma_channels(
+    dma: &RefCell<Dma>, memory: &mut Memory, channels: u8,
+    run: impl Fn(&mut Channel, &mut Memory) -> usize,
+) -> usize {
+    if channels == 0 {
+        return 0;
+    }
+
+    // HDMA takes priority over a general purpose DMA, and takes over its channels.
+    dma.borrow_mut().stop_gpdma(channels);
+
+    let mut clocks = HDMA_START_CLOCKS;
+    for index in (0..CHANNEL_COUNT).filter(|index| channels & (1 << index) != 0) {
+        // The registers are copied out, since the bus may be attached to registers which borrow the controller.
+        let mut channel = dma.borrow().channels[index];
+        clocks += run(&mut channel, memory);
+        dma.borrow_mut().channels[index] = channel;
+    }
+    clocks
+}
+
+/**************************************** Public Functions **************************************************************/
+
+/// Start HDMA on every enabled channel from the top of its table, at the start of the frame.
+/// # Parameters:
+///     - `dma`:        DMA controller, which is also attached to memory.
+///     - `memory`:     Memory the tables are in.
+/// # Returns:
+///     - The number of master clocks the CPU is stopped for.
+pub fn init_hdma(dma: &RefCell<Dma>, memory: &mut Memory) -> usize {
+    let channels = dma.borrow().hdma_enabled;
+    run_hdma_channels(dma, memory, channels, Channel::init_hdma)
+}
+
+/// Run HDMA for one scanline on every enabled channel which hasn't reached the end of its table.
+/// # Parameters:
+///     - `dma`:        DMA controller, which is also attached to memory.
+///     - `memory`:     Memory to move bytes through.
+/// # Returns:
+///     - The number of master clocks the CPU is stopped for.
+pub fn run_hdma(dma: &RefCell<Dma>, memory: &mut Memory) -> usize {
+    let channels = {
+        let dma = dma.borrow();
+        (0..CHANNEL_COUNT)
+            .filter(|index| !dma.channels[*index].hdma_terminated)
+            .fold(0, |channels, index| channels | (1 << index))
+            & dma.hdma_enabled
+    };
+    run_hdma_channels(dma, memory, channels, Channel::run_hdma_line)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    /// Registers which record every write made to them.
+    struct RecordingRegisters {
+        writes: Vec<(u16, u8)>,
+    }
+
+    impl IoRegisters for RecordingRegisters {
+        fn read(&mut self, address: u16) -> Option<u8> { Some(address as u8) }
+
+        fn peek(&self, address: u16) -> Option<u8> { Some(address as u8) }
+
+        fn write(&mut self, address: u16, value: u8) { self.writes.push((address, value)); }
+    }
+
+    /// Set up memory with the DMA controller and a recorder at $2118-$211B attached.
+    fn setup_dma_test() -> (Memory, Rc<RefCell<Dma>>, Rc<RefCell<RecordingRegisters>>) {
+        let mut memory = Memory::new();
+        let dma = Rc::new(RefCell::new(Dma::new()));
+        let recorder = Rc::new(RefCell::new(RecordingRegisters { writes: vec![] }));
+        memory.register_io(ENABLE_REGISTERS, dma.clone()).unwrap();
+        memory.register_io(CHANNEL_REGISTERS, dma.clone()).unwrap();
+        memory.register_io(0x2118..=0x211B, recorder.clone()).unwrap();
+
+        for offset in 0..0x10 {
+            memory.write(0x7E1000 + offset, offset as u8);
+        }
+        (memory, dma, recorder)
+    }
+
+    /// Run the general purpose DMA until it has finished.
+    fn run(dma: &RefCell<Dma>, memory: &mut Memory, elapsed: usize) -> usize {
+        dma.borrow_mut().run_until(elapsed, usize::MAX, memory).unwrap()
+    }
+
+    /// Set up a channel's registers through the bus.
+    fn setup_channel(memory: &mut Memory, channel: usize, registers: &[u8]) {
+        for (offset, value) in registers.iter().enumerate() {
+            memory.write(0x004300 + channel * 0x10 + offset, *value);
+        }
+    }
+
+    #[test]
+    fn test_transfer_modes() {
+        let test_cases: Vec<(u8, Vec<u16>)> = vec![
+            // mode, B-bus registers written for 4 bytes
+            (0, vec![0x2118, 0x2118, 0x2118, 0x2118]),
+            (1, vec![0x2118, 0x2119, 0x2118, 0x2119]),
+            (2, vec![0x2118, 0x2118, 0x2118, 0x2118]),
+            (3, vec![0x2118, 0x2118, 0x2119, 0x2119]),
+            (4, vec![0x2118, 0x2119, 0x211A, 0x211B]),
+            (5, vec![0x2118, 0x2119, 0x2118, 0x2119]),
+            (6, vec![0x2118, 0x2118, 0x2118, 0x2118]),
+            (7, vec![0x2118, 0x2118, 0x2119, 0x2119]),
+        ];
+
+        for (mode, registers) in test_cases {
+            println!("Test Case: mode {}", mode);
+            let (mut memory, dma, recorder) = setup_dma_test();
+            setup_channel(&mut memory, 2, &[mode, 0x18, 0x00, 0x10, 0x7E, 0x04, 0x00]);
+            memory.write(0x00420B, 0x04);
+            run(&dma, &mut memory, 0);
+
+            let expected: Vec<(u16, u8)> = registers.into_iter().zip(0..4).collect();
+            assert_eq!(recorder.borrow().writes, expected);
+
+            // The A-bus address is left after the last byte, and the count at 0.
+            assert_eq!(memory.read(0x004322), 0x04);
+            assert_eq!(memory.read(0x004325), 0x00);
+        }
+    }
+
+    #[test]
+    fn test_a_bus_step() {
+        let test_cases = vec![
+            // DMAPx, bytes read
+            (0x00, vec![0x04, 0x05, 0x06]),
+            (0x08, vec![0x04, 0x04, 0x04]),
+            (0x10, vec![0x04, 0x03, 0x02]),
+            (0x18, vec![0x04, 0x04, 0x04]),
+        ];
+
+        for (control, bytes) in test_cases {
+            println!("Test Case: {:#04X}", control);
+            let (mut memory, dma, recorder) = setup_dma_test();
+            setup_channel(&mut memory, 0, &[control, 0x18, 0x04, 0x10, 0x7E, 0x03, 0x00]);
+            memory.write(0x00420B, 0x01);
+            run(&dma, &mut memory, 0);
+
+            let writes = &recorder.borrow().writes;
+            let written: Vec<u8> = writes.iter().map(|(_, value)| *value).collect();
+            assert_eq!(written, bytes);
+        }
+    }
+
+    #[test]
+    fn test_b_to_a() {
+        let (mut memory, dma, _) = setup_dma_test();
+
+        // Read WRAM back through the WMDATA port, into $7E:2000.
+        memory.write(0x002181, 0x00);
+        memory.write(0x002182, 0x10);
+        setup_channel(&mut memory, 7, &[0x80, 0x80, 0x00, 0x20, 0x7E, 0x10, 0x00]);
+        memory.write(0x00420B, 0x80);
+        run(&dma, &mut memory, 0);
+
+        for offset in 0..0x10 {
+            assert_eq!(memory.get_byte(0x7E2000 + offset).unwrap(), offset as u8);
+        }
+    }
+
+    #[test]
+    fn test_unreachable_a_bus() {
+        let (mut memory, dma, recorder) = setup_dma_test();
+
+        // The channel can't read its own registers through the A-bus, so it sees open bus.
+        setup_channel(&mut memory, 0, &[0x00, 0x18, 0x00, 0x43, 0x00, 0x02, 0x00]);
+        memory.write(0x00420B, 0x01);
+        run(&dma, &mut memory, 0);
+        assert_eq!(recorder.borrow().writes, vec![(0x2118, 0x01), (0x2118, 0x01)]);
+    }
+
+    #[test]
+    fn test_timing() {
+        let (mut memory, dma, _) = setup_dma_test();
+
+        // Nothing runs until MDMAEN is written.
+        assert_eq!(run(&dma, &mut memory, 0), 0);
+
+        // Two channels of 16 bytes, one of them with a count of 0 to move 65536 bytes.
+        setup_channel(&mut memory, 0, &[0x00, 0x18, 0x00, 0x10, 0x7E, 0x10, 0x00]);
+        setup_channel(&mut memory, 1, &[0x00, 0x18, 0x00, 0x10, 0x7E, 0x00, 0x00]);
+        memory.write(0x00420B, 0x03);
+        let elapsed = run(&dma, &mut memory, 3);
+        assert_eq!(elapsed, 3 + 5 + START_CLOCKS + 2 * CHANNEL_CLOCKS + (0x10 + 0x10000) * BYTE_CLOCKS);
+
+        // MDMAEN is cleared once the DMA has run.
+        assert_eq!(memory.get_byte(0x00420B).unwrap(), 0x00);
+        assert_eq!(run(&dma, &mut memory, elapsed), elapsed);
+    }
+
+    #[test]
+    fn test_hdma_direct() {
+        let (mut memory, dma, recorder) = setup_dma_test();
+        let table = [0x02, 0x11, 0x12, 0x83, 0x21, 0x22, 0x31, 0x32, 0x41, 0x42, 0x00];
+        for (offset, byte) in table.into_iter().enumerate() {
+            memory.write(0x7E2000 + offset, byte);
+        }
+        setup_channel(&mut memory, 1, &[0x01, 0x18, 0x00, 0x20, 0x7E]);
+        memory.write(0x00420C, 0x02);
+
+        let clocks = init_hdma(&dma, &mut memory);
+        assert_eq!(clocks, HDMA_START_CLOCKS + CHANNEL_CLOCKS + HDMA_ENTRY_CLOCKS);
+        let clocks = run_hdma(&dma, &mut memory);
+        assert_eq!(clocks, HDMA_START_CLOCKS + CHANNEL_CLOCKS + 2 * BYTE_CLOCKS);
+        for _ in 1..8 {
+            run_hdma(&dma, &mut memory);
+        }
+
+        // The first entry transfers once and waits a line, and the second repeats for 3 lines.
+        let expected = vec![
+            (0x2118, 0x11),
+            (0x2119, 0x12),
+            (0x2118, 0x21),
+            (0x2119, 0x22),
+            (0x2118, 0x31),
+            (0x2119, 0x32),
+            (0x2118, 0x41),
+            (0x2119, 0x42),
+        ];
+        assert_eq!(recorder.borrow().writes, expected);
+
+        // Once the table has ended, nothing runs until the next frame.
+        assert_eq!(run_hdma(&dma, &mut memory), 0);
+        init_hdma(&dma, &mut memory);
+        run_hdma(&dma, &mut memory);
+        assert_eq!(recorder.borrow().writes.len(), 10);
+    }
+
+    #[test]
+    fn test_hdma_indirect() {
+        let (mut memory, dma, recorder) = setup_dma_test();
+        for (offset, byte) in [0x82, 0x00, 0x10, 0x00].into_iter().enumerate() {
+            memory.write(0x7E2000 + offset, byte);
+        }
+        setup_channel(&mut memory, 0, &[0x40, 0x18, 0x00, 0x20, 0x7E, 0x00, 0x00, 0x7E]);
+        memory.write(0x00420C, 0x01);
+
+        let clocks = init_hdma(&dma, &mut memory);
+        let entry_clocks = HDMA_ENTRY_CLOCKS + HDMA_INDIRECT_CLOCKS;
+        assert_eq!(clocks, HDMA_START_CLOCKS + CHANNEL_CLOCKS + entry_clocks);
+        for _ in 0..3 {
+            run_hdma(&dma, &mut memory);
+        }
+
+        // The data comes from $7E:1000, where the test data counts up from 0.
+        assert_eq!(recorder.borrow().writes, vec![(0x2118, 0x00), (0x2118, 0x01)]);
+    }
+
+    #[test]
+    fn test_hdma_interrupts_gpdma() {
+        let (mut memory, dma, recorder) = setup_dma_test();
+        memory.write(0x7E2000, 0x00);
+        setup_channel(&mut memory, 0, &[0x00, 0x19, 0x00, 0x20, 0x7E]);
+        setup_channel(&mut memory, 1, &[0x00, 0x18, 0x00, 0x10, 0x7E, 0x04, 0x00]);
+        setup_channel(&mut memory, 2, &[0x00, 0x1A, 0x00, 0x10, 0x7E, 0x04, 0x00]);
+        memory.write(0x00420B, 0x06);
+
+        // The DMA stops part way through channel 1, when the budget runs out.
+        let budget = START_CLOCKS + CHANNEL_CLOCKS + 2 * BYTE_CLOCKS;
+        let elapsed = dma.borrow_mut().run_until(0, budget, &mut memory).unwrap();
+        assert_eq!(elapsed, budget);
+
+        // HDMA starting on channel 2 stops its general purpose DMA, but channel 1 carries on where it left off.
+        memory.write(0x00420C, 0x04);
+        init_hdma(&dma, &mut memory);
+        let elapsed = run(&dma, &mut memory, elapsed);
+        assert_eq!(elapsed, budget + 2 * BYTE_CLOCKS);
+        assert_eq!(run(&dma, &mut memory, elapsed), elapsed);
+
+        let writes: Vec<(u16, u8)> = recorder.borrow().writes.clone();
+        assert_eq!(writes, vec![(0x2118, 0x00), (0x2118, 0x01), (0x2118, 0x02), (0x2118, 0x03)]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{cell::RefCell, fmt, ops::RangeInclusive, rc::Rc};
+
+use super::bus;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Behaviour attached to a range of I/O register addresses in the system banks, such as the PPU's at $2100-$213F.
+/// Addresses passed in are the 16-bit register address, whichever bank the access was made through.
+pub trait IoRegisters {
+    /// Read a register, as the CPU or DMA does. This may have side effects, such as clearing a flag or moving a latch.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the register.
+    /// # Returns:
+    ///     - `Some(value)`:    The value read.
+    ///     - `None`:           If the register is write only, so the read sees open bus.
+    fn read(&mut self, address: u16) -> Option<u8>;
+
+    /// Look at a register without any side effects, for the debugger.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the register.
+    /// # Returns:
+    ///     - `Some(value)`:    The value a read would return, or the value last written to a write only register.
+    ///     - `None`:           If there is nothing to show.
+    fn peek(&self, address: u16) -> Option<u8>;
+
+    /// Write a register.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the register.
+    ///     - `value`:      Value written.
+    fn write(&mut self, address: u16, value: u8);
+}
+
+/// Shared handle to a set of registers, so the component that owns them can keep using them once they are attached.
+pub type IoHandler = Rc<RefCell<dyn IoRegisters>>;
+
+/// Error which is returned when registers can't be attached to a range of addresses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoRangeError {
+    start: u16,
+    end: u16,
+}
+
+impl fmt::Display for IoRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "I/O range ${:04X}-${:04X} is outside the I/O area or already has registers attached",
+            self.start, self.end
+        )
+    }
+}
+
+/// Table of which registers are attached to each address in the I/O area.
+///     handlers:   Every set of registers attached.
+///     map:        For each address in the I/O area, the index into `handlers` of the registers attached to it.
+pub struct IoMap {
+    handlers: Vec<IoHandler>,
+    map: Vec<Option<usize>>,
+}
+
+impl IoMap {
+    /// Return a table with nothing attached.
+    pub fn new() -> Self {
+        Self {
+            handlers: vec![],
+            map: vec![None; bus::IO_SIZE],
+        }
+    }
+
+    /// Attach registers to a range of addresses.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `range`:      Addresses to attach to, from $2000-$5FFF.
+    ///     - `handler`:    Registers which handle accesses to the range.
+    /// # Returns:
+    ///     - `Ok(())`:     If the registers were attached.
+    ///     - `Err(error)`: If the range is outside the I/O area, or overlaps registers already attached.
+    pub fn register(
+        &mut self, range: RangeInclusive<u16>, handler: IoHandler,
+    ) -> Result<(), IoRangeError> {
+        let error = IoRangeError {
+            start: *range.start(),
+            end: *range.end(),
+        };
+        let (Some(start), Some(end)) = (index(*range.start()), index(*range.end()))
+        else {
+            return Err(error);
+        };
+        if start > end || self.map[start..=end].iter().any(Option::is_some) {
+            return Err(error);
+        }
+
+        self.handlers.push(handler);
+        self.map[start..=end].fill(Some(self.handlers.len() - 1));
+        Ok(())
+    }
+
+    /// Find the registers attached to an address.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the register.
+    /// # Returns:
+    ///     - `Some(handler)`:  The registers attached.
+    ///     - `None`:           If nothing is attached.
+    pub fn get(&self, address: u16) -> Option<&IoHandler> {
+        index(address)
+            .and_then(|index| self.map[index])
+            .map(|handler| &self.handlers[handler])
+    }
+}
+
+/// Write only registers which hold whatever was last written to them, with no other behaviour.
+/// Reads see open bus, but the latched values can still be peeked at by the debugger and by other components.
+///     start:  Address of the first register.
+///     values: Value of each register.
+pub struct LatchRegisters {
+    start: u16,
+    values: Vec<u8>,
+}
+
+impl LatchRegisters {
+    /// Return a set of latches covering a range of addresses, all holding 0.
+    /// # Parameters:
+    ///     - `range`:  Addresses covered.
+    pub fn new(range: RangeInclusive<u16>) -> Self {
+        Self {
+            start: *range.start(),
+            values: vec![0; range.len()],
+        }
+    }
+}
+
+impl IoRegisters for LatchRegisters {
+    fn read(&mut self, _address: u16) -> Option<u8> { None }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        Some(self.values[(address - self.start) as usize])
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.values[(address - self.start) as usize] = value;
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Find the index of an address into the I/O area.
+/// # Parameters:
+///     - `address`:    Address of the register.
+/// # Returns:
+///     - `Some(index)`:    Index into the I/O area.
+///     - `None`:           If the address is outside of it.
+fn index(address: u16) -> Option<usize> {
+    address
+        .checked_sub(bus::IO_START)
+        .map(|index| index as usize)
+        .filter(|index| *index < bus::IO_SIZE)
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let mut io = IoMap::new();
+        let latches = Rc::new(RefCell::new(LatchRegisters::new(0x2100..=0x213F)));
+        io.register(0x2100..=0x213F, latches.clone()).unwrap();
+
+        assert!(io.get(0x20FF).is_none());
+        assert!(io.get(0x2140).is_none());
+
+        io.get(0x2105).unwrap().borrow_mut().write(0x2105, 0xAA);
+        assert_eq!(latches.borrow().peek(0x2105), Some(0xAA));
+    }
+
+    #[test]
+    fn test_register_bad_range() {
+        let mut io = IoMap::new();
+        let latches = Rc::new(RefCell::new(LatchRegisters::new(0x4200..=0x421F)));
+        io.register(0x4200..=0x421F, latches.clone()).unwrap();
+
+        let test_cases = vec![
+            // Overlapping the start, the end, and the whole of the registers already attached.
+            0x41F0..=0x4200,
+            0x421F..=0x4220,
+            0x4100..=0x4300,
+            // Outside of the I/O area.
+            0x1FFF..=0x2000,
+            0x5FFF..=0x6000,
+        ];
+
+        for range in test_cases {
+            println!("Test Case: {:04X?}", range);
+            assert!(io.register(range, latches.clone()).is_err());
+        }
+    }
+}
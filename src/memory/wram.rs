@@ -0,0 +1,143 @@
+use super::{bus::WRAM_SIZE, io::IoRegisters};
+
+/**************************************** Constant Values ***************************************************************/
+
+/// WMDATA, which reads or writes WRAM at the port address, and then increments it.
+pub const WMDATA_ADDR: u16 = 0x2180;
+
+/// WMADDL, the low byte of the port address.
+const WMADDL_ADDR: u16 = 0x2181;
+
+/// WMADDM, the middle byte of the port address.
+const WMADDM_ADDR: u16 = 0x2182;
+
+/// WMADDH, the high bit of the port address.
+pub const WMADDH_ADDR: u16 = 0x2183;
+
+/// Mask for the 17-bit port address.
+const PORT_ADDRESS_MASK: usize = WRAM_SIZE - 1;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Wrapper type for a u8 array which represents work RAM.
+pub type WramData = Box<[u8; WRAM_SIZE]>;
+
+/// The 128KiB of work RAM, and the port at $2180-$2183 that reaches it over the B-bus.
+/// It is seen at $7E0000-$7FFFFF, and its first 8KiB is mirrored into the system banks.
+///     data:           Contents of WRAM.
+///     port_address:   17-bit address the WMDATA port reads and writes.
+pub struct Wram {
+    data: WramData,
+    port_address: usize,
+}
+
+impl Wram {
+    /// Return WRAM with every byte 0.
+    pub fn new() -> Self {
+        Self {
+            // https://github.com/rust-lang/rust/issues/53827
+            data: vec![0; WRAM_SIZE].into_boxed_slice().try_into().unwrap(),
+            port_address: 0,
+        }
+    }
+
+    /// Get a byte of WRAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `offset`: Offset into WRAM.
+    pub fn get(&self, offset: usize) -> u8 { self.data[offset] }
+
+    /// Set a byte of WRAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `offset`: Offset into WRAM.
+    ///     - `value`:  Value to store.
+    pub fn set(&mut self, offset: usize, value: u8) { self.data[offset] = value; }
+
+    /// Replace one byte of the port address.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `shift`:  Bit position of the byte to replace.
+    ///     - `value`:  New value of the byte.
+    fn set_port_address_byte(&mut self, shift: usize, value: u8) {
+        let address = (self.port_address & !(0xFF << shift)) | ((value as usize) << shift);
+        self.port_address = address & PORT_ADDRESS_MASK;
+    }
+}
+
+impl IoRegisters for Wram {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        match address {
+            WMDATA_ADDR => {
+                let value = self.data[self.port_address];
+                self.port_address = (self.port_address + 1) & PORT_ADDRESS_MASK;
+                Some(value)
+            }
+            // The address registers are write only.
+            _ => None,
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let [low, middle, high, _] = (self.port_address as u32).to_le_bytes();
+        match address {
+            WMDATA_ADDR => Some(self.data[self.port_address]),
+            WMADDL_ADDR => Some(low),
+            WMADDM_ADDR => Some(middle),
+            _ => Some(high),
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            WMDATA_ADDR => {
+                self.data[self.port_address] = value;
+                self.port_address = (self.port_address + 1) & PORT_ADDRESS_MASK;
+            }
+            WMADDL_ADDR => self.set_port_address_byte(0, value),
+            WMADDM_ADDR => self.set_port_address_byte(8, value),
+            _ => self.set_port_address_byte(16, value),
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_address() {
+        let mut wram = Wram::new();
+        wram.write(WMADDL_ADDR, 0x56);
+        wram.write(WMADDM_ADDR, 0x34);
+        wram.write(WMADDH_ADDR, 0xFF);
+        assert_eq!(wram.port_address, 0x13456);
+
+        // Only the low bit of WMADDH is used.
+        assert_eq!(wram.peek(WMADDH_ADDR), Some(0x01));
+        assert_eq!(wram.read(WMADDL_ADDR), None);
+    }
+
+    #[test]
+    fn test_port_increment() {
+        let mut wram = Wram::new();
+        wram.write(WMADDL_ADDR, 0xFF);
+        wram.write(WMADDM_ADDR, 0xFF);
+        wram.write(WMADDH_ADDR, 0x01);
+
+        // The address wraps around the end of WRAM.
+        wram.write(WMDATA_ADDR, 0xAA);
+        wram.write(WMDATA_ADDR, 0xBB);
+        assert_eq!(wram.get(0x1FFFF), 0xAA);
+        assert_eq!(wram.get(0x00000), 0xBB);
+        assert_eq!(wram.port_address, 0x00001);
+
+        wram.write(WMADDL_ADDR, 0x00);
+        assert_eq!(wram.peek(WMDATA_ADDR), Some(0xBB));
+        assert_eq!(wram.read(WMDATA_ADDR), Some(0xBB));
+        assert_eq!(wram.read(WMDATA_ADDR), Some(0x00));
+        assert_eq!(wram.port_address, 0x00002);
+    }
+}
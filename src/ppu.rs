@@ -0,0 +1,486 @@
+use std::ops::RangeInclusive;
+
+use crate::emu::scheduler::Component;
+use crate::emu::timeline::{CLOCKS_PER_LINE, LINES_PER_FRAME};
+use crate::emu::EmulationError;
+use crate::memory::{self, io::IoRegisters};
+
+mod cgram;
+mod mode7;
+mod oam;
+pub mod render;
+mod vram;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Every PPU register, INIDISP-STAT78.
+pub const REGISTERS: RangeInclusive<u16> = INIDISP_ADDR..=STAT78_ADDR;
+
+/// INIDISP-SETINI, the registers which can only be written.
+const WRITE_ONLY_REGISTERS: RangeInclusive<u16> = INIDISP_ADDR..=SETINI_ADDR;
+
+const INIDISP_ADDR: u16 = 0x2100;
+const OAMADDL_ADDR: u16 = 0x2102;
+const OAMADDH_ADDR: u16 = 0x2103;
+const OAMDATA_ADDR: u16 = 0x2104;
+const BGMODE_ADDR: u16 = 0x2105;
+const BG1SC_ADDR: u16 = 0x2107;
+const BG12NBA_ADDR: u16 = 0x210B;
+const BG1HOFS_ADDR: u16 = 0x210D;
+const BG4VOFS_ADDR: u16 = 0x2114;
+const VMAIN_ADDR: u16 = 0x2115;
+const VMADDL_ADDR: u16 = 0x2116;
+const VMADDH_ADDR: u16 = 0x2117;
+const VMDATAL_ADDR: u16 = 0x2118;
+const VMDATAH_ADDR: u16 = 0x2119;
+const CGADD_ADDR: u16 = 0x2121;
+const CGDATA_ADDR: u16 = 0x2122;
+const TM_ADDR: u16 = 0x212C;
+const SETINI_ADDR: u16 = 0x2133;
+const SLHV_ADDR: u16 = 0x2137;
+const OAMDATAREAD_ADDR: u16 = 0x2138;
+const VMDATALREAD_ADDR: u16 = 0x2139;
+const VMDATAHREAD_ADDR: u16 = 0x213A;
+const CGDATAREAD_ADDR: u16 = 0x213B;
+const OPHCT_ADDR: u16 = 0x213C;
+const OPVCT_ADDR: u16 = 0x213D;
+const STAT77_ADDR: u16 = 0x213E;
+const STAT78_ADDR: u16 = 0x213F;
+
+/// Number of write only registers.
+const WRITE_ONLY_COUNT: usize = (SETINI_ADDR - INIDISP_ADDR + 1) as usize;
+
+/// Mask for the 10-bit background scroll registers.
+const SCROLL_MASK: u16 = 0x03FF;
+
+/// Master clocks in one PPU dot.
+const MASTER_CLOCKS_PER_DOT: usize = 4;
+
+/// Dots in one scanline, counted by the horizontal counter.
+const DOTS_PER_LINE: usize = CLOCKS_PER_LINE / MASTER_CLOCKS_PER_DOT;
+
+/// First line of vertical blanking, without and with overscan.
+pub const VBLANK_LINE: usize = 225;
+const OVERSCAN_VBLANK_LINE: usize = 240;
+
+/// Bit of INIDISP set to blank the screen.
+const FORCE_BLANK_BIT: u8 = 0x80;
+
+/// Bit of SETINI set to show 239 lines rather than 224.
+const OVERSCAN_BIT: u8 = 0x04;
+
+/// Bit of SETINI set to show Mode 7's playfield as BG2 as well, with a priority bit in place of the top colour bit.
+const EXTBG_BIT: u8 = 0x40;
+
+/// Bit of STAT78 set once the counters have been latched, until STAT78 is read.
+const COUNTER_LATCHED_BIT: u8 = 0x40;
+
+/// Chip versions reported in STAT77 and STAT78.
+const PPU1_VERSION: u8 = 0x01;
+const PPU2_VERSION: u8 = 0x03;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Memories inside the PPU which the debugger can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PpuMemory {
+    Vram,
+    Cgram,
+    Oam,
+}
+
+impl PpuMemory {
+    /// Find the size of the memory in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            PpuMemory::Vram => vram::VRAM_WORDS * 2,
+            PpuMemory::Cgram => cgram::CGRAM_SIZE,
+            PpuMemory::Oam => oam::OAM_SIZE,
+        }
+    }
+}
+
+/// The picture processing unit's memories and registers at $2100-$213F.
+/// The PPU is made of two chips, and each keeps its own open bus, the last value read from one of its registers.
+/// https://snes.nesdev.org/wiki/PPU_registers
+///     vram:               Video RAM, holding tiles and tilemaps.
+///     cgram:              Palette RAM.
+///     oam:                Sprite attributes.
+///     mode7:              Mode 7 matrix, which is shared with the signed multiplier.
+///     registers:          Last value written to each write only register.
+///     bg_hofs:            Horizontal scroll of each background.
+///     bg_vofs:            Vertical scroll of each background.
+///     bgofs_latch:        Previous byte written to any scroll register.
+///     bghofs_latch:       Previous byte written to a horizontal scroll register.
+///     h_counter:          Dot within the scanline.
+///     v_counter:          Scanline within the frame.
+///     latched_h:          Horizontal counter latched by SLHV.
+///     latched_v:          Vertical counter latched by SLHV.
+///     counter_latched:    Whether the counters have been latched since STAT78 was last read.
+///     ophct_high:         Whether the next read of OPHCT returns the high bit.
+///     opvct_high:         Whether the next read of OPVCT returns the high bit.
+///     ppu1_open_bus:      Last value read from PPU1.
+///     ppu2_open_bus:      Last value read from PPU2.
+///     drawing:            Frame being drawn.
+///     frame:              Last frame finished.
+pub struct Ppu {
+    vram: vram::Vram,
+    cgram: cgram::Cgram,
+    oam: oam::Oam,
+    mode7: mode7::Mode7,
+    registers: [u8; WRITE_ONLY_COUNT],
+    bg_hofs: [u16; 4],
+    bg_vofs: [u16; 4],
+    bgofs_latch: u8,
+    bghofs_latch: u8,
+    h_counter: usize,
+    v_counter: usize,
+    latched_h: u16,
+    latched_v: u16,
+    counter_latched: bool,
+    ophct_high: bool,
+    opvct_high: bool,
+    ppu1_open_bus: u8,
+    ppu2_open_bus: u8,
+    drawing: render::Frame,
+    frame: render::Frame,
+}
+
+impl Ppu {
+    /// Return a PPU with its memories and registers 0, at the start of the frame.
+    pub fn new() -> Self {
+        Self {
+            vram: vram::Vram::new(),
+            cgram: cgram::Cgram::new(),
+            oam: oam::Oam::new(),
+            mode7: mode7::Mode7::new(),
+            registers: [0; WRITE_ONLY_COUNT],
+            bg_hofs: [0; 4],
+            bg_vofs: [0; 4],
+            bgofs_latch: 0,
+            bghofs_latch: 0,
+            h_counter: 0,
+            v_counter: 0,
+            latched_h: 0,
+            latched_v: 0,
+            counter_latched: false,
+            ophct_high: false,
+            opvct_high: false,
+            ppu1_open_bus: 0,
+            ppu2_open_bus: 0,
+            drawing: render::Frame::new(),
+            frame: render::Frame::new(),
+        }
+    }
+
+    /// Get the last frame drawn.
+    pub fn frame(&self) -> &render::Frame { &self.frame }
+
+    /// Get a byte from one of the PPU's memories, without it being seen as an access.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory to look in.
+    ///     - `offset`: Byte offset into the memory, wrapping around its size.
+    pub fn get_memory_byte(&self, memory: PpuMemory, offset: usize) -> u8 {
+        match memory {
+            PpuMemory::Vram => self.vram.get(offset),
+            PpuMemory::Cgram => self.cgram.get(offset),
+            PpuMemory::Oam => self.oam.get(offset),
+        }
+    }
+
+    /// Print 128 bytes from one of the PPU's memories.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `memory`: Memory to print from.
+    ///     - `start`:  Byte offset to start from.
+    pub fn print_memory(&self, memory: PpuMemory, start: usize) {
+        print!("\n0x|");
+        for i in 0..16 {
+            print!("{:02X} ", i);
+        }
+        println!("\n==================================================");
+
+        for i in 0..8 {
+            print!("{:04x}|", (start + 16 * i) % memory.size());
+            for j in 0..16 {
+                print!("{:02x} ", self.get_memory_byte(memory, start + (16 * i) + j));
+            }
+            println!();
+        }
+    }
+
+    /// Print the position of the counters.
+    pub fn print_state(&self) {
+        println!("PPU H: {}, V: {}", self.h_counter, self.v_counter);
+    }
+
+    /// Find the first line of vertical blanking, which depends on the overscan setting.
+    pub fn vblank_line(&self) -> usize {
+        match self.register(SETINI_ADDR) & OVERSCAN_BIT {
+            0 => VBLANK_LINE,
+            _ => OVERSCAN_VBLANK_LINE,
+        }
+    }
+
+    /// Check whether the screen is blanked by INIDISP.
+    fn is_force_blank(&self) -> bool { self.register(INIDISP_ADDR) & FORCE_BLANK_BIT != 0 }
+
+    /// Get the last value written to a write only register.
+    fn register(&self, address: u16) -> u8 { self.registers[(address - INIDISP_ADDR) as usize] }
+
+    /// Write one of the background scroll registers. Each is written twice, low byte then high byte, through a latch
+    /// shared by all of them. Horizontal scrolls also take their low 3 bits from a latch of their own.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Address of the register, BG1HOFS-BG4VOFS.
+    ///     - `value`:      Value written.
+    fn write_bg_scroll(&mut self, address: u16, value: u8) {
+        let offset = (address - BG1HOFS_ADDR) as usize;
+        let bg = offset / 2;
+        match offset % 2 {
+            0 => {
+                let hofs = ((value as u16) << 8)
+                    | (self.bgofs_latch & !0x07) as u16
+                    | (self.bghofs_latch & 0x07) as u16;
+                self.bg_hofs[bg] = hofs & SCROLL_MASK;
+                self.bghofs_latch = value;
+            }
+            _ => self.bg_vofs[bg] = u16::from_le_bytes([self.bgofs_latch, value]) & SCROLL_MASK,
+        }
+        self.bgofs_latch = value;
+    }
+
+    /// Latch the counters, as reading SLHV does.
+    fn latch_counters(&mut self) {
+        self.latched_h = self.h_counter as u16;
+        self.latched_v = self.v_counter as u16;
+        self.counter_latched = true;
+    }
+
+    /// Find the byte of a latched counter that the next read returns. The 9-bit counters are read low byte then high
+    /// bit, with the rest of the high byte from PPU2 open bus.
+    fn counter_byte(&self, counter: u16, high: bool) -> u8 {
+        match high {
+            true => ((counter >> 8) as u8 & 0x01) | (self.ppu2_open_bus & 0xFE),
+            false => counter as u8,
+        }
+    }
+}
+
+impl Component for Ppu {
+    /// Run the counters on by one dot, drawing each visible line as it starts.
+    fn tick(&mut self, _memory: &mut memory::Memory) -> Result<usize, EmulationError> {
+        self.h_counter += 1;
+        if self.h_counter == DOTS_PER_LINE {
+            self.h_counter = 0;
+            self.v_counter = (self.v_counter + 1) % LINES_PER_FRAME;
+
+            // Each line is drawn at its start, once HDMA has made its changes for it.
+            if (1..self.vblank_line()).contains(&self.v_counter) {
+                self.render_line(self.v_counter);
+            }
+            else if self.v_counter == self.vblank_line() {
+                self.finish_frame();
+
+                // The OAM address is reloaded at the start of vertical blanking, unless the screen is blanked.
+                if !self.is_force_blank() {
+                    self.oam.reset_address();
+                }
+            }
+        }
+        Ok(MASTER_CLOCKS_PER_DOT)
+    }
+}
+
+impl IoRegisters for Ppu {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        if address == SLHV_ADDR {
+            // SLHV doesn't drive the bus, so the CPU sees its own open bus.
+            self.latch_counters();
+            return None;
+        }
+        if WRITE_ONLY_REGISTERS.contains(&address) {
+            return Some(self.ppu1_open_bus);
+        }
+
+        let value = self.peek(address)?;
+        match address {
+            OAMDATAREAD_ADDR => self.oam.after_read(),
+            VMDATALREAD_ADDR => self.vram.after_read(false),
+            VMDATAHREAD_ADDR => self.vram.after_read(true),
+            CGDATAREAD_ADDR => self.cgram.after_read(),
+            OPHCT_ADDR => self.ophct_high = !self.ophct_high,
+            OPVCT_ADDR => self.opvct_high = !self.opvct_high,
+            STAT78_ADDR => {
+                self.counter_latched = false;
+                self.ophct_high = false;
+                self.opvct_high = false;
+            }
+            _ => {}
+        }
+
+        match address {
+            CGDATAREAD_ADDR..=OPVCT_ADDR | STAT78_ADDR => self.ppu2_open_bus = value,
+            _ => self.ppu1_open_bus = value,
+        }
+        Some(value)
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            _ if WRITE_ONLY_REGISTERS.contains(&address) => Some(self.register(address)),
+            _ if mode7::READ_REGISTERS.contains(&address) => self.mode7.peek(address),
+            OAMDATAREAD_ADDR => Some(self.oam.peek()),
+            VMDATALREAD_ADDR => Some(self.vram.peek(false)),
+            VMDATAHREAD_ADDR => Some(self.vram.peek(true)),
+            CGDATAREAD_ADDR => Some(self.cgram.peek(self.ppu2_open_bus)),
+            OPHCT_ADDR => Some(self.counter_byte(self.latched_h, self.ophct_high)),
+            OPVCT_ADDR => Some(self.counter_byte(self.latched_v, self.opvct_high)),
+            STAT77_ADDR => Some((self.ppu1_open_bus & 0x10) | PPU1_VERSION),
+            STAT78_ADDR => {
+                let latched = match self.counter_latched {
+                    true => COUNTER_LATCHED_BIT,
+                    false => 0,
+                };
+                Some(latched | (self.ppu2_open_bus & 0x20) | PPU2_VERSION)
+            }
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        if !WRITE_ONLY_REGISTERS.contains(&address) {
+            // The status registers are read only.
+            return;
+        }
+        self.registers[(address - INIDISP_ADDR) as usize] = value;
+
+        match address {
+            OAMADDL_ADDR => self.oam.set_address_low(value),
+            OAMADDH_ADDR => self.oam.set_address_high(value),
+            OAMDATA_ADDR => self.oam.write(value),
+            BG1HOFS_ADDR..=BG4VOFS_ADDR => {
+                // BG1's scroll registers are also Mode 7's.
+                if mode7::SCROLL_REGISTERS.contains(&address) {
+                    self.mode7.write(address, value);
+                }
+                self.write_bg_scroll(address, value);
+            }
+            VMAIN_ADDR => self.vram.set_control(value),
+            VMADDL_ADDR => self.vram.set_address_low(value),
+            VMADDH_ADDR => self.vram.set_address_high(value),
+            VMDATAL_ADDR => self.vram.write(false, value),
+            VMDATAH_ADDR => self.vram.write(true, value),
+            CGADD_ADDR => self.cgram.set_address(value),
+            CGDATA_ADDR => self.cgram.write(value),
+            _ if mode7::WRITE_REGISTERS.contains(&address) => self.mode7.write(address, value),
+            _ => {}
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vram_port() {
+        let mut ppu = Ppu::new();
+        ppu.write(VMAIN_ADDR, 0x80);
+        ppu.write(VMADDL_ADDR, 0x00);
+        ppu.write(VMADDH_ADDR, 0x20);
+        for (low, high) in [(0x11, 0x22), (0x33, 0x44)] {
+            ppu.write(VMDATAL_ADDR, low);
+            ppu.write(VMDATAH_ADDR, high);
+        }
+        assert_eq!(ppu.get_memory_byte(PpuMemory::Vram, 0x4000), 0x11);
+        assert_eq!(ppu.get_memory_byte(PpuMemory::Vram, 0x4003), 0x44);
+
+        // Setting the address fills the prefetch latch. Each read then refills it from the address before it is
+        // incremented, so the first word is read twice.
+        ppu.write(VMADDL_ADDR, 0x00);
+        for (low, high) in [(0x11, 0x22), (0x11, 0x22), (0x33, 0x44)] {
+            assert_eq!(ppu.read(VMDATALREAD_ADDR), Some(low));
+            assert_eq!(ppu.read(VMDATAHREAD_ADDR), Some(high));
+        }
+    }
+
+    #[test]
+    fn test_cgram_port() {
+        let mut ppu = Ppu::new();
+        ppu.write(CGADD_ADDR, 0x01);
+        ppu.write(CGDATA_ADDR, 0xFF);
+        ppu.write(CGDATA_ADDR, 0xFF);
+        assert_eq!(ppu.get_memory_byte(PpuMemory::Cgram, 0x02), 0xFF);
+        assert_eq!(ppu.get_memory_byte(PpuMemory::Cgram, 0x03), 0x7F);
+
+        // The top bit of the high byte is PPU2 open bus, which the low byte just read has set.
+        ppu.write(CGADD_ADDR, 0x01);
+        assert_eq!(ppu.read(CGDATAREAD_ADDR), Some(0xFF));
+        assert_eq!(ppu.read(CGDATAREAD_ADDR), Some(0xFF));
+        ppu.write(CGADD_ADDR, 0x00);
+        assert_eq!(ppu.read(CGDATAREAD_ADDR), Some(0x00));
+        assert_eq!(ppu.read(CGDATAREAD_ADDR), Some(0x00));
+    }
+
+    #[test]
+    fn test_oam_port() {
+        let mut ppu = Ppu::new();
+        ppu.write(OAMADDL_ADDR, 0x01);
+        ppu.write(OAMADDH_ADDR, 0x00);
+        ppu.write(OAMDATA_ADDR, 0x12);
+        ppu.write(OAMDATA_ADDR, 0x34);
+        assert_eq!(ppu.get_memory_byte(PpuMemory::Oam, 0x02), 0x12);
+
+        ppu.write(OAMADDL_ADDR, 0x01);
+        assert_eq!(ppu.read(OAMDATAREAD_ADDR), Some(0x12));
+        assert_eq!(ppu.read(OAMDATAREAD_ADDR), Some(0x34));
+
+        // The address is reloaded at the start of vertical blanking.
+        ppu.run_until(0, VBLANK_LINE * CLOCKS_PER_LINE, &mut memory::Memory::new())
+            .unwrap();
+        assert_eq!(ppu.read(OAMDATAREAD_ADDR), Some(0x12));
+    }
+
+    #[test]
+    fn test_counters() {
+        let mut ppu = Ppu::new();
+        let mut memory = memory::Memory::new();
+        let elapsed = ppu
+            .run_until(0, 261 * CLOCKS_PER_LINE + 300 * MASTER_CLOCKS_PER_DOT, &mut memory)
+            .unwrap();
+        assert_eq!((ppu.h_counter, ppu.v_counter), (300, 261));
+        assert_eq!(ppu.peek(STAT78_ADDR), Some(PPU2_VERSION));
+
+        assert_eq!(ppu.read(SLHV_ADDR), None);
+        ppu.run_until(elapsed, elapsed + CLOCKS_PER_LINE, &mut memory).unwrap();
+        assert_eq!(ppu.read(OPHCT_ADDR), Some(0x2C));
+        // The rest of the high byte is PPU2 open bus, the low byte just read.
+        assert_eq!(ppu.read(OPHCT_ADDR), Some(0x2D));
+        assert_eq!(ppu.read(OPVCT_ADDR), Some(0x05));
+        assert_eq!(ppu.read(STAT78_ADDR), Some(COUNTER_LATCHED_BIT | PPU2_VERSION));
+
+        // Reading STAT78 clears the latch flag, and starts the counters again from their low bytes.
+        assert_eq!(ppu.read(STAT78_ADDR), Some(PPU2_VERSION));
+        assert_eq!(ppu.read(OPHCT_ADDR), Some(0x2C));
+    }
+
+    #[test]
+    fn test_open_bus() {
+        let mut ppu = Ppu::new();
+        ppu.write(INIDISP_ADDR, 0x8F);
+        ppu.write(OAMDATA_ADDR, 0xA5);
+        ppu.write(OAMDATA_ADDR, 0x5A);
+        ppu.write(OAMADDL_ADDR, 0x00);
+
+        // Write only registers show the last value read from PPU1, but the debugger sees what was written.
+        assert_eq!(ppu.read(INIDISP_ADDR), Some(0x00));
+        assert_eq!(ppu.read(OAMDATAREAD_ADDR), Some(0xA5));
+        assert_eq!(ppu.read(INIDISP_ADDR), Some(0xA5));
+        assert_eq!(ppu.peek(INIDISP_ADDR), Some(0x8F));
+        assert_eq!(ppu.read(STAT77_ADDR), Some(PPU1_VERSION));
+    }
+}
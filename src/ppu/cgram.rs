@@ -0,0 +1,133 @@
+/**************************************** Constant Values ***************************************************************/
+
+/// Number of colours in CGRAM.
+pub const CGRAM_COLORS: usize = 256;
+
+/// Size of CGRAM in bytes.
+pub const CGRAM_SIZE: usize = CGRAM_COLORS * 2;
+
+/// Bits of a colour which are stored. The top bit of each word doesn't exist.
+const COLOR_MASK: u16 = 0x7FFF;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// The 512 bytes of CGRAM, which hold 256 15-bit BGR colours, and the port at $2121-$2122 and $213B that reaches it.
+/// Colours are written a byte at a time, and the low byte is held in a latch until the high byte completes the word.
+/// https://snes.nesdev.org/wiki/PPU_registers#CGDATA
+///     data:       Contents of CGRAM.
+///     address:    CGADD, the colour the port reads and writes.
+///     latch:      Low byte written, waiting for the high byte.
+///     high:       Whether the next access is to the high byte.
+pub struct Cgram {
+    data: [u16; CGRAM_COLORS],
+    address: u8,
+    latch: u8,
+    high: bool,
+}
+
+impl Cgram {
+    /// Return CGRAM with every colour black.
+    pub fn new() -> Self {
+        Self {
+            data: [0; CGRAM_COLORS],
+            address: 0,
+            latch: 0,
+            high: false,
+        }
+    }
+
+    /// Get a byte of CGRAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `offset`: Byte offset into CGRAM, wrapping within 512 bytes.
+    pub fn get(&self, offset: usize) -> u8 {
+        self.data[(offset >> 1) % CGRAM_COLORS].to_le_bytes()[offset & 1]
+    }
+
+    /// Get a colour, as the PPU reads it to draw.
+    pub fn color(&self, index: u8) -> u16 { self.data[index as usize] }
+
+    /// Set CGADD, which starts the next access at the low byte.
+    pub fn set_address(&mut self, value: u8) {
+        self.address = value;
+        self.high = false;
+    }
+
+    /// Write a byte through CGDATA. The colour is only stored once the high byte is written.
+    pub fn write(&mut self, value: u8) {
+        match self.high {
+            true => {
+                self.data[self.address as usize] = u16::from_le_bytes([self.latch, value]) & COLOR_MASK;
+                self.address = self.address.wrapping_add(1);
+            }
+            false => self.latch = value,
+        }
+        self.high = !self.high;
+    }
+
+    /// Look at the byte the next read through CGDATAREAD returns.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `open_bus`:   PPU2 open bus, which is seen in the top bit of the high byte.
+    pub fn peek(&self, open_bus: u8) -> u8 {
+        let [low, high] = self.data[self.address as usize].to_le_bytes();
+        match self.high {
+            true => high | (open_bus & 0x80),
+            false => low,
+        }
+    }
+
+    /// Move the port on after a read, to the next colour once both bytes have been read.
+    pub fn after_read(&mut self) {
+        if self.high {
+            self.address = self.address.wrapping_add(1);
+        }
+        self.high = !self.high;
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_latch() {
+        let mut cgram = Cgram::new();
+        cgram.set_address(0x10);
+
+        // The low byte is held until the high byte is written.
+        cgram.write(0x34);
+        assert_eq!(cgram.get(0x20), 0x00);
+        cgram.write(0xD2);
+        assert_eq!(cgram.get(0x20), 0x34);
+        assert_eq!(cgram.get(0x21), 0x52);
+
+        // Setting the address starts again from the low byte.
+        cgram.write(0x78);
+        cgram.set_address(0x20);
+        cgram.write(0x9A);
+        cgram.write(0x3C);
+        assert_eq!(cgram.get(0x40), 0x9A);
+        assert_eq!(cgram.get(0x22), 0x00);
+    }
+
+    #[test]
+    fn test_read() {
+        let mut cgram = Cgram::new();
+        cgram.set_address(0xFF);
+        cgram.write(0xCD);
+        cgram.write(0x2B);
+
+        cgram.set_address(0xFF);
+        assert_eq!(cgram.peek(0xFF), 0xCD);
+        cgram.after_read();
+        assert_eq!(cgram.peek(0x80), 0xAB);
+        assert_eq!(cgram.peek(0x00), 0x2B);
+        cgram.after_read();
+
+        // The address wraps around to the first colour.
+        assert_eq!(cgram.address, 0x00);
+    }
+}
@@ -0,0 +1,319 @@
+use std::ops::RangeInclusive;
+
+use crate::memory::io::IoRegisters;
+
+use super::vram::Vram;
+
+/**************************************** Constant Values ***************************************************************/
+
+/// M7SEL-M7Y, the Mode 7 settings and matrix. M7A and M7B are also the operands of the multiplier.
+pub const WRITE_REGISTERS: RangeInclusive<u16> = M7SEL_ADDR..=M7Y_ADDR;
+
+/// M7HOFS-M7VOFS, the Mode 7 scroll, which shares its addresses with BG1HOFS and BG1VOFS.
+pub const SCROLL_REGISTERS: RangeInclusive<u16> = M7HOFS_ADDR..=M7VOFS_ADDR;
+
+/// MPYL-MPYH, the 24-bit product.
+pub const READ_REGISTERS: RangeInclusive<u16> = MPYL_ADDR..=MPYH_ADDR;
+
+const M7HOFS_ADDR: u16 = 0x210D;
+const M7VOFS_ADDR: u16 = 0x210E;
+const M7SEL_ADDR: u16 = 0x211A;
+const M7A_ADDR: u16 = 0x211B;
+const M7D_ADDR: u16 = 0x211E;
+const M7X_ADDR: u16 = 0x211F;
+const M7Y_ADDR: u16 = 0x2120;
+const MPYL_ADDR: u16 = 0x2134;
+const MPYM_ADDR: u16 = 0x2135;
+const MPYH_ADDR: u16 = 0x2136;
+
+/// Bits of M7SEL.
+const H_FLIP_BIT: u8 = 0x01;
+const V_FLIP_BIT: u8 = 0x02;
+const SCREEN_OVER_SHIFT: u8 = 6;
+
+/// Screen over settings, for what is shown outside the 1024x1024 pixel playfield.
+const SCREEN_OVER_TRANSPARENT: u8 = 2;
+const SCREEN_OVER_TILE_0: u8 = 3;
+
+/// Size of the playfield in tiles across and down.
+const PLAYFIELD_TILES: i32 = 128;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Mode 7's registers, which rotate and scale a single 1024x1024 pixel playfield, and the signed 16x8 multiplier which
+/// shares the matrix.
+/// Every register except M7SEL is written twice, low byte then high byte, through a latch holding the previous byte
+/// written to any of them. The multiplier multiplies M7A by the last byte written to M7B, and its product is ready
+/// straight away.
+/// https://snes.nesdev.org/wiki/PPU_registers#M7SEL
+///     m7sel:  Screen over and flip settings.
+///     matrix: M7A-M7D, signed 8.8 fixed point.
+///     center: M7X and M7Y, the 13-bit signed centre of rotation and scaling.
+///     scroll: M7HOFS and M7VOFS, the 13-bit signed scroll.
+///     latch:  Previous byte written.
+#[derive(Debug)]
+pub struct Mode7 {
+    m7sel: u8,
+    matrix: [i16; 4],
+    center: [u16; 2],
+    scroll: [u16; 2],
+    latch: u8,
+}
+
+impl Mode7 {
+    /// Return Mode 7 with every register 0.
+    pub fn new() -> Self {
+        Self {
+            m7sel: 0,
+            matrix: [0; 4],
+            center: [0; 2],
+            scroll: [0; 2],
+            latch: 0,
+        }
+    }
+
+    /// Find the playfield pixel shown at a point on the screen.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `vram`:   VRAM, with the tilemap in the low bytes of its first 16K words and the characters in the high
+    ///                 bytes.
+    ///     - `x`:      Pixel across the screen.
+    ///     - `y`:      Line down the screen.
+    /// # Returns:
+    ///     - The 8-bit colour of the pixel, where 0 is transparent.
+    pub fn pixel(&self, vram: &Vram, x: usize, y: usize) -> u8 {
+        let [a, b, c, d] = self.matrix.map(|value| value as i32);
+        let [center_x, center_y] = self.center.map(sign_extend_13);
+        let [hofs, vofs] = self.scroll.map(sign_extend_13);
+        let x = match self.m7sel & H_FLIP_BIT {
+            0 => x as i32,
+            _ => 255 - x as i32,
+        };
+        let y = match self.m7sel & V_FLIP_BIT {
+            0 => y as i32,
+            _ => 255 - y as i32,
+        };
+
+        // The hardware drops the fractional bits of each term before adding them up.
+        let dx = clip_offset(hofs - center_x);
+        let dy = clip_offset(vofs - center_y);
+        let origin_x = ((a * dx) & !63) + ((b * dy) & !63) + ((b * y) & !63) + (center_x << 8);
+        let origin_y = ((c * dx) & !63) + ((d * dy) & !63) + ((d * y) & !63) + (center_y << 8);
+        let px = (origin_x + a * x) >> 8;
+        let py = (origin_y + c * x) >> 8;
+
+        let out_of_bounds = (px | py) & !1023 != 0;
+        let screen_over = self.m7sel >> SCREEN_OVER_SHIFT;
+        if out_of_bounds && screen_over == SCREEN_OVER_TRANSPARENT {
+            return 0;
+        }
+
+        let tile_address = ((py >> 3) & (PLAYFIELD_TILES - 1)) * PLAYFIELD_TILES + ((px >> 3) & (PLAYFIELD_TILES - 1));
+        let character = match out_of_bounds && screen_over == SCREEN_OVER_TILE_0 {
+            true => 0,
+            false => vram.word(tile_address as u16) as u8,
+        };
+        let pixel_address = (character as u16) << 6 | ((py & 7) << 3 | (px & 7)) as u16;
+        (vram.word(pixel_address) >> 8) as u8
+    }
+
+    /// Find the signed 24-bit product.
+    fn product(&self) -> i32 { self.matrix[0] as i32 * (self.matrix[1] >> 8) as i32 }
+}
+
+impl IoRegisters for Mode7 {
+    fn read(&mut self, address: u16) -> Option<u8> {
+        match address {
+            MPYL_ADDR..=MPYH_ADDR => self.peek(address),
+            // The settings are write only.
+            _ => None,
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let [low, middle, high, _] = self.product().to_le_bytes();
+        match address {
+            M7HOFS_ADDR..=M7VOFS_ADDR => Some((self.scroll[(address - M7HOFS_ADDR) as usize] >> 8) as u8),
+            M7SEL_ADDR => Some(self.m7sel),
+            M7A_ADDR..=M7D_ADDR => Some((self.matrix[(address - M7A_ADDR) as usize] >> 8) as u8),
+            M7X_ADDR..=M7Y_ADDR => Some((self.center[(address - M7X_ADDR) as usize] >> 8) as u8),
+            MPYL_ADDR => Some(low),
+            MPYM_ADDR => Some(middle),
+            MPYH_ADDR => Some(high),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        let word = u16::from_le_bytes([self.latch, value]);
+        match address {
+            M7SEL_ADDR => {
+                // M7SEL is written once, and doesn't go through the latch.
+                self.m7sel = value;
+                return;
+            }
+            M7HOFS_ADDR..=M7VOFS_ADDR => self.scroll[(address - M7HOFS_ADDR) as usize] = word & 0x1FFF,
+            M7A_ADDR..=M7D_ADDR => self.matrix[(address - M7A_ADDR) as usize] = word as i16,
+            M7X_ADDR..=M7Y_ADDR => self.center[(address - M7X_ADDR) as usize] = word & 0x1FFF,
+            // The product is read only.
+            _ => return,
+        }
+        self.latch = value;
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Sign extend a 13-bit value.
+fn sign_extend_13(value: u16) -> i32 { ((value as i32) << 19) >> 19 }
+
+/// Clip the difference between the scroll and the centre to 10 bits, keeping its sign.
+fn clip_offset(offset: i32) -> i32 {
+    match offset & 0x2000 {
+        0 => offset & 0x03FF,
+        _ => offset | !0x03FF,
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M7B_ADDR: u16 = 0x211C;
+    const M7C_ADDR: u16 = 0x211D;
+
+    /// Write a register twice, low byte then high byte.
+    fn write_word(mode7: &mut Mode7, address: u16, value: u16) {
+        mode7.write(address, value as u8);
+        mode7.write(address, (value >> 8) as u8);
+    }
+
+    /// Set up a playfield where every tile is character 1, whose pixels are numbered 0-63 across then down, and the
+    /// tilemap entry at (1, 0) is character 2, whose pixels are all $FF.
+    fn playfield() -> Vram {
+        let mut vram = Vram::new();
+        vram.set_control(0x00);
+        vram.set_address_low(0x00);
+        vram.set_address_high(0x00);
+        for word in 0..0x4000_u16 {
+            let character = match word {
+                1 => 2,
+                _ => 1,
+            };
+            let pixel = match word {
+                0x40..=0x7F => word as u8 & 0x3F,
+                0x80..=0xBF => 0xFF,
+                _ => 0x00,
+            };
+            vram.write(true, pixel);
+            vram.write(false, character);
+        }
+        vram
+    }
+
+    #[test]
+    fn test_multiply() {
+        let test_cases = vec![
+            // m7a, m7b, product
+            (0x0000, 0x00, 0x000000),
+            (0x0100, 0x02, 0x000200),
+            (0x7FFF, 0x7F, 0x3F7F81),
+            (0x8000, 0x7F, 0xC08000),
+            (0xFFFF, 0xFF, 0x000001),
+            (0x8000, 0x80, 0x400000),
+        ];
+
+        for (m7a, m7b, product) in test_cases {
+            println!("Test Case: {:#06X} * {:#04X}", m7a, m7b);
+            let mut mode7 = Mode7::new();
+            write_word(&mut mode7, M7A_ADDR, m7a);
+            // Only the second byte written to M7B is used.
+            mode7.write(M7B_ADDR, 0x55);
+            mode7.write(M7B_ADDR, m7b);
+
+            let result = u32::from_le_bytes([
+                mode7.read(MPYL_ADDR).unwrap(),
+                mode7.read(MPYM_ADDR).unwrap(),
+                mode7.read(MPYH_ADDR).unwrap(),
+                0,
+            ]);
+            assert_eq!(result, product);
+        }
+    }
+
+    #[test]
+    fn test_shared_latch() {
+        let mut mode7 = Mode7::new();
+
+        // Every register is written through the same latch, including the scroll.
+        mode7.write(M7X_ADDR, 0x34);
+        mode7.write(M7HOFS_ADDR, 0x12);
+        mode7.write(M7C_ADDR, 0x56);
+        assert_eq!(mode7.scroll[0], 0x1234);
+        assert_eq!(mode7.matrix[2], 0x5612);
+
+        // M7SEL doesn't touch it, and the centre and scroll are 13 bits.
+        mode7.write(M7SEL_ADDR, 0xC3);
+        mode7.write(M7Y_ADDR, 0xFF);
+        assert_eq!(mode7.center[1], 0x1F56);
+        assert_eq!(sign_extend_13(mode7.center[1]), -0x00AA);
+        assert_eq!(mode7.read(M7SEL_ADDR), None);
+        assert_eq!(mode7.peek(M7SEL_ADDR), Some(0xC3));
+    }
+
+    #[test]
+    fn test_pixel() {
+        let vram = playfield();
+        let mut mode7 = Mode7::new();
+        write_word(&mut mode7, M7A_ADDR, 0x0100);
+        write_word(&mut mode7, M7D_ADDR, 0x0100);
+
+        let test_cases = vec![
+            // M7SEL, scroll, screen position, pixel
+            (0x00, (0, 0), (0, 0), 0x00),
+            (0x00, (0, 0), (3, 2), 0x13),
+            (0x00, (0, 0), (8, 0), 0xFF),
+            (0x00, (5, 1), (0, 0), 0x0D),
+            // Flips mirror the screen position.
+            (0x01, (0, 0), (252, 0), 0x03),
+            (0x02, (0, 0), (0, 254), 0x08),
+            // Outside the playfield, it repeats, is transparent, or is filled with character 0.
+            (0x00, (0x1FF8, 0), (1, 0), 0x01),
+            (0x80, (0x1FF8, 0), (1, 0), 0x00),
+            (0xC0, (0x1FF8, 0), (1, 0), 0x00),
+            (0xC0, (0x1FF8, 0), (8, 0), 0x00),
+            (0xC0, (0x1FF8, 0), (16, 0), 0xFF),
+        ];
+
+        for (m7sel, (hofs, vofs), (x, y), pixel) in test_cases {
+            println!("Test Case: {:#04X} ({}, {}) ({}, {})", m7sel, hofs, vofs, x, y);
+            mode7.write(M7SEL_ADDR, m7sel);
+            write_word(&mut mode7, M7HOFS_ADDR, hofs);
+            write_word(&mut mode7, M7VOFS_ADDR, vofs);
+            assert_eq!(mode7.pixel(&vram, x, y), pixel);
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        let vram = playfield();
+        let mut mode7 = Mode7::new();
+
+        // Doubling the size halves the step through the playfield for each pixel.
+        write_word(&mut mode7, M7A_ADDR, 0x0080);
+        write_word(&mut mode7, M7D_ADDR, 0x0080);
+        assert_eq!(mode7.pixel(&vram, 7, 0), 0x03);
+        assert_eq!(mode7.pixel(&vram, 6, 4), 0x13);
+        assert_eq!(mode7.pixel(&vram, 16, 0), 0xFF);
+    }
+
+    #[test]
+    fn test_write_only() {
+        let mut mode7 = Mode7::new();
+        mode7.write(M7A_ADDR, 0xAA);
+        assert_eq!(mode7.read(M7A_ADDR), None);
+        assert_eq!(mode7.read(MPYL_ADDR), Some(0x00));
+    }
+}
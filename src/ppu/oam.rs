@@ -0,0 +1,160 @@
+/**************************************** Constant Values ***************************************************************/
+
+/// Size of OAM in bytes, a 512 byte table of 4 bytes for each sprite, and a 32 byte table of 2 bits for each sprite.
+pub const OAM_SIZE: usize = 544;
+
+/// Size of the low table.
+const LOW_TABLE_SIZE: usize = 512;
+
+/// Mask for the 10-bit byte address of the port. The high table is mirrored through the top half of it.
+const ADDRESS_MASK: u16 = 0x03FF;
+
+/// Bit of OAMADDH which enables priority rotation.
+const PRIORITY_ROTATION_BIT: u8 = 0x80;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// The 544 bytes of OAM, which hold the sprite attributes, and the port at $2102-$2104 and $2138 that reaches it.
+/// Writes to the low table are made a word at a time, with the even byte held in a latch until the odd byte is written.
+/// https://snes.nesdev.org/wiki/PPU_registers#OAMDATA
+///     data:               Contents of OAM.
+///     reload:             OAMADDL/H, the word address the port starts from.
+///     address:            Byte address the port reads and writes.
+///     priority_rotation:  Whether the sprite at the reload address has the highest priority.
+///     latch:              Even byte written to the low table, waiting for the odd byte.
+pub struct Oam {
+    data: [u8; OAM_SIZE],
+    reload: u16,
+    address: u16,
+    priority_rotation: bool,
+    latch: u8,
+}
+
+impl Oam {
+    /// Return OAM with every byte 0.
+    pub fn new() -> Self {
+        Self {
+            data: [0; OAM_SIZE],
+            reload: 0,
+            address: 0,
+            priority_rotation: false,
+            latch: 0,
+        }
+    }
+
+    /// Get a byte of OAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `offset`: Byte offset into OAM, wrapping within 544 bytes.
+    pub fn get(&self, offset: usize) -> u8 { self.data[offset % OAM_SIZE] }
+
+    /// Set OAMADDL, and move the port to the new address.
+    pub fn set_address_low(&mut self, value: u8) {
+        self.reload = (self.reload & 0x0100) | value as u16;
+        self.reset_address();
+    }
+
+    /// Set OAMADDH, and move the port to the new address.
+    pub fn set_address_high(&mut self, value: u8) {
+        self.reload = ((value as u16 & 0x01) << 8) | (self.reload & 0x00FF);
+        self.priority_rotation = value & PRIORITY_ROTATION_BIT != 0;
+        self.reset_address();
+    }
+
+    /// Move the port back to the address in OAMADDL/H, as happens when it is written, and at the start of vertical
+    /// blanking.
+    pub fn reset_address(&mut self) { self.address = self.reload << 1; }
+
+    /// Write a byte through OAMDATA.
+    pub fn write(&mut self, value: u8) {
+        let offset = self.offset();
+        if offset >= LOW_TABLE_SIZE {
+            self.data[offset] = value;
+        }
+        else if offset & 1 == 0 {
+            self.latch = value;
+        }
+        else {
+            self.data[offset - 1] = self.latch;
+            self.data[offset] = value;
+        }
+        self.address = (self.address + 1) & ADDRESS_MASK;
+    }
+
+    /// Look at the byte the next read through OAMDATAREAD returns.
+    pub fn peek(&self) -> u8 { self.data[self.offset()] }
+
+    /// Move the port on after a read.
+    pub fn after_read(&mut self) { self.address = (self.address + 1) & ADDRESS_MASK; }
+
+    /// Find the byte offset into OAM the port accesses.
+    fn offset(&self) -> usize {
+        match self.address as usize {
+            address if address < LOW_TABLE_SIZE => address,
+            address => LOW_TABLE_SIZE + (address & 0x1F),
+        }
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_table_latch() {
+        let mut oam = Oam::new();
+        oam.set_address_low(0x02);
+        oam.set_address_high(0x80);
+        assert!(oam.priority_rotation);
+
+        // The even byte is only written along with the odd byte.
+        oam.write(0xAA);
+        assert_eq!(oam.get(0x004), 0x00);
+        oam.write(0xBB);
+        assert_eq!((oam.get(0x004), oam.get(0x005)), (0xAA, 0xBB));
+
+        // An odd byte on its own writes whatever the latch holds.
+        oam.write(0xCC);
+        oam.set_address_low(0x03);
+        oam.after_read();
+        oam.write(0xDD);
+        assert_eq!((oam.get(0x006), oam.get(0x007)), (0xCC, 0xDD));
+    }
+
+    #[test]
+    fn test_high_table() {
+        let mut oam = Oam::new();
+        oam.set_address_low(0x00);
+        oam.set_address_high(0x01);
+
+        // The high table is written a byte at a time.
+        oam.write(0x11);
+        assert_eq!(oam.get(0x200), 0x11);
+
+        // And mirrored through the rest of the address space, until the address wraps to the low table.
+        oam.set_address_low(0xF0);
+        oam.set_address_high(0x01);
+        oam.write(0x22);
+        assert_eq!(oam.get(0x200), 0x22);
+        oam.set_address_low(0xFF);
+        oam.set_address_high(0x01);
+        oam.write(0x33);
+        oam.write(0x44);
+        assert_eq!(oam.get(0x21E), 0x33);
+        assert_eq!(oam.address, 0x000);
+    }
+
+    #[test]
+    fn test_read() {
+        let mut oam = Oam::new();
+        oam.write(0x12);
+        oam.write(0x34);
+
+        oam.set_address_low(0x00);
+        assert_eq!(oam.peek(), 0x12);
+        oam.after_read();
+        assert_eq!(oam.peek(), 0x34);
+    }
+}
@@ -0,0 +1,715 @@
+use std::io;
+
+use super::{
+    Ppu, BG12NBA_ADDR, BG1SC_ADDR, BGMODE_ADDR, EXTBG_BIT, FORCE_BLANK_BIT, INIDISP_ADDR, OVERSCAN_BIT,
+    SCROLL_MASK, SETINI_ADDR, TM_ADDR,
+};
+
+/**************************************** Constant Values ***************************************************************/
+
+/// Width of a frame in pixels. Hi-res modes draw twice as many, which are blended in pairs to fit.
+pub const FRAME_WIDTH: usize = 256;
+
+/// Height of a frame in lines, without and with overscan.
+pub const FRAME_HEIGHT: usize = 224;
+pub const OVERSCAN_FRAME_HEIGHT: usize = 239;
+
+/// Bits per pixel of BG1-BG4 in modes 0-6, with 0 where the mode has no such background.
+const MODE_DEPTHS: [[usize; 4]; 7] = [
+    [2, 2, 2, 2],
+    [4, 4, 2, 0],
+    [4, 4, 0, 0],
+    [8, 4, 0, 0],
+    [8, 2, 0, 0],
+    [4, 2, 0, 0],
+    [4, 0, 0, 0],
+];
+
+/// Order backgrounds are drawn in for each mode, from front to back, as the background and its tiles' priority.
+/// Sprites are drawn between these, and aren't rendered yet.
+const MODE_0_ORDER: &[(usize, bool)] = &[
+    (0, true),
+    (1, true),
+    (0, false),
+    (1, false),
+    (2, true),
+    (3, true),
+    (2, false),
+    (3, false),
+];
+const MODE_1_ORDER: &[(usize, bool)] = &[
+    (0, true),
+    (1, true),
+    (0, false),
+    (1, false),
+    (2, true),
+    (2, false),
+];
+const MODE_1_BG3_PRIORITY_ORDER: &[(usize, bool)] = &[
+    (2, true),
+    (0, true),
+    (1, true),
+    (0, false),
+    (1, false),
+    (2, false),
+];
+const MODE_2_ORDER: &[(usize, bool)] = &[(0, true), (1, true), (0, false), (1, false)];
+const MODE_6_ORDER: &[(usize, bool)] = &[(0, true), (0, false)];
+const MODE_7_ORDER: &[(usize, bool)] = &[(0, false)];
+const MODE_7_EXTBG_ORDER: &[(usize, bool)] = &[(1, true), (0, false), (1, false)];
+
+/// Bit of BGMODE which moves BG3's high priority tiles to the front in mode 1.
+const BG3_PRIORITY_BIT: u8 = 0x08;
+
+/// Bits of BGMODE which select the mode.
+const MODE_MASK: u8 = 0x07;
+
+/// Bit of a Mode 7 pixel which is BG2's priority with EXTBG.
+const EXTBG_PRIORITY_BIT: u8 = 0x80;
+
+/// Bits of a tilemap entry.
+const ENTRY_CHARACTER_MASK: u16 = 0x03FF;
+const ENTRY_PRIORITY_BIT: u16 = 0x2000;
+const ENTRY_H_FLIP_BIT: u16 = 0x4000;
+const ENTRY_V_FLIP_BIT: u16 = 0x8000;
+
+/// Bits of an offset-per-tile entry. Each enables the entry for one of BG1 and BG2, and in mode 4, the top bit picks
+/// whether the entry replaces the vertical scroll instead of the horizontal scroll.
+const OPT_BG1_ENABLE_BIT: u16 = 0x2000;
+const OPT_VERTICAL_BIT: u16 = 0x8000;
+const OPT_SCROLL_MASK: u16 = 0x03FF;
+
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// A picture drawn by the PPU, in 15-bit BGR colour.
+///     pixels: Each line of the frame in turn, with room for the overscan lines.
+///     height: Number of lines shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pixels: Vec<u16>,
+    height: usize,
+}
+
+impl Frame {
+    /// Return a black frame, without overscan.
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![0; FRAME_WIDTH * OVERSCAN_FRAME_HEIGHT],
+            height: FRAME_HEIGHT,
+        }
+    }
+
+    /// Get the width of the frame in pixels.
+    pub fn width(&self) -> usize { FRAME_WIDTH }
+
+    /// Get the height of the frame in lines, 224 or 239.
+    pub fn height(&self) -> usize { self.height }
+
+    /// Get the colour of a pixel, with red in the low bits.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `x`:  Pixel across, from the left.
+    ///     - `y`:  Line down, from the top.
+    pub fn pixel(&self, x: usize, y: usize) -> u16 { self.pixels[y * FRAME_WIDTH + x] }
+
+    /// Write the frame as a binary PPM image, so it can be checked without a display.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `writer`: Where to write the image.
+    pub fn write_ppm(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let color = self.pixel(x, y);
+                let rgb = [0, 5, 10].map(|shift| {
+                    let channel = ((color >> shift) & 0x1F) as u8;
+                    (channel << 3) | (channel >> 2)
+                });
+                writer.write_all(&rgb)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get a line of the frame to draw into.
+    fn line_mut(&mut self, y: usize) -> &mut [u16] { &mut self.pixels[y * FRAME_WIDTH..(y + 1) * FRAME_WIDTH] }
+}
+
+/// A pixel drawn by a background.
+///     color:      Colour from CGRAM.
+///     priority:   Priority bit of the tile it came from.
+#[derive(Debug, Clone, Copy)]
+struct BgPixel {
+    color: u16,
+    priority: bool,
+}
+
+impl Ppu {
+    /// Draw a line of the frame from the backgrounds on the main screen.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `line`:   Visible line to draw, from 1.
+    pub(super) fn render_line(&mut self, line: usize) {
+        let mut pixels = [0; FRAME_WIDTH];
+        let inidisp = self.register(INIDISP_ADDR);
+        if inidisp & FORCE_BLANK_BIT == 0 {
+            self.render_backgrounds(line, &mut pixels);
+            let brightness = (inidisp & 0x0F) as u16;
+            for pixel in pixels.iter_mut() {
+                *pixel = map_channels(*pixel, |channel| channel * (brightness + 1) / 16);
+            }
+        }
+        self.drawing.line_mut(line - 1).copy_from_slice(&pixels);
+    }
+
+    /// Finish the frame being drawn, so it can be shown.
+    pub(super) fn finish_frame(&mut self) {
+        self.drawing.height = match self.register(SETINI_ADDR) & OVERSCAN_BIT {
+            0 => FRAME_HEIGHT,
+            _ => OVERSCAN_FRAME_HEIGHT,
+        };
+        self.frame.clone_from(&self.drawing);
+    }
+
+    /// Draw the backgrounds of a line, in front of the backdrop colour.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `line`:   Visible line to draw, from 1.
+    ///     - `pixels`: Line to draw into.
+    fn render_backgrounds(&self, line: usize, pixels: &mut [u16; FRAME_WIDTH]) {
+        let bgmode = self.register(BGMODE_ADDR);
+        let mode = (bgmode & MODE_MASK) as usize;
+        let hires = mode == 5 || mode == 6;
+        let extbg = self.register(SETINI_ADDR) & EXTBG_BIT != 0;
+        let main_screen = self.register(TM_ADDR);
+        let layers: Vec<Option<Vec<Option<BgPixel>>>> = match mode {
+            7 => self.render_mode7_line(line, main_screen, extbg),
+            _ => (0..4)
+                .map(|bg| {
+                    let bpp = MODE_DEPTHS[mode][bg];
+                    (bpp != 0 && main_screen & (1 << bg) != 0).then(|| self.render_bg_line(bg, bpp, line))
+                })
+                .collect(),
+        };
+
+        let order = match mode {
+            0 => MODE_0_ORDER,
+            1 if bgmode & BG3_PRIORITY_BIT != 0 => MODE_1_BG3_PRIORITY_ORDER,
+            1 => MODE_1_ORDER,
+            6 => MODE_6_ORDER,
+            7 if extbg => MODE_7_EXTBG_ORDER,
+            7 => MODE_7_ORDER,
+            _ => MODE_2_ORDER,
+        };
+        let backdrop = self.cgram.color(0);
+        let color_at = |x: usize| {
+            order
+                .iter()
+                .find_map(|(bg, priority)| {
+                    layers[*bg]
+                        .as_ref()
+                        .and_then(|layer| layer[x])
+                        .filter(|pixel| pixel.priority == *priority)
+                })
+                .map_or(backdrop, |pixel| pixel.color)
+        };
+
+        for (x, pixel) in pixels.iter_mut().enumerate() {
+            *pixel = match hires {
+                true => blend(color_at(x * 2), color_at(x * 2 + 1)),
+                false => color_at(x),
+            };
+        }
+    }
+
+    /// Draw one background's pixels across a line.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `bg`:     Background to draw, 0 for BG1.
+    ///     - `bpp`:    Bits per pixel of the background's tiles in this mode.
+    ///     - `line`:   Visible line to draw, from 1.
+    /// # Returns:
+    ///     - Each pixel across the line, twice as many in hi-res modes, or None where the background is transparent.
+    fn render_bg_line(&self, bg: usize, bpp: usize, line: usize) -> Vec<Option<BgPixel>> {
+        let bgmode = self.register(BGMODE_ADDR);
+        let mode = bgmode & MODE_MASK;
+        let hires = mode == 5 || mode == 6;
+        let offset_per_tile = mode == 2 || mode == 4 || mode == 6;
+
+        // Hi-res modes always use tiles 16 pixels wide, made of two 8x8 characters.
+        let large_tiles = bgmode & (0x10 << bg) != 0;
+        let tile_width = if large_tiles || hires { 16 } else { 8 };
+        let tile_height = if large_tiles { 16 } else { 8 };
+
+        let character_base = ((self.register(BG12NBA_ADDR + (bg / 2) as u16) >> ((bg % 2) * 4)) & 0x0F) as u16;
+        let character_base = character_base << 12;
+        let character_words = bpp as u16 * 4;
+        let palette_base = match mode {
+            0 => bg as u16 * 32,
+            _ => 0,
+        };
+
+        let width = if hires { FRAME_WIDTH * 2 } else { FRAME_WIDTH };
+        (0..width)
+            .map(|x| {
+                let screen_x = if hires { x / 2 } else { x };
+                let (hofs, vofs) = match offset_per_tile {
+                    true => self.offset_per_tile(bg, screen_x, mode),
+                    false => (self.bg_hofs[bg] as usize, self.bg_vofs[bg] as usize),
+                };
+                let px = x + if hires { hofs * 2 } else { hofs };
+                let py = (line + vofs) & SCROLL_MASK as usize;
+
+                let entry = self.tilemap_entry(bg, px / tile_width, py / tile_height);
+                let mut fine_x = px % tile_width;
+                let mut fine_y = py % tile_height;
+                if entry & ENTRY_H_FLIP_BIT != 0 {
+                    fine_x = tile_width - 1 - fine_x;
+                }
+                if entry & ENTRY_V_FLIP_BIT != 0 {
+                    fine_y = tile_height - 1 - fine_y;
+                }
+
+                // Larger tiles are made of the characters to the right and below in the character table.
+                let character = (entry + (fine_x / 8) as u16 + (fine_y / 8) as u16 * 16) & ENTRY_CHARACTER_MASK;
+                let row_address = character_base
+                    .wrapping_add(character * character_words)
+                    .wrapping_add((fine_y % 8) as u16);
+                let bit = 7 - (fine_x % 8);
+                let index = (0..bpp / 2).fold(0, |index, plane_pair| {
+                    let [low, high] = self.vram.word(row_address.wrapping_add(plane_pair as u16 * 8)).to_le_bytes();
+                    let bits = ((low >> bit) & 1) | ((high >> bit) & 1) << 1;
+                    index | (bits as u16) << (plane_pair * 2)
+                });
+                if index == 0 {
+                    return None;
+                }
+
+                let palette = (entry >> 10) & 0x07;
+                let color_index = match bpp {
+                    2 => palette_base + palette * 4 + index,
+                    4 => palette * 16 + index,
+                    _ => index,
+                };
+                Some(BgPixel {
+                    color: self.cgram.color(color_index as u8),
+                    priority: entry & ENTRY_PRIORITY_BIT != 0,
+                })
+            })
+            .collect()
+    }
+
+    /// Draw Mode 7's playfield across a line, as BG1, and with EXTBG, as BG2.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `line`:           Visible line to draw, from 1.
+    ///     - `main_screen`:    TM, the backgrounds shown.
+    ///     - `extbg`:          Whether BG2 is shown from the playfield too.
+    /// # Returns:
+    ///     - The pixels of each background across the line, or None for a background which isn't shown.
+    fn render_mode7_line(&self, line: usize, main_screen: u8, extbg: bool) -> Vec<Option<Vec<Option<BgPixel>>>> {
+        let playfield: Vec<u8> = (0..FRAME_WIDTH).map(|x| self.mode7.pixel(&self.vram, x, line)).collect();
+        let bg1 = (main_screen & 0x01 != 0).then(|| {
+            playfield
+                .iter()
+                .map(|pixel| {
+                    (*pixel != 0).then(|| BgPixel {
+                        color: self.cgram.color(*pixel),
+                        priority: false,
+                    })
+                })
+                .collect()
+        });
+
+        // BG2 uses the top bit of each pixel as its priority, and the rest as its colour.
+        let bg2 = (extbg && main_screen & 0x02 != 0).then(|| {
+            playfield
+                .iter()
+                .map(|pixel| {
+                    let color = pixel & !EXTBG_PRIORITY_BIT;
+                    (color != 0).then(|| BgPixel {
+                        color: self.cgram.color(color),
+                        priority: pixel & EXTBG_PRIORITY_BIT != 0,
+                    })
+                })
+                .collect()
+        });
+        vec![bg1, bg2, None, None]
+    }
+
+    /// Find the scroll of BG1 or BG2 at a point across the screen in an offset-per-tile mode. From the second column
+    /// of tiles on, each column's scroll can be replaced by entries in BG3's tilemap, at BG3's scroll.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `bg`:         Background being drawn, 0 for BG1.
+    ///     - `screen_x`:   Pixel across the screen.
+    ///     - `mode`:       BG mode, 2, 4 or 6.
+    /// # Returns:
+    ///     - The horizontal and vertical scroll.
+    fn offset_per_tile(&self, bg: usize, screen_x: usize, mode: u8) -> (usize, usize) {
+        let mut hofs = self.bg_hofs[bg] as usize;
+        let mut vofs = self.bg_vofs[bg] as usize;
+        let column = (screen_x + (hofs & 0x07)) / 8;
+        if column == 0 {
+            return (hofs, vofs);
+        }
+
+        let tx = column - 1 + (self.bg_hofs[2] as usize >> 3);
+        let ty = self.bg_vofs[2] as usize >> 3;
+        let (h_entry, v_entry) = match mode {
+            4 => {
+                let entry = self.tilemap_entry(2, tx, ty);
+                match entry & OPT_VERTICAL_BIT {
+                    0 => (Some(entry), None),
+                    _ => (None, Some(entry)),
+                }
+            }
+            _ => (Some(self.tilemap_entry(2, tx, ty)), Some(self.tilemap_entry(2, tx, ty + 1))),
+        };
+
+        let enable = OPT_BG1_ENABLE_BIT << bg;
+        if let Some(entry) = h_entry.filter(|entry| entry & enable != 0) {
+            hofs = (entry & OPT_SCROLL_MASK & !0x07) as usize | (hofs & 0x07);
+        }
+        if let Some(entry) = v_entry.filter(|entry| entry & enable != 0) {
+            vofs = (entry & OPT_SCROLL_MASK) as usize;
+        }
+        (hofs, vofs)
+    }
+
+    /// Read a background's tilemap. Tilemaps are made of 32x32 screens, laid out across and then down.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `bg`: Background, 0 for BG1.
+    ///     - `tx`: Tile across, wrapping around the tilemap.
+    ///     - `ty`: Tile down, wrapping around the tilemap.
+    fn tilemap_entry(&self, bg: usize, tx: usize, ty: usize) -> u16 {
+        let screen_select = self.register(BG1SC_ADDR + bg as u16);
+        let wide = screen_select & 0x01 != 0;
+        let tall = screen_select & 0x02 != 0;
+        let tx = tx % if wide { 64 } else { 32 };
+        let ty = ty % if tall { 64 } else { 32 };
+        let screen = match (wide, tall) {
+            (true, true) => tx / 32 + (ty / 32) * 2,
+            (true, false) => tx / 32,
+            (false, true) => ty / 32,
+            (false, false) => 0,
+        };
+
+        let base = (screen_select as u16 & 0xFC) << 8;
+        let offset = screen * 0x400 + (ty % 32) * 32 + tx % 32;
+        self.vram.word(base.wrapping_add(offset as u16))
+    }
+}
+
+/**************************************** File Scope Functions **********************************************************/
+
+/// Apply a function to each 5-bit channel of a colour.
+fn map_channels(color: u16, f: impl Fn(u16) -> u16) -> u16 {
+    (0..3).fold(0, |result, channel| result | (f((color >> (channel * 5)) & 0x1F) & 0x1F) << (channel * 5))
+}
+
+/// Average two colours, as two hi-res pixels are shown in the space of one.
+fn blend(left: u16, right: u16) -> u16 {
+    (0..3).fold(0, |result, channel| {
+        let shift = channel * 5;
+        let average = (((left >> shift) & 0x1F) + ((right >> shift) & 0x1F)) / 2;
+        result | average << shift
+    })
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::io::IoRegisters;
+    use crate::ppu::{
+        BG1HOFS_ADDR, CGADD_ADDR, CGDATA_ADDR, VMADDH_ADDR, VMADDL_ADDR, VMAIN_ADDR, VMDATAH_ADDR,
+        VMDATAL_ADDR,
+    };
+
+    /// Write words into VRAM through the port.
+    fn upload(ppu: &mut Ppu, address: u16, words: &[u16]) {
+        ppu.write(VMAIN_ADDR, 0x80);
+        ppu.write(VMADDL_ADDR, address as u8);
+        ppu.write(VMADDH_ADDR, (address >> 8) as u8);
+        for word in words {
+            ppu.write(VMDATAL_ADDR, *word as u8);
+            ppu.write(VMDATAH_ADDR, (*word >> 8) as u8);
+        }
+    }
+
+    /// Write a colour into CGRAM through the port.
+    fn set_color(ppu: &mut Ppu, index: u8, color: u16) {
+        ppu.write(CGADD_ADDR, index);
+        ppu.write(CGDATA_ADDR, color as u8);
+        ppu.write(CGDATA_ADDR, (color >> 8) as u8);
+    }
+
+    /// Write a background's scroll registers, each written twice.
+    fn set_scroll(ppu: &mut Ppu, bg: usize, hofs: u16, vofs: u16) {
+        let address = BG1HOFS_ADDR + bg as u16 * 2;
+        ppu.write(address, hofs as u8);
+        ppu.write(address, (hofs >> 8) as u8);
+        ppu.write(address + 1, vofs as u8);
+        ppu.write(address + 1, (vofs >> 8) as u8);
+    }
+
+    /// Set up a PPU at full brightness in a mode, with BG1 at tilemap $0000 and characters $1000, BG2 at tilemap
+    /// $0400 and characters $2000, and BG3 at tilemap $0800 and characters $3000.
+    fn setup(mode: u8) -> Ppu {
+        let mut ppu = Ppu::new();
+        ppu.write(INIDISP_ADDR, 0x0F);
+        ppu.write(BGMODE_ADDR, mode);
+        ppu.write(BG1SC_ADDR, 0x00);
+        ppu.write(BG1SC_ADDR + 1, 0x04);
+        ppu.write(BG1SC_ADDR + 2, 0x08);
+        ppu.write(BG12NBA_ADDR, 0x21);
+        ppu.write(BG12NBA_ADDR + 1, 0x03);
+        ppu.write(TM_ADDR, 0x07);
+        // Line 1 shows line 0 of the tilemap.
+        for bg in 0..3 {
+            set_scroll(&mut ppu, bg, 0, 0x3FF);
+        }
+        ppu
+    }
+
+    /// Render line 1, and return its pixels.
+    fn render(ppu: &mut Ppu) -> Vec<u16> {
+        ppu.render_line(1);
+        ppu.drawing.line_mut(0).to_vec()
+    }
+
+    #[test]
+    fn test_tile_depths() {
+        let test_cases = vec![
+            // mode, BG, character words, palette bits of the entry, colour index of pixel value 1
+            (0, 0, 8, 0x0400, 0x05),
+            (0, 1, 8, 0x0400, 0x25),
+            (1, 0, 16, 0x0400, 0x11),
+            (1, 2, 8, 0x0800, 0x09),
+            (3, 0, 32, 0x1C00, 0x01),
+        ];
+
+        for (mode, bg, character_words, palette, color_index) in test_cases {
+            println!("Test Case: mode {} BG{}", mode, bg + 1);
+            let mut ppu = setup(mode);
+            ppu.write(TM_ADDR, 1 << bg);
+            set_color(&mut ppu, color_index, 0x7FFF);
+
+            // Character 1 has its left pixel set to 1 in every row, using only the first bit plane.
+            let tilemap = [0x0000, 0x0400, 0x0800][bg];
+            let characters = [0x1000, 0x2000, 0x3000][bg];
+            upload(&mut ppu, tilemap, &[palette | 0x0001]);
+            upload(&mut ppu, characters + character_words, &[0x0080; 8]);
+
+            let pixels = render(&mut ppu);
+            assert_eq!(pixels[0], 0x7FFF);
+            assert_eq!(pixels[1], 0x0000);
+            assert_eq!(pixels[8], 0x0000);
+        }
+    }
+
+    #[test]
+    fn test_scroll_and_flip() {
+        let mut ppu = setup(1);
+        ppu.write(TM_ADDR, 0x01);
+        set_color(&mut ppu, 0x01, 0x001F);
+
+        // Character 1 has one pixel set, at the top left, in tilemap column 1.
+        upload(&mut ppu, 0x1010, &[0x0080]);
+        upload(&mut ppu, 0x0000, &[0x0000, 0x0001]);
+        assert_eq!(render(&mut ppu)[8], 0x001F);
+
+        set_scroll(&mut ppu, 0, 3, 0x3FF);
+        assert_eq!(render(&mut ppu)[5], 0x001F);
+
+        // The tilemap wraps around.
+        set_scroll(&mut ppu, 0, 0x100 + 3, 0x3FF);
+        assert_eq!(render(&mut ppu)[5], 0x001F);
+
+        // Flipped horizontally the pixel is on the right, and flipped vertically it is on the bottom line.
+        upload(&mut ppu, 0x0001, &[0x4001]);
+        assert_eq!(render(&mut ppu)[12], 0x001F);
+        upload(&mut ppu, 0x0001, &[0x8001]);
+        assert_eq!(render(&mut ppu)[5], 0x0000);
+        set_scroll(&mut ppu, 0, 3, 6);
+        assert_eq!(render(&mut ppu)[5], 0x001F);
+    }
+
+    #[test]
+    fn test_large_tiles() {
+        let mut ppu = setup(1);
+        ppu.write(BGMODE_ADDR, 0x11);
+        ppu.write(TM_ADDR, 0x01);
+        set_color(&mut ppu, 0x01, 0x001F);
+        set_color(&mut ppu, 0x02, 0x03E0);
+
+        // A 16x16 tile uses character 1 on its left, and character 2 to the right of it.
+        upload(&mut ppu, 0x0000, &[0x0001]);
+        upload(&mut ppu, 0x1010, &[0x0080]);
+        upload(&mut ppu, 0x1020, &[0x8000]);
+        let pixels = render(&mut ppu);
+        assert_eq!((pixels[0], pixels[8], pixels[16]), (0x001F, 0x03E0, 0x0000));
+
+        // Flipping the tile swaps the characters.
+        upload(&mut ppu, 0x0000, &[0x4001]);
+        let pixels = render(&mut ppu);
+        assert_eq!((pixels[7], pixels[15]), (0x03E0, 0x001F));
+    }
+
+    #[test]
+    fn test_tilemap_size() {
+        let mut ppu = setup(1);
+        ppu.write(TM_ADDR, 0x01);
+        set_color(&mut ppu, 0x01, 0x001F);
+        upload(&mut ppu, 0x1010, &[0x0080; 8]);
+
+        // The second screen of a 64x32 tilemap follows the first.
+        ppu.write(BG1SC_ADDR, 0x01);
+        upload(&mut ppu, 0x0400, &[0x0001]);
+        set_scroll(&mut ppu, 0, 256, 0x3FF);
+        assert_eq!(render(&mut ppu)[0], 0x001F);
+
+        // A 32x32 tilemap repeats the first screen instead.
+        ppu.write(BG1SC_ADDR, 0x00);
+        assert_eq!(render(&mut ppu)[0], 0x0000);
+    }
+
+    #[test]
+    fn test_priority() {
+        let test_cases = vec![
+            // BGMODE, BG1 entry, BG3 entry, colour shown
+            (0x01, 0x0001, 0x0001, 0x001F),
+            (0x01, 0x0001, 0x2001, 0x001F),
+            (0x09, 0x0001, 0x2001, 0x7C00),
+            (0x09, 0x2001, 0x0001, 0x001F),
+            (0x09, 0x0000, 0x0000, 0x1234),
+        ];
+
+        for (bgmode, bg1_entry, bg3_entry, color) in test_cases {
+            println!("Test Case: {:#04X} {:#06X} {:#06X}", bgmode, bg1_entry, bg3_entry);
+            let mut ppu = setup(bgmode);
+            ppu.write(TM_ADDR, 0x05);
+            set_color(&mut ppu, 0x00, 0x1234);
+            set_color(&mut ppu, 0x01, 0x001F);
+            set_color(&mut ppu, 0x09, 0x7C00);
+            upload(&mut ppu, 0x0000, &[bg1_entry]);
+            upload(&mut ppu, 0x0800, &[bg3_entry | 0x0800]);
+            upload(&mut ppu, 0x1010, &[0x0080]);
+            upload(&mut ppu, 0x3008, &[0x0080]);
+            assert_eq!(render(&mut ppu)[0], color);
+        }
+    }
+
+    #[test]
+    fn test_offset_per_tile() {
+        let mut ppu = setup(2);
+        ppu.write(TM_ADDR, 0x01);
+        set_color(&mut ppu, 0x01, 0x001F);
+        upload(&mut ppu, 0x1010, &[0x0080]);
+        upload(&mut ppu, 0x0000, &[0x0001]);
+
+        // The second column of tiles is scrolled to show tile 0, and the first column never is.
+        set_scroll(&mut ppu, 2, 0, 0);
+        upload(&mut ppu, 0x0800, &[0x2000 | 0x3F8]);
+        upload(&mut ppu, 0x0820, &[0x2000 | 0x3FF]);
+        let pixels = render(&mut ppu);
+        assert_eq!((pixels[0], pixels[8]), (0x001F, 0x001F));
+
+        // BG2's enable bit leaves BG1 alone.
+        upload(&mut ppu, 0x0800, &[0x4000 | 0x3F8]);
+        assert_eq!(render(&mut ppu)[8], 0x0000);
+
+        // In mode 4, one entry replaces either scroll. BG1 has 8bpp characters.
+        ppu.write(BGMODE_ADDR, 0x04);
+        upload(&mut ppu, 0x1020, &[0x0080]);
+        upload(&mut ppu, 0x0800, &[0x2000 | 0x3F8]);
+        assert_eq!(render(&mut ppu)[8], 0x001F);
+        upload(&mut ppu, 0x0800, &[0xA000 | 0x3FF]);
+        assert_eq!(render(&mut ppu)[8], 0x0000);
+    }
+
+    #[test]
+    fn test_hires() {
+        let mut ppu = setup(5);
+        ppu.write(TM_ADDR, 0x01);
+        set_color(&mut ppu, 0x01, 0x001F);
+
+        // Each pixel shown blends two hi-res pixels, so one lit pixel is half as bright.
+        upload(&mut ppu, 0x0000, &[0x0001]);
+        upload(&mut ppu, 0x1010, &[0x0080]);
+        upload(&mut ppu, 0x1020, &[0x0080]);
+        let pixels = render(&mut ppu);
+        assert_eq!((pixels[0], pixels[1], pixels[4]), (0x000F, 0x0000, 0x000F));
+    }
+
+    #[test]
+    fn test_mode7() {
+        let mut ppu = setup(7);
+        ppu.write(TM_ADDR, 0x03);
+        set_color(&mut ppu, 0x00, 0x1234);
+        set_color(&mut ppu, 0x01, 0x001F);
+        set_color(&mut ppu, 0x02, 0x03E0);
+        set_color(&mut ppu, 0x82, 0x7C00);
+
+        // An identity matrix in M7A and M7D, with every tile character 1, whose pixels are 1, then $82, then $02
+        // across.
+        for address in [0x211B, 0x211E] {
+            ppu.write(address, 0x00);
+            ppu.write(address, 0x01);
+        }
+        ppu.write(VMAIN_ADDR, 0x00);
+        ppu.write(VMADDL_ADDR, 0x00);
+        ppu.write(VMADDH_ADDR, 0x00);
+        for _ in 0..0x4000 {
+            ppu.write(VMDATAL_ADDR, 0x01);
+        }
+        upload(&mut ppu, 0x0040, &[0x0100, 0x8200, 0x0200, 0x0000]);
+        upload(&mut ppu, 0x0048, &[0x0100, 0x8200, 0x0200, 0x0000]);
+
+        // BG2 isn't shown without EXTBG.
+        let pixels = render(&mut ppu);
+        assert_eq!(&pixels[..4], &[0x001F, 0x7C00, 0x03E0, 0x1234]);
+
+        // With EXTBG, BG2's high priority pixels are in front of BG1, and its low priority pixels behind.
+        ppu.write(SETINI_ADDR, EXTBG_BIT);
+        let pixels = render(&mut ppu);
+        assert_eq!(&pixels[..4], &[0x001F, 0x03E0, 0x03E0, 0x1234]);
+        ppu.write(TM_ADDR, 0x02);
+        let pixels = render(&mut ppu);
+        assert_eq!(&pixels[..4], &[0x001F, 0x03E0, 0x03E0, 0x1234]);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut ppu = setup(1);
+        ppu.write(TM_ADDR, 0x00);
+        set_color(&mut ppu, 0x00, 0x7C1F);
+        ppu.render_line(1);
+        ppu.finish_frame();
+
+        let mut image = vec![];
+        ppu.frame().write_ppm(&mut image).unwrap();
+        let header = b"P6\n256 224\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 256 * 224 * 3);
+        assert_eq!(&image[header.len()..header.len() + 3], &[0xFF, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn test_brightness() {
+        let mut ppu = setup(1);
+        ppu.write(TM_ADDR, 0x00);
+        set_color(&mut ppu, 0x00, 0x7FFF);
+        assert_eq!(render(&mut ppu)[0], 0x7FFF);
+
+        ppu.write(INIDISP_ADDR, 0x07);
+        assert_eq!(render(&mut ppu)[0], 0x3DEF);
+
+        ppu.write(INIDISP_ADDR, 0x8F);
+        assert_eq!(render(&mut ppu)[0], 0x0000);
+    }
+}
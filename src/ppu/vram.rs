@@ -0,0 +1,223 @@
+/**************************************** Constant Values ***************************************************************/
+
+/// Size of VRAM in 16-bit words, 64KiB.
+pub const VRAM_WORDS: usize = 0x8000;
+
+/// Bit of VMAIN set to increment the address after accessing the high byte, rather than the low byte.
+const INCREMENT_ON_HIGH_BIT: u8 = 0x80;
+
+/// Bits of VMAIN which select the address remapping.
+const REMAP_MASK: u8 = 0x0C;
+
+/// Bits of VMAIN which select the address increment.
+const INCREMENT_MASK: u8 = 0x03;
+
+/**************************************** Struct and Type definitions ***************************************************/
+
+/// Wrapper type for a u16 array which represents VRAM.
+type VramData = Box<[u16; VRAM_WORDS]>;
+
+/// The 64KiB of VRAM, and the port at $2115-$2119 and $2139-$213A that reaches it.
+/// VRAM is addressed by 16-bit word, and the port reads through a prefetch latch which is filled from the address
+/// whenever it is set, or whenever a read increments it.
+/// https://snes.nesdev.org/wiki/PPU_registers#VMAIN
+///     data:       Contents of VRAM.
+///     control:    VMAIN, the address increment and remapping.
+///     address:    VMADDL/H, the word address the port reads and writes, before remapping.
+///     prefetch:   Word the next read returns.
+pub struct Vram {
+    data: VramData,
+    control: u8,
+    address: u16,
+    prefetch: u16,
+}
+
+impl Vram {
+    /// Return VRAM with every byte 0.
+    pub fn new() -> Self {
+        Self {
+            // https://github.com/rust-lang/rust/issues/53827
+            data: vec![0; VRAM_WORDS].into_boxed_slice().try_into().unwrap(),
+            control: 0,
+            address: 0,
+            prefetch: 0,
+        }
+    }
+
+    /// Get a byte of VRAM.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `offset`: Byte offset into VRAM, wrapping within 64KiB.
+    pub fn get(&self, offset: usize) -> u8 {
+        self.data[(offset >> 1) % VRAM_WORDS].to_le_bytes()[offset & 1]
+    }
+
+    /// Get a word of VRAM, as the PPU reads it to draw.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `address`:    Word address, wrapping within VRAM.
+    pub fn word(&self, address: u16) -> u16 { self.data[address as usize % VRAM_WORDS] }
+
+    /// Set VMAIN.
+    pub fn set_control(&mut self, value: u8) { self.control = value; }
+
+    /// Set the low byte of the address, and fill the prefetch latch from it.
+    pub fn set_address_low(&mut self, value: u8) {
+        self.address = (self.address & 0xFF00) | value as u16;
+        self.prefetch = self.data[self.remapped_address()];
+    }
+
+    /// Set the high byte of the address, and fill the prefetch latch from it.
+    pub fn set_address_high(&mut self, value: u8) {
+        self.address = (self.address & 0x00FF) | (value as u16) << 8;
+        self.prefetch = self.data[self.remapped_address()];
+    }
+
+    /// Write a byte through the port.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `high`:   True to write the high byte of the word, VMDATAH, false for VMDATAL.
+    ///     - `value`:  Value written.
+    pub fn write(&mut self, high: bool, value: u8) {
+        let address = self.remapped_address();
+        let [low_byte, high_byte] = self.data[address].to_le_bytes();
+        self.data[address] = match high {
+            true => u16::from_le_bytes([low_byte, value]),
+            false => u16::from_le_bytes([value, high_byte]),
+        };
+
+        if high == self.increments_on_high() {
+            self.address = self.address.wrapping_add(self.increment());
+        }
+    }
+
+    /// Look at the byte the next read through the port returns.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `high`:   True for the high byte of the prefetch latch, VMDATAHREAD, false for VMDATALREAD.
+    pub fn peek(&self, high: bool) -> u8 { self.prefetch.to_le_bytes()[high as usize] }
+
+    /// Move the port on after a read. If the read was of the byte which increments the address, the prefetch latch is
+    /// refilled from the address before it is incremented.
+    /// # Parameters:
+    ///     - `self`
+    ///     - `high`:   True if the high byte was read, false for the low byte.
+    pub fn after_read(&mut self, high: bool) {
+        if high == self.increments_on_high() {
+            self.prefetch = self.data[self.remapped_address()];
+            self.address = self.address.wrapping_add(self.increment());
+        }
+    }
+
+    /// Check whether the address increments after the high byte is accessed, rather than the low byte.
+    fn increments_on_high(&self) -> bool { self.control & INCREMENT_ON_HIGH_BIT != 0 }
+
+    /// Find the number of words the address increments by.
+    fn increment(&self) -> u16 {
+        match self.control & INCREMENT_MASK {
+            0 => 1,
+            1 => 32,
+            _ => 128,
+        }
+    }
+
+    /// Find the word address the port accesses. Remapping rotates the low bits of the address left by 3, which lays
+    /// out 2bpp, 4bpp or 8bpp tiles a row at a time.
+    /// # Returns:
+    ///     - Word offset into VRAM.
+    fn remapped_address(&self) -> usize {
+        let address = self.address;
+        let remapped = match (self.control & REMAP_MASK) >> 2 {
+            1 => (address & 0xFF00) | (address & 0x001F) << 3 | (address >> 5) & 0x0007,
+            2 => (address & 0xFE00) | (address & 0x003F) << 3 | (address >> 6) & 0x0007,
+            3 => (address & 0xFC00) | (address & 0x007F) << 3 | (address >> 7) & 0x0007,
+            _ => address,
+        };
+        remapped as usize % VRAM_WORDS
+    }
+}
+
+/**************************************** Tests *************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_increment() {
+        let test_cases = vec![
+            // VMAIN, words apart, bytes written for each increment
+            (0x00, 1, false),
+            (0x80, 1, true),
+            (0x81, 32, true),
+            (0x82, 128, true),
+            (0x83, 128, true),
+        ];
+
+        for (control, step, on_high) in test_cases {
+            println!("Test Case: {:#04X}", control);
+            let mut vram = Vram::new();
+            vram.set_control(control);
+            vram.set_address_low(0x00);
+            vram.set_address_high(0x10);
+
+            for value in 0..4 {
+                // Writing only the byte which doesn't increment leaves the address alone.
+                vram.write(!on_high, 0xEE);
+                vram.write(on_high, value);
+            }
+            for word in 0..4 {
+                let offset = (0x1000 + word * step) * 2;
+                assert_eq!(vram.get(offset + on_high as usize), word as u8);
+                assert_eq!(vram.get(offset + !on_high as usize), 0xEE);
+            }
+        }
+    }
+
+    #[test]
+    fn test_remap() {
+        let test_cases = vec![
+            // VMAIN, address, remapped address
+            (0x00, 0x1234, 0x1234),
+            (0x04, 0x00FF, 0x00FF),
+            (0x04, 0x0021, 0x0009),
+            (0x08, 0x0041, 0x0009),
+            (0x0C, 0x0081, 0x0009),
+            (0x0C, 0x7FFF, 0x7FFF),
+        ];
+
+        for (control, address, remapped) in test_cases {
+            println!("Test Case: {:#04X} {:#06X}", control, address);
+            let mut vram = Vram::new();
+            vram.set_control(control);
+            vram.set_address_low(address as u8);
+            vram.set_address_high((address >> 8) as u8);
+            assert_eq!(vram.remapped_address(), remapped);
+        }
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let mut vram = Vram::new();
+        vram.set_control(0x80);
+        for word in [0x1111_u16, 0x2222, 0x3333] {
+            vram.write(false, word as u8);
+            vram.write(true, (word >> 8) as u8);
+        }
+
+        // Setting the address fills the prefetch latch.
+        vram.set_address_low(0x00);
+        vram.set_address_high(0x00);
+        assert_eq!((vram.peek(false), vram.peek(true)), (0x11, 0x11));
+
+        // Reading the byte which increments refills the latch from the address before it is incremented, so the
+        // word at the new address is only seen one read later.
+        vram.after_read(false);
+        assert_eq!(vram.address, 0x0000);
+        vram.after_read(true);
+        assert_eq!(vram.address, 0x0001);
+        assert_eq!(vram.peek(true), 0x11);
+        vram.after_read(true);
+        assert_eq!(vram.peek(true), 0x22);
+    }
+}
@@ -68,7 +68,7 @@ const EV_EMU_ABORT_LEN: usize = 2;
 const EV_EMU_NMI_LEN: usize = 2;
 const EV_EMU_RESET_LEN: usize = 2;
 const EV_EMU_IRQ_BRK_LEN: usize = 2;
-const EV_LEN_BYTES: usize = 32;
+pub const EV_LEN_BYTES: usize = 32;
 
 // NATIVE UNUSED 1
 const EV_NATIVE_COP_INDEX: usize = _EV_NATIVE_UNUSED_1_LEN;
@@ -76,17 +76,17 @@ const EV_NATIVE_BRK_INDEX: usize = EV_NATIVE_COP_INDEX + EV_NATIVE_COP_LEN;
 const EV_NATIVE_ABORT_INDEX: usize = EV_NATIVE_BRK_INDEX + EV_NATIVE_BRK_LEN;
 const EV_NATIVE_NMI_INDEX: usize = EV_NATIVE_ABORT_INDEX + EV_NATIVE_ABORT_LEN;
 // NATIVE UNUSED 2
-const EV_NATIVE_IRQ_INDEX: usize = EV_NATIVE_NMI_INDEX + EV_NATIVE_UNUSED_2_LEN;
+const EV_NATIVE_IRQ_INDEX: usize = EV_NATIVE_NMI_INDEX + EV_NATIVE_NMI_LEN + EV_NATIVE_UNUSED_2_LEN;
 // EMU UNUSED 1
-const EV_EMU_COP_INDEX: usize = EV_NATIVE_IRQ_INDEX + EV_EMU_UNUSED_2_LEN;
+const EV_EMU_COP_INDEX: usize = EV_NATIVE_IRQ_INDEX + EV_NATIVE_IRQ_LEN + EV_EMU_UNUSED_1_LEN;
 // EMU UNUSED 2
-const EV_EMU_ABORT_INDEX: usize = EV_EMU_COP_INDEX + EV_EMU_UNUSED_2_LEN;
+const EV_EMU_ABORT_INDEX: usize = EV_EMU_COP_INDEX + EV_EMU_COP_LEN + EV_EMU_UNUSED_2_LEN;
 const EV_EMU_NMI_INDEX: usize = EV_EMU_ABORT_INDEX + EV_EMU_ABORT_LEN;
 const EV_EMU_RESET_INDEX: usize = EV_EMU_NMI_INDEX + EV_EMU_NMI_LEN;
 const EV_EMU_IRQ_BRK_INDEX: usize = EV_EMU_RESET_INDEX + EV_EMU_RESET_LEN;
 
 /// LoRom specific values
-const LO_ROM_BANK_ADDR: u8 = 0x80; // LoRom starts at bank $808000 and is mirrored to $008000.
+pub const LO_ROM_BANK_ADDR: u8 = 0x80; // LoRom starts at bank $808000 and is mirrored to $008000.
 const LO_ROM_BANK_SIZE_BYTES: usize = 32 * 1024; // LoRom, ExLoRom Bank size is 32 KiB
 const LO_ROM_EXC_VECTOR_ADDR: usize = LO_ROM_BANK_SIZE_BYTES - EV_LEN_BYTES;
 const LO_ROM_HEADER_ADDR: usize = LO_ROM_EXC_VECTOR_ADDR - HDR_LEN_BYTES;
@@ -121,7 +121,7 @@ pub const TOTAL_HDR_BYTES: usize = OPT_HEADER_LEN_BYTES + HDR_LEN_BYTES + EV_LEN
 /**************************************** Struct and Type definitions ***************************************************/
 type Header = [u8; HDR_LEN_BYTES];
 type OptionalHeader = [u8; OPT_HEADER_LEN_BYTES];
-type ExceptionVectorTable = [u8; EV_LEN_BYTES];
+pub type ExceptionVectorTable = [u8; EV_LEN_BYTES];
 
 /// Memory map and size of the ROM.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -304,6 +304,17 @@ impl From<u8> for RomRegion {
     }
 }
 
+/// Exceptions which the CPU can take, each with an entry in the exception vector table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExceptionVector {
+    Cop,
+    Brk,
+    Abort,
+    Nmi,
+    Reset,
+    Irq,
+}
+
 /// Header data parsed out into enumerated types.
 pub struct RomModeMapping {
     pub mem_map: RomSize,
@@ -335,6 +346,7 @@ pub struct RomData {
     pub opt_header: OptionalHeader,
     pub exception_vectors: ExceptionVectorTable,
     pub opt_is_present: bool,
+    pub is_headerless: bool,
     pub mode: RomModeMapping,
 }
 
@@ -346,9 +358,17 @@ impl RomData {
             opt_header: [0; OPT_HEADER_LEN_BYTES],
             exception_vectors: [0; EV_LEN_BYTES],
             opt_is_present: false,
+            is_headerless: false,
             mode: RomModeMapping::new(),
         }
     }
+
+    /// Get the emulation mode RESET vector, which the CPU starts executing from after power on or reset.
+    /// # Returns:
+    ///     - The address in bank $00 to start executing from.
+    pub fn reset_vector(&self) -> u16 {
+        exception_vector(&self.exception_vectors, ExceptionVector::Reset, true)
+    }
 }
 
 /// Error which is returned if a ROM fails to be parsed for any reason.
@@ -399,12 +419,11 @@ pub fn load_rom(
     let mut data = RomData::new();
 
     if bypass_tests {
-        for offset in 0..rom.len() {
-            memory.put_byte(
-                memory::compose_address(LO_ROM_BANK_ADDR, ROM_BASE_ADDR) + offset,
-                rom[offset],
-            );
-        }
+        // Pad test ROMs out to a whole bank, so that running off the end of them does not wrap back to the start.
+        data.is_headerless = true;
+        let mut rom = rom;
+        rom.resize(rom.len().next_power_of_two().max(LO_ROM_BANK_SIZE_BYTES), 0);
+        memory.load_rom(rom, RomSize::LoRom, 0);
     }
     else {
         // Grab the header from the ROM to determine what size it is.
@@ -415,13 +434,41 @@ pub fn load_rom(
         // Decompose the header into more easily usable data.
         populate_rom_mapping(&mut data)?;
 
-        // Write ROM into memory, and then write the mirror.
-        write_rom_to_memory(&rom, data.mode.mem_map, memory)?;
-        write_rom_mirror(&rom, data.mode.mem_map, memory)?;
+        // Connect the ROM and its SRAM to the bus.
+        let sram_size = data.mode.sram_size as usize * 1024;
+        memory.load_rom(rom, data.mode.mem_map, sram_size);
     }
     Ok(data)
 }
 
+/// Look up the address an exception vectors to in bank $00.
+/// There is no native mode RESET vector, since a reset always enters emulation mode, and BRK shares the IRQ vector in
+/// emulation mode.
+/// # Parameters:
+///     - `table`:      Exception vector table from the ROM.
+///     - `vector`:     Exception to look up.
+///     - `emulation`:  Whether the CPU is in emulation mode.
+/// # Returns:
+///     - The address in bank $00 to start executing from.
+pub fn exception_vector(
+    table: &ExceptionVectorTable, vector: ExceptionVector, emulation: bool,
+) -> u16 {
+    let index = match (vector, emulation) {
+        (ExceptionVector::Cop, false) => EV_NATIVE_COP_INDEX,
+        (ExceptionVector::Brk, false) => EV_NATIVE_BRK_INDEX,
+        (ExceptionVector::Abort, false) => EV_NATIVE_ABORT_INDEX,
+        (ExceptionVector::Nmi, false) => EV_NATIVE_NMI_INDEX,
+        (ExceptionVector::Irq, false) => EV_NATIVE_IRQ_INDEX,
+        (ExceptionVector::Cop, true) => EV_EMU_COP_INDEX,
+        (ExceptionVector::Abort, true) => EV_EMU_ABORT_INDEX,
+        (ExceptionVector::Nmi, true) => EV_EMU_NMI_INDEX,
+        (ExceptionVector::Reset, _) => EV_EMU_RESET_INDEX,
+        (ExceptionVector::Brk | ExceptionVector::Irq, true) => EV_EMU_IRQ_BRK_INDEX,
+    };
+
+    u16::from_le_bytes([table[index], table[index + 1]])
+}
+
 /// Check file, and attempt to read it into a buffer.
 /// # Parameters:
 ///     - `path`:       Path to file to open.
@@ -465,156 +512,6 @@ fn read_rom_to_buf(path: PathBuf) -> Result<Vec<u8>, RomReadError> {
     retval
 }
 
-/// Actually place the rom into memory.
-/// https://snes.nesdev.org/wiki/Memory_map
-///
-/// # Parameters:
-///     - `rom`:        Data from the ROM to use.
-///     - `mem_map`:    Size of the rom, used to locate where to place the ROM.
-///     - `mem_ptr`:    Mutable pointer to memory to populate.
-/// # Returns:
-///     - `Ok()`:           If file wrote successfully,
-///     - `RomReadError`:   If an error ocurred in the process.
-fn write_rom_to_memory(
-    rom: &Vec<u8>, mem_map: RomSize, mem_ptr: &mut memory::Memory,
-) -> Result<(), RomReadError> {
-    let mut num_banks: usize;
-    let bank_size: BankSize;
-    let base_addr: usize;
-    match mem_map {
-        RomSize::ExHiRom => {
-            // Populate 0xC00000 - 0xFFFFFF, then wrap around and populate 0x3E8000 - 0x7DFFFF
-            // TODO: https://github.com/HunterKing/RuSuper/issues/28
-            num_banks = 0;
-            bank_size = BankSize::Hi;
-            base_addr = 0;
-        }
-        RomSize::HiRom => {
-            // Populate 0xC00000 - 0xFFFFFF, then mirror half to 0x008000 - 3F8FFFF, and half to 0x808000 - 0xBF8000.
-            num_banks = rom.capacity() / HI_ROM_BANK_SIZE_BYTES;
-            bank_size = BankSize::Hi;
-            base_addr = compose_address(HI_ROM_BANK_ADDR as u8, 0);
-        }
-        RomSize::LoRom => {
-            // Populate 0x808000 - 0xFF8000, then mirror to 0x008000 - 0x7DFFFF
-            num_banks = rom.capacity() / LO_ROM_BANK_SIZE_BYTES;
-            bank_size = BankSize::Lo;
-            base_addr = compose_address(LO_ROM_BANK_ADDR as u8, ROM_BASE_ADDR);
-        }
-    }
-
-    let mut write_result: Result<(), RomReadError> = Ok(());
-    for bank in 0..num_banks {
-        let offset: usize = bank * bank_size as usize;
-        match mem_ptr.put_bank(
-            bank_size,
-            base_addr + offset,
-            &rom[offset..offset + bank_size as usize - 1],
-        ) {
-            Ok(_t) => write_result = Ok(()),
-            Err(e) => write_result = Err(RomReadError::from(e.to_string())),
-        }
-    }
-    return write_result;
-}
-
-/// Write rom mirror to correct location.
-/// https://snes.nesdev.org/wiki/Memory_map
-///
-/// # Parameters:
-///     - `rom`:        Rom to read data from.
-///     - `mem_map`:    Type of rom to write mirror for.
-///     - `mem_ptr`:    Mutable pointer to memory to populate.
-/// # Returns:
-///     - `Ok()`:           If written successfully,
-///     - `RomReadError`:   If an error was encountered in the process.
-fn write_rom_mirror(
-    rom: &Vec<u8>, mem_map: RomSize, mem_ptr: &mut memory::Memory,
-) -> Result<(), RomReadError> {
-    let bank_clusters: Vec<usize>;
-    let base_addrs: Vec<usize>;
-    match mem_map {
-        //TODO: https://github.com/HunterKing/RuSuper/issues/28
-        RomSize::ExHiRom => return Err(RomReadError::from("ExHiROM Mirror is unimplemented")),
-        RomSize::HiRom => {
-            // HiRom gets split across 2 areas of half-size banks.
-            bank_clusters = vec![
-                rom.capacity() / HI_ROM_BANK_SIZE_BYTES,
-                rom.capacity() / HI_ROM_BANK_SIZE_BYTES,
-            ];
-            base_addrs = vec![
-                memory::MEMORY_START,
-                memory::compose_address(LO_ROM_BANK_ADDR, ROM_BASE_ADDR),
-            ]
-        }
-        RomSize::LoRom => {
-            // LoRom gets written contiguously across 0x008000 - 0x7DFFFF.
-            // We'll let this write through 7E-7F because those will be reset on RAM init anyways.
-            bank_clusters = vec![rom.capacity() / LO_ROM_BANK_SIZE_BYTES];
-            base_addrs = vec![memory::MEMORY_START];
-        }
-    }
-
-    let mut write_result: Result<(), RomReadError> = Ok(());
-
-    // Between each bank the location in memory may move, but the rom remains contiguous.
-    let mut rom_cluster_offset: usize = 0;
-
-    // Iterate through each cluster, and then iterate through the banks in those clusters.
-    for cluster in 0..bank_clusters.len() {
-        for bank in 0..bank_clusters[cluster] {
-            let mem_offset: usize = bank * LO_ROM_BANK_SIZE_BYTES as usize;
-            let rom_offset: usize = (bank * LO_ROM_BANK_SIZE_BYTES as usize)
-                + (rom_cluster_offset * LO_ROM_BANK_SIZE_BYTES as usize);
-            match mem_ptr.put_bank(
-                BankSize::Lo,
-                base_addrs[cluster] + mem_offset,
-                &rom[rom_offset..rom_offset + LO_ROM_BANK_SIZE_BYTES - 1],
-            ) {
-                Ok(_t) => write_result = Ok(()),
-                Err(e) => write_result = Err(RomReadError::from(e.to_string())),
-            }
-        }
-        rom_cluster_offset += bank_clusters[cluster];
-    }
-
-    return write_result;
-}
-
-/// Write an exhirom to memory.
-/// https://snes.nesdev.org/wiki/Memory_map#ExHiROM
-/// TODO: https://github.com/HunterKing/RuSuper/issues/28
-///
-/// # Parameters:
-///     - `rom`:        Rom to read data from.
-///     - `mem_ptr`:    Memory to modify.
-/// # Returns:
-///     - `Ok()`:           If written Ok.
-///     - `RomReadError`:   If process failed.
-fn _write_ex_hi_rom(rom: &Vec<u8>, mem_ptr: &mut memory::Memory) -> Result<(), RomReadError> {
-    return Err(RomReadError::from(String::from(
-        "Unimplemented for ExHiRom",
-    )));
-}
-
-/// Write an exhirom mirror into memory.
-/// https://snes.nesdev.org/wiki/Memory_map#ExHiROM
-/// TODO: https://github.com/HunterKing/RuSuper/issues/28
-///
-/// # Parameters:
-///     - `rom`:        Rom to read data from.
-///     - `mem_ptr`:    Memory to modify.
-/// # Returns:
-///     - `Ok()`:           If written Ok.
-///     - `RomReadError`:   If process failed.
-fn _write_ex_hi_rom_mirror(
-    rom: &Vec<u8>, mem_ptr: &mut memory::Memory,
-) -> Result<(), RomReadError> {
-    return Err(RomReadError::from(String::from(
-        "Unimplemented for ExHiRom",
-    )));
-}
-
 /// Find and grab the header from target rom if available.
 /// # Parameters:
 ///     - `rom`:    Pointer to rom data to analyze.
@@ -680,6 +577,7 @@ fn fetch_header(rom: &Vec<u8>) -> Result<RomData, RomReadError> {
                         opt_header: [0; OPT_HEADER_LEN_BYTES],
                         exception_vectors: [0; EV_LEN_BYTES],
                         opt_is_present: false,
+                        is_headerless: false,
                         mode: newmode,
                     });
                 }
@@ -1106,4 +1004,55 @@ mod tests {
                                                                         // TODO: The ExHiROM variants are as-yet untested, because the ExHiRom functionality is not present.
                                                                         // https://github.com/HunterKing/RuSuper/issues/28
     }
+
+    #[test]
+    fn test_reset_vector() {
+        let mut data = RomData::new();
+
+        // The emulation mode RESET vector lives at $00:FFFC, 28 bytes into the table at $00:FFE0.
+        data.exception_vectors[0x1C] = 0x34;
+        data.exception_vectors[0x1D] = 0x82;
+        assert_eq!(EV_EMU_RESET_INDEX, 0x1C);
+        assert_eq!(data.reset_vector(), 0x8234);
+
+        // Check the rest of the table lines up with the hardware layout.
+        assert_eq!(EV_NATIVE_COP_INDEX, 0x04);
+        assert_eq!(EV_NATIVE_BRK_INDEX, 0x06);
+        assert_eq!(EV_NATIVE_ABORT_INDEX, 0x08);
+        assert_eq!(EV_NATIVE_NMI_INDEX, 0x0A);
+        assert_eq!(EV_NATIVE_IRQ_INDEX, 0x0E);
+        assert_eq!(EV_EMU_COP_INDEX, 0x14);
+        assert_eq!(EV_EMU_ABORT_INDEX, 0x18);
+        assert_eq!(EV_EMU_NMI_INDEX, 0x1A);
+        assert_eq!(EV_EMU_IRQ_BRK_INDEX, 0x1E);
+    }
+
+    #[test]
+    fn test_exception_vector() {
+        let mut table: ExceptionVectorTable = [0; EV_LEN_BYTES];
+        for (index, byte) in table.iter_mut().enumerate() {
+            *byte = index as u8;
+        }
+
+        let test_cases = vec![
+            // vector, emulation, address
+            (ExceptionVector::Cop, false, 0x0504),
+            (ExceptionVector::Brk, false, 0x0706),
+            (ExceptionVector::Abort, false, 0x0908),
+            (ExceptionVector::Nmi, false, 0x0B0A),
+            (ExceptionVector::Irq, false, 0x0F0E),
+            (ExceptionVector::Reset, false, 0x1D1C),
+            (ExceptionVector::Cop, true, 0x1514),
+            (ExceptionVector::Abort, true, 0x1918),
+            (ExceptionVector::Nmi, true, 0x1B1A),
+            (ExceptionVector::Reset, true, 0x1D1C),
+            (ExceptionVector::Irq, true, 0x1F1E),
+            (ExceptionVector::Brk, true, 0x1F1E),
+        ];
+
+        for (vector, emulation, address) in test_cases {
+            println!("Test Case: {:?} emulation: {}", vector, emulation);
+            assert_eq!(exception_vector(&table, vector, emulation), address);
+        }
+    }
 }
@@ -2,6 +2,8 @@ arch 65816
 lorom
 ; Actual ROM Data.
 org $008000
-LDA #$1000
-ADC #$1000
+CLC
+XCE ; Leave emulation mode; registers stay 8-bit.
+LDA #$10
+ADC #$10
 STP